@@ -0,0 +1,339 @@
+// Loading of formatting options from ".yangfmt.toml" files
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use regex::Regex;
+use serde::{Deserialize, Deserializer};
+
+use yangfmt_formatting::{
+    IdentifierQuoting, LineEnding, PathWrapping, Placement, Profile, QuoteStyle, ValuePlacement,
+};
+use yangfmt_linting::{find_rule, Severity, RULE_GROUPS};
+use yangfmt_parsing::ArgumentKind;
+
+const CONFIG_FILE_NAME: &str = ".yangfmt.toml";
+
+/// Options read from a config file
+///
+/// Everything is optional, options given on the command line take precedence.
+///
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ConfigFile {
+    #[serde(deserialize_with = "parse_option")]
+    pub profile: Option<Profile>,
+    pub max_width: Option<u16>,
+    pub tab_width: Option<u8>,
+    pub never_wrap: Option<Vec<String>>,
+    /// Regular expressions matching values that are never wrapped
+    #[serde(deserialize_with = "parse_regexes")]
+    pub never_wrap_patterns: Vec<Regex>,
+    pub verbatim: Option<Vec<String>>,
+    pub single_line_blocks: Option<Vec<String>>,
+    pub single_line_max_children: Option<usize>,
+    pub single_line_max_width: Option<u16>,
+    pub sort_by_name: Option<Vec<String>>,
+    /// Boolean options that profiles turn on, and which can be turned off here
+    pub canonical_order: Option<bool>,
+    pub sort_revisions: Option<bool>,
+    pub separate_definitions: Option<bool>,
+    #[serde(deserialize_with = "parse_option")]
+    pub quote_style: Option<QuoteStyle>,
+    #[serde(deserialize_with = "parse_option")]
+    pub line_ending: Option<LineEnding>,
+    #[serde(deserialize_with = "parse_option")]
+    pub path_wrapping: Option<PathWrapping>,
+    #[serde(deserialize_with = "parse_option")]
+    pub identifier_quoting: Option<IdentifierQuoting>,
+    /// Directories to look for imported modules in, relative to the config file
+    pub search_path: Vec<PathBuf>,
+    /// Options for specific statement keywords, e.g. "[overrides.pattern]"
+    pub overrides: HashMap<String, KeywordSection>,
+    /// Formatting rules for extension keywords, e.g. "[extensions."md:annotation"]"
+    pub extensions: HashMap<String, ExtensionSection>,
+    pub lint: LintSection,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct KeywordSection {
+    pub max_width: Option<u16>,
+    #[serde(deserialize_with = "parse_option")]
+    pub value_placement: Option<ValuePlacement>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ExtensionSection {
+    #[serde(deserialize_with = "parse_option")]
+    pub argument: Option<ArgumentKind>,
+    #[serde(deserialize_with = "parse_option")]
+    pub placement: Option<Placement>,
+    pub single_line: Option<bool>,
+    pub children: Option<bool>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LintSection {
+    /// Severity of lint rules by name, None for rules that are turned off
+    #[serde(deserialize_with = "parse_severities")]
+    pub rules: HashMap<String, Option<Severity>>,
+    pub description_required: Option<Vec<String>>,
+    pub reference_required: Option<Vec<String>>,
+    #[serde(deserialize_with = "parse_regex")]
+    pub naming_pattern: Option<Regex>,
+    #[serde(deserialize_with = "parse_regex")]
+    pub prefix_pattern: Option<Regex>,
+    pub max_prefix_length: Option<usize>,
+    #[serde(deserialize_with = "parse_regex")]
+    pub namespace_pattern: Option<Regex>,
+    /// Groups of optional lint rules to run, e.g. "guidelines"
+    #[serde(deserialize_with = "parse_rule_groups")]
+    pub groups: Vec<String>,
+}
+
+impl ConfigFile {
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let text = std::fs::read_to_string(path)
+            .map_err(|error| format!("Failed to read {}: {}", path.display(), error))?;
+
+        let mut config: Self = text
+            .parse()
+            .map_err(|error| format!("Invalid config file {}: {}", path.display(), error))?;
+
+        if let Some(config_dir) = path.parent() {
+            config.search_path = config
+                .search_path
+                .iter()
+                .map(|dir| config_dir.join(dir))
+                .collect();
+        }
+
+        Ok(config)
+    }
+}
+
+impl FromStr for ConfigFile {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        toml::from_str(text).map_err(|error| error.message().to_string())
+    }
+}
+
+/// Looks for a config file in the given directory and all its parent directories
+pub fn find_config_file(start_dir: &Path) -> Option<PathBuf> {
+    start_dir
+        .ancestors()
+        .map(|dir| dir.join(CONFIG_FILE_NAME))
+        .find(|path| path.is_file())
+}
+
+/// Deserializes an optional string using the target type's FromStr implementation
+fn parse_option<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: FromStr<Err = String>,
+{
+    Option::<String>::deserialize(deserializer)?
+        .map(|text| text.parse().map_err(serde::de::Error::custom))
+        .transpose()
+}
+
+fn parse_regexes<'de, D>(deserializer: D) -> Result<Vec<Regex>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Vec::<String>::deserialize(deserializer)?
+        .iter()
+        .map(|pattern| Regex::new(pattern).map_err(serde::de::Error::custom))
+        .collect()
+}
+
+fn parse_regex<'de, D>(deserializer: D) -> Result<Option<Regex>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Option::<String>::deserialize(deserializer)?
+        .map(|pattern| Regex::new(&pattern).map_err(serde::de::Error::custom))
+        .transpose()
+}
+
+/// Deserializes a list of lint rule group names
+fn parse_rule_groups<'de, D>(deserializer: D) -> Result<Vec<String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Vec::<String>::deserialize(deserializer)?
+        .into_iter()
+        .map(|group| match RULE_GROUPS.contains(&group.as_str()) {
+            true => Ok(group),
+            false => Err(serde::de::Error::custom(format!(
+                "unknown lint rule group {group:?}"
+            ))),
+        })
+        .collect()
+}
+
+/// Deserializes a table of lint rule names and severities, where "off" disables the rule
+fn parse_severities<'de, D>(deserializer: D) -> Result<HashMap<String, Option<Severity>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    HashMap::<String, String>::deserialize(deserializer)?
+        .into_iter()
+        .map(|(rule, severity)| {
+            if find_rule(&rule).is_none() {
+                return Err(serde::de::Error::custom(format!(
+                    "unknown lint rule {rule:?}"
+                )));
+            }
+
+            match severity.as_str() {
+                "off" => Ok((rule, None)),
+                _ => match severity.parse() {
+                    Ok(severity) => Ok((rule, Some(severity))),
+                    Err(error) => Err(serde::de::Error::custom(format!("{error} or off"))),
+                },
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_config_file() {
+        let config: ConfigFile = r#"
+            profile = "ietf"
+            max_width = 100
+            never_wrap = []
+            never_wrap_patterns = ["^https?://"]
+            verbatim = ["contact"]
+            single_line_blocks = ["bit", "import"]
+            single_line_max_children = 2
+            sort_by_name = ["identity", "feature"]
+            canonical_order = false
+            quote_style = "single"
+            line_ending = "crlf"
+            path_wrapping = "slash"
+            identifier_quoting = "bare"
+            search_path = ["modules", "/usr/share/yang"]
+
+            [overrides.pattern]
+            max_width = 200
+
+            [overrides.description]
+            value_placement = "always-newline"
+
+            [extensions."md:annotation"]
+            argument = "identifier"
+            placement = "after:description"
+            children = false
+
+            [lint]
+            reference_required = ["module", "typedef"]
+            naming_pattern = "^[a-z][a-z0-9_]*$"
+            max_prefix_length = 6
+            namespace_pattern = "^urn:example:params:xml:ns:yang:"
+            groups = ["guidelines"]
+
+            [lint.rules]
+            todo-comment = "off"
+            empty-block = "error"
+        "#
+        .parse()
+        .unwrap();
+
+        assert_eq!(Some(Profile::Ietf), config.profile);
+        assert_eq!(Some(100), config.max_width);
+        assert_eq!(None, config.tab_width);
+        assert_eq!(Some(vec![]), config.never_wrap);
+        assert_eq!(Some(vec!["contact".to_string()]), config.verbatim);
+        assert_eq!(
+            Some(vec!["bit".to_string(), "import".to_string()]),
+            config.single_line_blocks
+        );
+        assert_eq!(Some(2), config.single_line_max_children);
+        assert_eq!(None, config.single_line_max_width);
+        assert_eq!(
+            Some(vec!["identity".to_string(), "feature".to_string()]),
+            config.sort_by_name
+        );
+        assert_eq!(Some(false), config.canonical_order);
+        assert_eq!(None, config.sort_revisions);
+        assert_eq!(Some(QuoteStyle::Single), config.quote_style);
+        assert_eq!(Some(LineEnding::Crlf), config.line_ending);
+        assert_eq!(Some(PathWrapping::Slash), config.path_wrapping);
+        assert_eq!(Some(IdentifierQuoting::Bare), config.identifier_quoting);
+        assert_eq!(
+            vec![PathBuf::from("modules"), PathBuf::from("/usr/share/yang")],
+            config.search_path
+        );
+        assert!(config.never_wrap_patterns[0].is_match("https://example.com"));
+        assert_eq!(Some(200), config.overrides["pattern"].max_width);
+        assert_eq!(None, config.overrides["pattern"].value_placement);
+        assert_eq!(
+            Some(ValuePlacement::AlwaysNewline),
+            config.overrides["description"].value_placement
+        );
+
+        let annotation = &config.extensions["md:annotation"];
+        assert_eq!(Some(ArgumentKind::Identifier), annotation.argument);
+        assert_eq!(
+            Some(Placement::After("description".to_string())),
+            annotation.placement
+        );
+        assert_eq!(None, annotation.single_line);
+        assert_eq!(Some(false), annotation.children);
+
+        assert_eq!(None, config.lint.rules["todo-comment"]);
+        assert_eq!(Some(Severity::Error), config.lint.rules["empty-block"]);
+        assert!(!config.lint.rules.contains_key("unquoted-text"));
+        assert_eq!(None, config.lint.description_required);
+        assert_eq!(
+            Some(vec!["module".to_string(), "typedef".to_string()]),
+            config.lint.reference_required
+        );
+        assert!(config.lint.naming_pattern.unwrap().is_match("rx_packets"));
+        assert!(config.lint.prefix_pattern.is_none());
+        assert_eq!(Some(6), config.lint.max_prefix_length);
+        assert!(config
+            .lint
+            .namespace_pattern
+            .unwrap()
+            .is_match("urn:example:params:xml:ns:yang:foo"));
+        assert_eq!(vec!["guidelines".to_string()], config.lint.groups);
+    }
+
+    #[test]
+    fn test_parse_invalid_config_file() {
+        assert!("max_widht = 100".parse::<ConfigFile>().is_err());
+        assert!("never_wrap_patterns = [\"(\"]"
+            .parse::<ConfigFile>()
+            .is_err());
+        assert!("[overrides.leaf]\nvalue_placement = \"sometimes\""
+            .parse::<ConfigFile>()
+            .is_err());
+        assert!("[extensions.\"md:annotation\"]\nargument = \"name\""
+            .parse::<ConfigFile>()
+            .is_err());
+        assert!("[lint]\nnaming_pattern = \"[\""
+            .parse::<ConfigFile>()
+            .is_err());
+        assert!("[lint]\ngroups = [\"no-such-group\"]"
+            .parse::<ConfigFile>()
+            .is_err());
+        assert!("[lint.rules]\nno-such-rule = \"off\""
+            .parse::<ConfigFile>()
+            .is_err());
+        assert!("[lint.rules]\nempty-block = \"fatal\""
+            .parse::<ConfigFile>()
+            .is_err());
+    }
+}
@@ -1,26 +1,334 @@
+mod config_file;
+
+use std::collections::HashMap;
 use std::io::{stdin, stdout, Read, Write};
+use std::path::{Path, PathBuf};
 
-use clap::Parser;
+use clap::builder::PossibleValuesParser;
+use clap::{Parser, Subcommand};
+use regex::Regex;
 
-use yangfmt_formatting::{format_yang, Error as FormattingError, FormatConfig, Indent};
+use yangfmt_formatting::{
+    apply_pragmas, explanation, extension_rule, find_canonical_order_violations, find_long_lines,
+    format_yang, has_mixed_line_endings, has_skip_file_pragma, ClosingQuotePlacement,
+    ConcatAlignment, ConcatOperatorStyle, EnumSorting, Error as FormattingError, ExtensionRule,
+    ExtensionRules, FormatConfig, IdentifierQuoting, Indent, KeywordOverrides, LineEnding,
+    NonPrintableCharacters, PathWrapping, Placement, Profile, QuoteStyle, SemicolonPlacement,
+    Style, ValuePlacement, WrappedValueIndent, DEFAULT_BLANK_LINE_BEFORE, DEFAULT_NEVER_WRAP,
+    DEFAULT_VERBATIM, EXPLANATIONS, LOSSY_CHANGE_CODES,
+};
 use yangfmt_lexing::DebugTokenExt;
+use yangfmt_linting::{
+    apply_fixes, check_strict, find_rule, lint, Finding, LintConfig, DEFAULT_DESCRIPTION_REQUIRED,
+    DEFAULT_MAX_PREFIX_LENGTH, DEFAULT_NAMING_PATTERN, DEFAULT_PREFIX_PATTERN, RULES, RULE_GROUPS,
+};
+use yangfmt_modules::{Graph, GraphFormat, Module, Registry};
+use yangfmt_parsing::{ArgumentKind, Diagnostic};
+
+use crate::config_file::{find_config_file, ConfigFile};
 
 /// YANG auto-formatter, inspired by the consistent style of IETF YANG models
 #[derive(Parser, Debug)]
 #[command(author, version, about)]
 struct Args {
-    /// Will try to wrap at this column
-    #[arg(short, long, default_value_t = 79)]
-    max_width: u16,
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    /// Preset of options to start from: default, ietf, rfc or openconfig [default: default]
+    #[arg(long)]
+    profile: Option<Profile>,
+
+    /// Will try to wrap at this column [default: 79, depends on the profile]
+    #[arg(short, long)]
+    max_width: Option<u16>,
+
+    /// Output conventions to follow: yangfmt or pyang (mimics "pyang -f yang" to minimize diffs
+    /// when switching tools)
+    #[arg(long, default_value = "yangfmt")]
+    style: Style,
+
+    /// Fold lines that still don't fit within the max width after formatting, as described in
+    /// RFC 8792, to paste the module into a document (the output isn't valid YANG anymore).
+    /// Enabled by the rfc profile, but never with "--check" or "--in-place"
+    #[arg(
+        long,
+        default_value_t = false,
+        conflicts_with_all = ["check", "in_place"],
+        overrides_with = "no_fold_long_lines"
+    )]
+    fold_long_lines: bool,
+
+    /// Don't fold long lines, even if the profile does
+    #[arg(long, default_value_t = false, overrides_with = "fold_long_lines")]
+    no_fold_long_lines: bool,
+
+    /// Number of spaces used for indentation [default: 2]
+    #[arg(short, long)]
+    tab_width: Option<u8>,
+
+    /// Number of spaces used when a value is pushed to the next line, and before the continuation
+    /// lines of string concatenations with "--concat-alignment continuation" [default: tab width]
+    #[arg(long)]
+    continuation_indent: Option<u8>,
 
-    /// Number of spaces used for indentation
-    #[arg(short, long, default_value_t = 2)]
-    tab_width: u8,
+    /// Where values moved to the next line start: indent (one continuation indent deeper),
+    /// aligned (under the value position on the keyword line) or a fixed column number
+    #[arg(long, default_value = "indent")]
+    wrapped_value_indent: WrappedValueIndent,
 
     /// Sort statements to match canonical order
-    #[arg(short, long, default_value_t = false)]
+    #[arg(
+        short,
+        long,
+        default_value_t = false,
+        overrides_with = "no_canonical_order"
+    )]
     canonical_order: bool,
 
+    /// Don't sort statements to match canonical order, even if the profile does
+    #[arg(long, default_value_t = false, overrides_with = "canonical_order")]
+    no_canonical_order: bool,
+
+    /// Also sort statement lists that have comments or empty lines between the statements, which
+    /// are otherwise only reported as needing manual ordering. Applies to all sorting, whether
+    /// enabled by flags, the profile or the config file
+    #[arg(long, default_value_t = false)]
+    force_sort: bool,
+
+    /// Order revision statements newest first, as RFC 7950 requires
+    #[arg(long, default_value_t = false, overrides_with = "no_sort_revisions")]
+    sort_revisions: bool,
+
+    /// Don't order revision statements, even if the profile does
+    #[arg(long, default_value_t = false, overrides_with = "sort_revisions")]
+    no_sort_revisions: bool,
+
+    /// Order the enums of enumeration types: none, value or name (only if every enum has a value,
+    /// since moving the others changes their value)
+    #[arg(long, default_value = "none")]
+    sort_enums: EnumSorting,
+
+    /// Sort the definitions with these keywords by name, e.g. "identity,feature,typedef,grouping"
+    /// (comma separated)
+    #[arg(long, value_delimiter = ',', value_name = "KEYWORDS")]
+    sort_by_name: Option<Vec<String>>,
+
+    /// Move status, description and reference statements after the other meta statements of every
+    /// block, before any definitions and data nodes, a lightweight alternative to
+    /// "--canonical-order"
+    #[arg(long, default_value_t = false, conflicts_with = "canonical_order")]
+    documentation_last: bool,
+
+    /// Report statements that aren't in canonical order instead of sorting them, fails "--check"
+    /// if there are any
+    #[arg(long, default_value_t = false, conflicts_with = "canonical_order")]
+    check_canonical_order: bool,
+
+    /// Render empty blocks as "{}" on one line
+    #[arg(long, default_value_t = false)]
+    collapse_empty_blocks: bool,
+
+    /// Guarantee a blank line between consecutive top-level definition blocks
+    #[arg(
+        long,
+        default_value_t = false,
+        overrides_with = "no_separate_definitions"
+    )]
+    separate_definitions: bool,
+
+    /// Don't insert blank lines between top-level definition blocks, even if the profile does
+    #[arg(long, default_value_t = false, overrides_with = "separate_definitions")]
+    no_separate_definitions: bool,
+
+    /// Write blocks of these keywords on one line when they fit, e.g. "bit up { position 0; }"
+    /// (comma separated)
+    #[arg(long, value_delimiter = ',', value_name = "KEYWORDS")]
+    single_line_blocks: Option<Vec<String>>,
+
+    /// Maximum number of child statements of blocks written on one line [default: 1]
+    #[arg(long, value_name = "N")]
+    single_line_max_children: Option<usize>,
+
+    /// Maximum width of blocks written on one line, not counting the indentation
+    #[arg(long, value_name = "N")]
+    single_line_max_width: Option<u16>,
+
+    /// Always insert a blank line before these keywords (comma separated, leave empty for
+    /// grouping, container, list, rpc, notification and augment)
+    #[arg(long, value_delimiter = ',', num_args = 0.., require_equals = true)]
+    blank_line_before: Option<Vec<String>>,
+
+    /// Remove blank lines inside blocks with fewer than this many child statements
+    #[arg(long, value_name = "N")]
+    compact_blocks: Option<usize>,
+
+    /// Don't end the output with a line break
+    #[arg(long, default_value_t = false)]
+    no_final_newline: bool,
+
+    /// Keep trailing whitespace on the lines of multi-line strings and comments
+    #[arg(long, default_value_t = false)]
+    keep_trailing_whitespace: bool,
+
+    /// Whether values are placed on the keyword line: auto, always-newline or never-newline
+    #[arg(long, default_value = "auto")]
+    value_placement: ValuePlacement,
+
+    /// Value placement for a specific keyword, e.g. "description=always-newline" (repeatable)
+    #[arg(long, value_name = "KEYWORD=PLACEMENT", value_parser = parse_keyword_value_placement)]
+    keyword_value_placement: Vec<(String, ValuePlacement)>,
+
+    /// Where to put the semicolon after a wrapped value: attached, next-line or under-keyword
+    #[arg(long, default_value = "attached")]
+    semicolon_placement: SemicolonPlacement,
+
+    /// Where to put the closing quote of multi-line strings: attached or own-line
+    #[arg(long, default_value = "attached")]
+    closing_quote: ClosingQuotePlacement,
+
+    /// Where to put the "+" in string concatenations: leading or trailing
+    #[arg(long, default_value = "leading")]
+    concat_operator: ConcatOperatorStyle,
+
+    /// How to align string concatenation continuation lines: quote, continuation or keyword
+    #[arg(long, default_value = "quote")]
+    concat_alignment: ConcatAlignment,
+
+    /// Re-wrap the text of description, reference and contact statements to fit the max width
+    #[arg(long, default_value_t = false)]
+    reflow_text: bool,
+
+    /// Re-wrap paragraphs of consecutive "//" comment lines to fit the max width
+    #[arg(long, default_value_t = false)]
+    reflow_comments: bool,
+
+    /// Convert single-line block comments like "/* note */" to line comments
+    #[arg(long, default_value_t = false)]
+    prefer_line_comments: bool,
+
+    /// Put a space after "//" and "/*" and before "*/" in comments
+    #[arg(long, default_value_t = false)]
+    space_comment_markers: bool,
+
+    /// Never change the contents of strings, including whitespace and indentation
+    #[arg(long, default_value_t = false)]
+    preserve_strings: bool,
+
+    /// Keep the internal indentation of multi-line strings as written
+    #[arg(long, default_value_t = false)]
+    preserve_string_indent: bool,
+
+    /// Split strings that can't fit within the max width into string concatenations
+    #[arg(long, default_value_t = false)]
+    split_long_strings: bool,
+
+    /// Join string concatenations that fit on one line into a single string
+    #[arg(long, default_value_t = false)]
+    merge_short_concatenations: bool,
+
+    /// Line up the trailing comments of consecutive single-line statements
+    #[arg(long, default_value_t = false)]
+    align_trailing_comments: bool,
+
+    /// Line up the values of consecutive single-line statements in a column
+    #[arg(long, default_value_t = false)]
+    align_values: bool,
+
+    /// Write enums that only have a value on one line, lined up like a table
+    #[arg(long, default_value_t = false)]
+    align_enum_values: bool,
+
+    /// Never wrap or rewrite the values of these keywords, regardless of the max width (comma
+    /// separated, defaults to pattern)
+    #[arg(long, value_delimiter = ',', num_args = 0.., require_equals = true)]
+    never_wrap: Option<Vec<String>>,
+
+    /// Never wrap or split values matching this regular expression, e.g. "^https?://" (repeatable)
+    #[arg(long, value_name = "REGEX", value_parser = Regex::new)]
+    never_wrap_pattern: Vec<Regex>,
+
+    /// Keep the line layout of these keywords' strings exactly as written (comma separated,
+    /// defaults to contact,organization)
+    #[arg(long, value_delimiter = ',', num_args = 0.., require_equals = true)]
+    verbatim: Option<Vec<String>>,
+
+    /// Split augment, deviation and refine paths that can't fit on one line at "/"
+    #[arg(long, default_value_t = false)]
+    split_long_paths: bool,
+
+    /// Wrap long "when" and "must" expressions at logical operators
+    #[arg(long, default_value_t = false)]
+    wrap_xpath: bool,
+
+    /// Which quotes to use for strings: double, single or preserve [default: double]
+    #[arg(long)]
+    quote_style: Option<QuoteStyle>,
+
+    /// Put quotes around unquoted string arguments, such as enum names, key lists and defaults
+    #[arg(long, default_value_t = false)]
+    quote_string_arguments: bool,
+
+    /// Remove the quotes around identifiers, numbers and dates where YANG allows it
+    #[arg(
+        long,
+        default_value_t = false,
+        conflicts_with = "quote_string_arguments"
+    )]
+    unquote_simple_arguments: bool,
+
+    /// What to do about non-printable characters in strings: warn, replace or ignore
+    #[arg(long, default_value = "warn")]
+    non_printable: NonPrintableCharacters,
+
+    /// Remove plus signs and superfluous zeros from numeric arguments
+    #[arg(long, default_value_t = false)]
+    normalize_numbers: bool,
+
+    /// How long leafref paths are wrapped: auto, slash or never [default: auto]
+    #[arg(long)]
+    path_wrapping: Option<PathWrapping>,
+
+    /// Quoting of prefix, belongs-to, base, type and uses arguments: preserve, quoted or bare
+    /// [default: preserve]
+    #[arg(long)]
+    identifier_quoting: Option<IdentifierQuoting>,
+
+    /// Read options from this config file instead of looking for the nearest .yangfmt.toml
+    #[arg(long, value_name = "PATH", global = true)]
+    config: Option<PathBuf>,
+
+    /// Directories to look for imported modules in, e.g. "modules:vendor/ietf" (colon separated,
+    /// searched before the directories of the config file)
+    #[arg(long, value_delimiter = ':', value_name = "DIRS", global = true)]
+    path: Vec<PathBuf>,
+
+    /// Line breaks to write: auto (most common in the input), lf or crlf [default: auto]
+    #[arg(long)]
+    line_ending: Option<LineEnding>,
+
+    /// Print notes about problems in the input that were fixed, such as mixed line endings, and
+    /// about what the formatter removed or moved, such as blank lines removed by sorting
+    #[arg(short, long, default_value_t = false)]
+    verbose: bool,
+
+    /// Check if the input is already formatted rather than print it, exits with status 1 if not
+    #[arg(long, default_value_t = false, conflicts_with = "in_place")]
+    check: bool,
+
+    /// Check that every statement keyword is valid, has a declared prefix if it's an extension and
+    /// is allowed where it is per RFC 7950, and fail without formatting if not
+    #[arg(long, default_value_t = false)]
+    strict: bool,
+
+    /// Also run the lint rules and report what they find, fails "--check" if they find anything
+    #[arg(long, default_value_t = false)]
+    lint: bool,
+
+    /// Also format the submodules the module includes, found in the search path and the directory
+    /// of the file (use with "--check" or "--in-place")
+    #[arg(long, default_value_t = false, requires("file_path"))]
+    with_submodules: bool,
+
     /// Format the file in-place rather than print to STDOUT (use with caution!)
     #[arg(short, long, default_value_t = false, requires("file_path"))]
     in_place: bool,
@@ -37,33 +345,96 @@ struct Args {
     file_path: Option<String>,
 }
 
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Report problems that formatting can't fix, exits with status 1 if there are any
+    Lint {
+        /// Path of the file to lint (leave empty or use "-" for STDIN)
+        file_path: Option<String>,
+
+        /// Fix the findings that can be fixed automatically, modifying the file in place
+        #[arg(long, default_value_t = false)]
+        fix: bool,
+
+        /// Also run the rules of this group, e.g. "guidelines" for the RFC 8407 guidelines (can
+        /// be repeated)
+        #[arg(long = "group", value_name = "GROUP", value_parser = PossibleValuesParser::new(RULE_GROUPS))]
+        groups: Vec<String>,
+    },
+
+    /// Print the modules a module imports and includes, directly or through other modules, found
+    /// in the search path and the directory of the file
+    Deps {
+        /// Path of the module file
+        file_path: String,
+
+        /// Output format: tree, json (with an order the modules can be compiled in) or dot
+        #[arg(long, default_value = "tree")]
+        format: GraphFormat,
+    },
+
+    /// Describe a lint rule or diagnostic code, with an example, or list them all
+    Explain {
+        /// The name shown in brackets after the message, e.g. "empty-block"
+        name: Option<String>,
+    },
+}
+
 fn main() {
     let args = Args::parse();
 
-    let config = FormatConfig {
-        indent: Indent::Spaces(args.tab_width),
-        line_length: args.max_width,
-        fix_canonical_order: args.canonical_order,
-    };
+    if let Some(Command::Explain { ref name }) = args.command {
+        explain(name.as_deref());
+        return;
+    }
+
+    if let Some(Command::Deps {
+        ref file_path,
+        format,
+    }) = args.command
+    {
+        let config_file = load_config_file(args.config.as_deref(), Some(file_path));
+
+        print_dependencies(file_path, &config_file, &args.path, format);
+        return;
+    }
+
+    if let Some(Command::Lint {
+        ref file_path,
+        fix,
+        ref groups,
+    }) = args.command
+    {
+        let config_file = load_config_file(args.config.as_deref(), file_path.as_deref());
+        let mut config = lint_config(&config_file, &args.path);
+
+        config.groups.extend(groups.iter().cloned());
+
+        if fix && file_path.as_deref().unwrap_or("-") == "-" {
+            exit_with_error("Can't fix STDIN in place");
+        }
+
+        run_lint(file_path.as_deref(), &config, fix);
+        return;
+    }
+
+    let config_file = load_config_file(args.config.as_deref(), args.file_path.as_deref());
+
+    let config = format_config(&args, &config_file);
 
     let mut buffer: Vec<u8> = vec![];
 
     // Check that "-i" and file path "-" isn't provided at the same time
-    if args.file_path.as_ref().map_or(false, |path| path == "-") && args.in_place {
+    if args.file_path.as_ref().is_some_and(|path| path == "-") && args.in_place {
         exit_with_error("Can't modify STDIN in place");
     }
 
-    match args.file_path {
-        Some(ref file_path) => {
-            if file_path == "-" {
-                read_stdin(&mut buffer)
-            } else {
-                read_file(&mut buffer, file_path)
-            }
-        }
-        None => read_stdin(&mut buffer),
+    if args.with_submodules && !(args.check || args.in_place) {
+        exit_with_error("\"--with-submodules\" needs \"--check\" or \"--in-place\"");
     }
 
+    read_input(&mut buffer, args.file_path.as_deref());
+
     let mut stdout = stdout().lock();
 
     if args.lex {
@@ -91,13 +462,248 @@ fn main() {
         return;
     }
 
-    if args.in_place {
-        let file_path = args.file_path.as_ref().unwrap();
-        let mut output_buffer: Vec<u8> = vec![];
+    let mut failed = format_file(
+        &args,
+        &config_file,
+        config.clone(),
+        args.file_path.as_deref(),
+        &buffer,
+    );
+
+    if args.with_submodules {
+        let file_path = args.file_path.as_deref().unwrap();
+
+        for path in find_submodules(file_path, &buffer, &config_file, &args.path) {
+            let path = path.to_string_lossy();
+            let mut buffer: Vec<u8> = vec![];
+
+            read_file(&mut buffer, &path);
+
+            failed |= format_file(&args, &config_file, config.clone(), Some(&path), &buffer);
+        }
+    }
+
+    if failed {
+        std::process::exit(1);
+    }
+}
+
+/// Builds the formatting options from the command line arguments, the config file and the profile
+///
+/// Options given on the command line take precedence over the config file, which takes precedence
+/// over the profile.
+///
+fn format_config(args: &Args, config_file: &ConfigFile) -> FormatConfig {
+    let profile = args
+        .profile
+        .or(config_file.profile)
+        .unwrap_or(Profile::Default)
+        .config();
+
+    FormatConfig {
+        indent: Indent::Spaces(args.tab_width.or(config_file.tab_width).unwrap_or(2)),
+        continuation_indent: args.continuation_indent,
+        wrapped_value_indent: args.wrapped_value_indent,
+        line_length: args
+            .max_width
+            .or(config_file.max_width)
+            .unwrap_or(profile.line_length),
+        fold_long_lines: !args.check
+            && !args.in_place
+            && switch(args.fold_long_lines, args.no_fold_long_lines)
+                .unwrap_or(profile.fold_long_lines),
+        style: args.style,
+        extensions: build_extension_rules(config_file),
+        fix_canonical_order: !args.check_canonical_order
+            && switch(args.canonical_order, args.no_canonical_order)
+                .or(config_file.canonical_order)
+                .unwrap_or(profile.fix_canonical_order),
+        force_sort: args.force_sort,
+        sort_revisions: switch(args.sort_revisions, args.no_sort_revisions)
+            .or(config_file.sort_revisions)
+            .unwrap_or(profile.sort_revisions),
+        enum_sorting: args.sort_enums,
+        documentation_last: args.documentation_last,
+        sort_by_name: keyword_list(
+            args.sort_by_name
+                .as_ref()
+                .or(config_file.sort_by_name.as_ref()),
+            &[],
+        ),
+        collapse_empty_blocks: args.collapse_empty_blocks,
+        separate_definitions: switch(args.separate_definitions, args.no_separate_definitions)
+            .or(config_file.separate_definitions)
+            .unwrap_or(profile.separate_definitions),
+        blank_line_before: match args.blank_line_before {
+            Some(ref keywords) if keywords.is_empty() => DEFAULT_BLANK_LINE_BEFORE
+                .iter()
+                .map(|keyword| keyword.to_string())
+                .collect(),
+            Some(ref keywords) => keywords.clone(),
+            None => profile.blank_line_before.clone(),
+        },
+        compact_blocks: args.compact_blocks,
+        single_line_blocks: keyword_list(
+            args.single_line_blocks
+                .as_ref()
+                .or(config_file.single_line_blocks.as_ref()),
+            &[],
+        ),
+        single_line_max_children: args
+            .single_line_max_children
+            .or(config_file.single_line_max_children)
+            .unwrap_or(1),
+        single_line_max_width: args
+            .single_line_max_width
+            .or(config_file.single_line_max_width),
+        final_newline: !args.no_final_newline,
+        strip_trailing_whitespace: !args.keep_trailing_whitespace,
+        line_ending: args
+            .line_ending
+            .or(config_file.line_ending)
+            .unwrap_or(LineEnding::Auto),
+        value_placement: args.value_placement,
+        keyword_overrides: build_keyword_overrides(args, config_file),
+        semicolon_placement: args.semicolon_placement,
+        closing_quote: args.closing_quote,
+        concat_operator: args.concat_operator,
+        concat_alignment: args.concat_alignment,
+        reflow_text: args.reflow_text,
+        reflow_comments: args.reflow_comments,
+        prefer_line_comments: args.prefer_line_comments,
+        space_comment_markers: args.space_comment_markers,
+        preserve_strings: args.preserve_strings,
+        preserve_string_indent: args.preserve_string_indent,
+        split_long_strings: args.split_long_strings,
+        split_long_paths: args.split_long_paths,
+        merge_short_concatenations: args.merge_short_concatenations,
+        never_wrap: keyword_list(
+            args.never_wrap.as_ref().or(config_file.never_wrap.as_ref()),
+            DEFAULT_NEVER_WRAP,
+        ),
+        never_wrap_patterns: config_file
+            .never_wrap_patterns
+            .iter()
+            .chain(&args.never_wrap_pattern)
+            .cloned()
+            .collect(),
+        verbatim: keyword_list(
+            args.verbatim.as_ref().or(config_file.verbatim.as_ref()),
+            DEFAULT_VERBATIM,
+        ),
+        wrap_xpath: args.wrap_xpath,
+        quote_style: args
+            .quote_style
+            .or(config_file.quote_style)
+            .unwrap_or(profile.quote_style),
+        quote_string_arguments: args.quote_string_arguments,
+        unquote_simple_arguments: args.unquote_simple_arguments,
+        non_printable: args.non_printable,
+        normalize_numbers: args.normalize_numbers,
+        path_wrapping: args
+            .path_wrapping
+            .or(config_file.path_wrapping)
+            .unwrap_or(PathWrapping::Auto),
+        identifier_quoting: args
+            .identifier_quoting
+            .or(config_file.identifier_quoting)
+            .unwrap_or(IdentifierQuoting::Preserve),
+        align_trailing_comments: args.align_trailing_comments,
+        align_values: args.align_values,
+        align_enum_values: args.align_enum_values,
+        report_lossy_changes: args.verbose,
+    }
+}
+
+/// Returns the value of an option with a "--no-" flag, or None if neither flag was given
+fn switch(on: bool, off: bool) -> Option<bool> {
+    match (on, off) {
+        (true, _) => Some(true),
+        (_, true) => Some(false),
+        _ => None,
+    }
+}
+
+/// Formats a file, or checks if it's formatted with "--check", along with the other checks asked
+/// for on the command line
+///
+/// Returns true if "--check" fails.
+///
+fn format_file(
+    args: &Args,
+    config_file: &ConfigFile,
+    mut config: FormatConfig,
+    file_path: Option<&str>,
+    buffer: &[u8],
+) -> bool {
+    if let Err(error) = apply_pragmas(buffer, &mut config) {
+        exit_with_error(error);
+    }
+
+    if args.verbose && !has_skip_file_pragma(buffer) && has_mixed_line_endings(buffer) {
+        let line_ending = match config.line_ending.resolve(buffer) {
+            LineEnding::Crlf => "CRLF",
+            _ => "LF",
+        };
+
+        eprintln!("Note: Mixed line endings in the input, normalized to {line_ending}");
+    }
+
+    let name = input_name(file_path);
+
+    if args.strict && !has_skip_file_pragma(buffer) {
+        let errors = check_strict(buffer).unwrap_or_else(|error| {
+            handle_formatting_error(FormattingError::ParseError(error), buffer)
+        });
 
-        if let Err(error) = format_yang(&mut output_buffer, &buffer, &config) {
-            handle_formatting_error(error, &buffer);
+        if !errors.is_empty() {
+            report_findings(name, &errors, buffer);
+            std::process::exit(1);
         }
+    }
+
+    let order_violations = if args.check_canonical_order && !has_skip_file_pragma(buffer) {
+        find_canonical_order_violations(buffer, &config.extensions)
+            .unwrap_or_else(|error| handle_formatting_error(error, buffer))
+    } else {
+        vec![]
+    };
+
+    let file_name = file_path.filter(|path| *path != "-");
+
+    let findings = if args.lint && !has_skip_file_pragma(buffer) {
+        lint(buffer, file_name, &lint_config(config_file, &args.path)).unwrap_or_else(|error| {
+            handle_formatting_error(FormattingError::ParseError(error), buffer)
+        })
+    } else {
+        vec![]
+    };
+
+    if args.check {
+        if has_skip_file_pragma(buffer) {
+            eprintln!("{name}: skipped");
+            return false;
+        }
+
+        report_diagnostics(name, &order_violations, buffer);
+        report_findings(name, &findings, buffer);
+
+        let output_buffer = format_input(name, buffer, &config);
+
+        if output_buffer != buffer {
+            eprintln!("{name}: not formatted");
+            return true;
+        }
+
+        return !order_violations.is_empty() || !findings.is_empty();
+    }
+
+    report_diagnostics(name, &order_violations, buffer);
+    report_findings(name, &findings, buffer);
+
+    if args.in_place {
+        let file_path = file_path.unwrap();
+        let output_buffer = format_input(name, buffer, &config);
 
         if let Err(error) = std::fs::write(file_path, output_buffer) {
             exit_with_error(error);
@@ -105,13 +711,364 @@ fn main() {
     }
 
     if !args.in_place {
-        if let Err(error) = format_yang(&mut stdout, &buffer, &config) {
-            handle_formatting_error(error, &buffer);
+        let output_buffer = format_input(name, buffer, &config);
+
+        if let Err(error) = stdout().lock().write_all(&output_buffer) {
+            exit_with_error(error);
+        }
+    }
+
+    false
+}
+
+/// Formats the input, reporting the diagnostics and the lines of the output that are still too
+/// long
+fn format_input(name: &str, buffer: &[u8], config: &FormatConfig) -> Vec<u8> {
+    let mut output_buffer: Vec<u8> = vec![];
+
+    match format_yang(&mut output_buffer, buffer, config) {
+        Ok(diagnostics) => report_diagnostics(name, &diagnostics, buffer),
+        Err(error) => handle_formatting_error(error, buffer),
+    }
+
+    if !has_skip_file_pragma(buffer) {
+        // Positions of long lines are in the output, which can differ from the input
+        report_diagnostics(
+            name,
+            &find_long_lines(&output_buffer, config),
+            &output_buffer,
+        );
+    }
+
+    output_buffer
+}
+
+/// Runs the lint rules on the input and reports what they find, exits with status 1 if they find
+/// anything
+///
+/// With "fix", the findings that can be fixed are fixed in the file and only the rest are reported.
+///
+fn run_lint(file_path: Option<&str>, config: &LintConfig, fix: bool) {
+    let mut buffer: Vec<u8> = vec![];
+
+    read_input(&mut buffer, file_path);
+
+    let name = input_name(file_path);
+
+    if has_skip_file_pragma(&buffer) {
+        eprintln!("{name}: skipped");
+        return;
+    }
+
+    let file_name = file_path.filter(|path| *path != "-");
+    let mut findings = lint(&buffer, file_name, config).unwrap_or_else(|error| {
+        handle_formatting_error(FormattingError::ParseError(error), &buffer)
+    });
+
+    if fix {
+        let finding_count = findings.len();
+        let (fixed_buffer, unfixed) = apply_fixes(&buffer, findings);
+
+        if unfixed.len() < finding_count {
+            if let Err(error) = std::fs::write(file_path.unwrap(), &fixed_buffer) {
+                exit_with_error(error);
+            }
+
+            eprintln!("{name}: fixed {} problem(s)", finding_count - unfixed.len());
         }
+
+        // Lint again, since fixing moves the remaining findings and can reveal new ones
+        buffer = fixed_buffer;
+        findings = lint(&buffer, file_name, config).unwrap_or_else(|error| {
+            handle_formatting_error(FormattingError::ParseError(error), &buffer)
+        });
+    }
+
+    report_findings(name, &findings, &buffer);
+
+    if !findings.is_empty() {
+        std::process::exit(1);
     }
 }
 
-fn handle_formatting_error(error: FormattingError, buffer: &[u8]) {
+/// Prints the graph of the modules the module depends on
+fn print_dependencies(
+    file_path: &str,
+    config_file: &ConfigFile,
+    search_path: &[PathBuf],
+    format: GraphFormat,
+) {
+    let mut buffer: Vec<u8> = vec![];
+
+    read_file(&mut buffer, file_path);
+
+    let module = Module::new(file_path, &buffer).unwrap_or_else(|error| {
+        handle_formatting_error(FormattingError::ParseError(error), &buffer)
+    });
+
+    let registry = module_registry(file_path, config_file, search_path);
+
+    print!("{}", Graph::build(&module, &registry).render(format));
+}
+
+/// Returns the paths of the submodules the module includes, warns about the ones not found
+fn find_submodules(
+    file_path: &str,
+    buffer: &[u8],
+    config_file: &ConfigFile,
+    search_path: &[PathBuf],
+) -> Vec<PathBuf> {
+    let module = Module::new(file_path, buffer).unwrap_or_else(|error| {
+        handle_formatting_error(FormattingError::ParseError(error), buffer)
+    });
+
+    let registry = module_registry(file_path, config_file, search_path);
+
+    module
+        .dependencies()
+        .iter()
+        .filter(|dependency| dependency.keyword == "include")
+        .filter_map(
+            |dependency| match registry.find(dependency.module, dependency.revision) {
+                Some(path) => Some(path.to_path_buf()),
+                None => {
+                    eprintln!(
+                        "{file_path}: warning: Submodule \"{}\" isn't found in the search path",
+                        dependency.module
+                    );
+                    None
+                }
+            },
+        )
+        .collect()
+}
+
+/// Returns the registry of the modules in the search path of the command line, then the one of
+/// the config file, and then the directory of the file
+fn module_registry(file_path: &str, config_file: &ConfigFile, search_path: &[PathBuf]) -> Registry {
+    let file_dir = Path::new(file_path)
+        .parent()
+        .map(|dir| match dir.as_os_str().is_empty() {
+            true => PathBuf::from("."),
+            false => dir.to_path_buf(),
+        });
+
+    let search_path: Vec<PathBuf> = search_path
+        .iter()
+        .chain(&config_file.search_path)
+        .chain(&file_dir)
+        .cloned()
+        .collect();
+
+    Registry::new(&search_path).unwrap_or_else(|error| exit_with_error(error))
+}
+
+/// Prints the documentation of a lint rule or diagnostic code, or a list of them all
+fn explain(name: Option<&str>) {
+    let Some(name) = name else {
+        for rule in RULES {
+            println!("{:<24} {}", rule.name, rule.summary);
+        }
+        for explanation in EXPLANATIONS {
+            println!("{:<24} {}", explanation.code, explanation.summary);
+        }
+        return;
+    };
+
+    let (summary, rationale, example) = if let Some(rule) = find_rule(name) {
+        (rule.summary, rule.rationale, rule.example)
+    } else if let Some(explanation) = explanation(name) {
+        (
+            explanation.summary,
+            explanation.rationale,
+            explanation.example,
+        )
+    } else {
+        exit_with_error(format!(
+            "Unknown lint rule or diagnostic code {name:?}, run \"yangfmt explain\" to list them"
+        ));
+    };
+
+    println!("{name}: {summary}");
+
+    if let Some(rule) = find_rule(name) {
+        match (rule.enabled, rule.group) {
+            (true, _) => println!("Default severity: {}", rule.severity),
+            (false, Some(group)) => println!(
+                "Default severity: {}, when the \"{group}\" group is enabled",
+                rule.severity
+            ),
+            (false, None) => println!("Default severity: {}, when enabled", rule.severity),
+        }
+    }
+
+    println!("\n{rationale}");
+
+    if let Some((before, after)) = example {
+        println!(
+            "\nExample:\n\n{}\n\nFixed:\n\n{}",
+            indent(before),
+            indent(after)
+        );
+    }
+}
+
+/// Indents every line of the text by four spaces
+fn indent(text: &str) -> String {
+    text.lines()
+        .map(|line| format!("    {line}"))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn parse_keyword_value_placement(value: &str) -> Result<(String, ValuePlacement), String> {
+    match value.split_once('=') {
+        Some((keyword, placement)) => Ok((keyword.to_string(), placement.parse()?)),
+        None => Err("expected KEYWORD=PLACEMENT".to_string()),
+    }
+}
+
+/// Loads the config file given on the command line, or the nearest one found from the input file
+///
+/// If there's no config file, all options get their default values.
+///
+fn load_config_file(config_path: Option<&Path>, file_path: Option<&str>) -> ConfigFile {
+    let path = match config_path {
+        Some(path) => Some(path.to_path_buf()),
+        None => {
+            let start_dir = match file_path {
+                Some(file_path) if file_path != "-" => std::fs::canonicalize(file_path)
+                    .ok()
+                    .and_then(|path| path.parent().map(Path::to_path_buf)),
+                _ => None,
+            };
+
+            start_dir
+                .or_else(|| std::env::current_dir().ok())
+                .and_then(|dir| find_config_file(&dir))
+        }
+    };
+
+    match path {
+        Some(path) => ConfigFile::load(&path).unwrap_or_else(|error| exit_with_error(error)),
+        None => ConfigFile::default(),
+    }
+}
+
+/// Builds the lint options from the config file, with the module search path of the command line
+/// followed by the one of the config file
+fn lint_config(config_file: &ConfigFile, search_path: &[PathBuf]) -> LintConfig {
+    let search_path: Vec<PathBuf> = search_path
+        .iter()
+        .chain(&config_file.search_path)
+        .cloned()
+        .collect();
+
+    LintConfig {
+        severities: config_file.lint.rules.clone(),
+        description_required: keyword_list(
+            config_file.lint.description_required.as_ref(),
+            DEFAULT_DESCRIPTION_REQUIRED,
+        ),
+        reference_required: keyword_list(config_file.lint.reference_required.as_ref(), &[]),
+        naming_pattern: config_file
+            .lint
+            .naming_pattern
+            .clone()
+            .unwrap_or_else(|| DEFAULT_NAMING_PATTERN.clone()),
+        prefix_pattern: config_file
+            .lint
+            .prefix_pattern
+            .clone()
+            .unwrap_or_else(|| DEFAULT_PREFIX_PATTERN.clone()),
+        max_prefix_length: config_file
+            .lint
+            .max_prefix_length
+            .unwrap_or(DEFAULT_MAX_PREFIX_LENGTH),
+        namespace_pattern: config_file.lint.namespace_pattern.clone(),
+        groups: config_file.lint.groups.clone(),
+        modules: Registry::new(&search_path).unwrap_or_else(|error| exit_with_error(error)),
+    }
+}
+
+/// Returns the given keyword list, or the defaults if no list was given
+///
+/// Empty keywords are dropped, so "--never-wrap=" can be used to clear the list.
+///
+fn keyword_list(keywords: Option<&Vec<String>>, default: &[&str]) -> Vec<String> {
+    match keywords {
+        Some(keywords) => keywords
+            .iter()
+            .filter(|keyword| !keyword.is_empty())
+            .cloned()
+            .collect(),
+        None => default.iter().map(|keyword| keyword.to_string()).collect(),
+    }
+}
+
+/// Merges keyword overrides from the config file and the command line, the latter taking
+/// precedence
+fn build_keyword_overrides(
+    args: &Args,
+    config_file: &ConfigFile,
+) -> HashMap<String, KeywordOverrides> {
+    let mut overrides: HashMap<String, KeywordOverrides> = HashMap::new();
+
+    for (keyword, section) in &config_file.overrides {
+        overrides.insert(
+            keyword.clone(),
+            KeywordOverrides {
+                value_placement: section.value_placement,
+                max_width: section.max_width,
+            },
+        );
+    }
+
+    for (keyword, placement) in &args.keyword_value_placement {
+        overrides
+            .entry(keyword.clone())
+            .or_default()
+            .value_placement = Some(*placement);
+    }
+
+    overrides
+}
+
+/// Builds the extension rules from the config file, unset fields keep the value of the built-in
+/// rule for the keyword if there is one
+fn build_extension_rules(config_file: &ConfigFile) -> ExtensionRules {
+    config_file
+        .extensions
+        .iter()
+        .map(|(keyword, section)| {
+            let mut rule = extension_rule(&ExtensionRules::new(), keyword)
+                .cloned()
+                .unwrap_or(ExtensionRule {
+                    argument: ArgumentKind::Unknown,
+                    placement: Placement::Preserve,
+                    single_line: false,
+                    children: true,
+                });
+
+            if let Some(argument) = section.argument {
+                rule.argument = argument;
+            }
+            if let Some(placement) = &section.placement {
+                rule.placement = placement.clone();
+            }
+            if let Some(single_line) = section.single_line {
+                rule.single_line = single_line;
+            }
+            if let Some(children) = section.children {
+                rule.children = children;
+            }
+
+            (keyword.clone(), rule)
+        })
+        .collect()
+}
+
+fn handle_formatting_error(error: FormattingError, buffer: &[u8]) -> ! {
     match error {
         FormattingError::ParseError(parse_error) => {
             let pos = TextPosition::from_buffer_index(buffer, parse_error.position);
@@ -121,6 +1078,54 @@ fn handle_formatting_error(error: FormattingError, buffer: &[u8]) {
     }
 }
 
+/// Prints the diagnostics in the same format as the lint findings, as notes if they're about
+/// lossy changes and warnings otherwise
+fn report_diagnostics(name: &str, diagnostics: &[Diagnostic], buffer: &[u8]) {
+    for diagnostic in diagnostics {
+        let pos = TextPosition::from_buffer_index(buffer, diagnostic.position);
+        let severity = match diagnostic.code {
+            Some(code) if LOSSY_CHANGE_CODES.contains(&code) => "note",
+            _ => "warning",
+        };
+
+        eprintln!("{name}: {pos}: {severity}: {}", describe(diagnostic));
+    }
+}
+
+/// Returns the diagnostic message, followed by its code if it has one
+fn describe(diagnostic: &Diagnostic) -> String {
+    match diagnostic.code {
+        Some(code) => format!("{} [{}]", diagnostic.message, code),
+        None => diagnostic.message.clone(),
+    }
+}
+
+fn report_findings(name: &str, findings: &[Finding], buffer: &[u8]) {
+    for finding in findings {
+        let pos = TextPosition::from_buffer_index(buffer, finding.diagnostic.position);
+        eprintln!(
+            "{name}: {pos}: {}: {} [{}]",
+            finding.severity, finding.diagnostic.message, finding.rule
+        );
+    }
+}
+
+/// Reads the given file, or STDIN if there's no file path or it's "-"
+fn read_input(buffer: &mut Vec<u8>, file_path: Option<&str>) {
+    match file_path {
+        Some(file_path) if file_path != "-" => read_file(buffer, file_path),
+        _ => read_stdin(buffer),
+    }
+}
+
+/// Returns the name of the input used in messages
+fn input_name(file_path: Option<&str>) -> &str {
+    match file_path {
+        Some(file_path) if file_path != "-" => file_path,
+        _ => "<stdin>",
+    }
+}
+
 fn read_stdin(buffer: &mut Vec<u8>) {
     if let Err(error) = stdin().read_to_end(buffer) {
         exit_with_error(format!("Failed to read from STDIN: {}", error));
@@ -192,3 +1197,44 @@ impl<T, E> OrError<T> for Result<T, E> {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn config(args: &[&str], config_file: &str) -> FormatConfig {
+        let args = Args::try_parse_from([&["yangfmt"], args].concat()).unwrap();
+        let config_file: ConfigFile = toml::from_str(config_file).unwrap();
+
+        format_config(&args, &config_file)
+    }
+
+    #[test]
+    fn test_format_config_turns_off_profile_options() {
+        assert!(config(&["--profile", "ietf"], "").fix_canonical_order);
+        assert!(!config(&["--profile", "ietf", "--no-canonical-order"], "").fix_canonical_order);
+        assert!(!config(&["--profile", "ietf"], "sort_revisions = false").sort_revisions);
+        assert!(
+            !config(&[], "profile = \"ietf\"\nseparate_definitions = false").separate_definitions
+        );
+
+        // The command line takes precedence over the config file
+        assert!(config(&["--sort-revisions"], "sort_revisions = false").sort_revisions);
+        assert!(!config(&["-c", "--no-canonical-order"], "").fix_canonical_order);
+    }
+
+    #[test]
+    fn test_format_config_folds_long_lines_only_for_output() {
+        assert!(config(&["--profile", "rfc"], "").fold_long_lines);
+        assert!(!config(&["--profile", "rfc", "--no-fold-long-lines"], "").fold_long_lines);
+        assert!(!config(&["--profile", "rfc", "--check"], "").fold_long_lines);
+        assert!(!config(&["--profile", "rfc", "-i", "foo.yang"], "").fold_long_lines);
+        assert!(Args::try_parse_from(["yangfmt", "--fold-long-lines", "--check"]).is_err());
+    }
+
+    #[test]
+    fn test_format_config_force_sort_without_canonical_order_flag() {
+        assert!(config(&["--profile", "ietf", "--force-sort"], "").force_sort);
+        assert!(config(&["--sort-by-name", "identity", "--force-sort"], "").force_sort);
+    }
+}
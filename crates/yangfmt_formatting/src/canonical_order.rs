@@ -1,4 +1,3 @@
-#![allow(dead_code)]
 /// This module handles sorting statements into the canonical order described by the ABNF.
 ///
 /// Since this code formatter is designed to be used while editing (for example on-save) it has to
@@ -9,13 +8,16 @@
 /// on the same screen at any point in time, such as statements within leaf blocks or at the top of
 /// module blocks.
 ///
-/// Note: I can't imagine a way to properly handle empty lines when sorting a list of statements,
-/// so if a statement list needs to be sorted, any empty lines will be removed. This is an area for
-/// possible improvement if I can think of a good strategy.
+/// Empty lines are kept at their positions when sorting, so blank line separated groups of
+/// statements keep their sizes. Statements only move between groups when a group is out of order
+/// with the groups around it.
 ///
 use phf::phf_map;
 
-use yangfmt_parsing::Node;
+use yangfmt_parsing::{Node, NodeHelpers, NodeValue, Statement, StatementKeyword};
+
+use crate::extensions::{extension_rule, ExtensionRules, Placement};
+use crate::EnumSorting;
 
 type OrderMapping = phf::Map<&'static str, u8>;
 
@@ -37,29 +39,430 @@ static LEAF_CANONICAL_ORDER: OrderMapping = phf_map! {
     "reference" => 14,
 };
 
+/// Describes the canonical order of statements inside a container block
+static CONTAINER_CANONICAL_ORDER: OrderMapping = phf_map! {
+    "when" => 1,
+    "if-feature" => 2,
+    "must" => 3,
+    "presence" => 4,
+    "config" => 5,
+    "status" => 6,
+    "description" => 7,
+    "reference" => 8,
+    "typedef" => 9,
+    "grouping" => 9,
+    "container" => 10,
+    "leaf" => 10,
+    "leaf-list" => 10,
+    "list" => 10,
+    "choice" => 10,
+    "anydata" => 10,
+    "anyxml" => 10,
+    "uses" => 10,
+    "action" => 11,
+    "notification" => 12,
+};
+
+/// Describes the canonical order of statements inside a list block
+static LIST_CANONICAL_ORDER: OrderMapping = phf_map! {
+    "when" => 1,
+    "if-feature" => 2,
+    "must" => 3,
+    "key" => 4,
+    "unique" => 5,
+    "config" => 6,
+    "min-elements" => 7,
+    "max-elements" => 8,
+    "ordered-by" => 9,
+    "status" => 10,
+    "description" => 11,
+    "reference" => 12,
+    "typedef" => 13,
+    "grouping" => 13,
+    "container" => 14,
+    "leaf" => 14,
+    "leaf-list" => 14,
+    "list" => 14,
+    "choice" => 14,
+    "anydata" => 14,
+    "anyxml" => 14,
+    "uses" => 14,
+    "action" => 15,
+    "notification" => 16,
+};
+
+/// Describes the canonical order of statements inside a typedef block
+static TYPEDEF_CANONICAL_ORDER: OrderMapping = phf_map! {
+    "type" => 1,
+    "units" => 2,
+    "default" => 3,
+    "status" => 4,
+    "description" => 5,
+    "reference" => 6,
+};
+
+/// Describes the canonical order of statements inside a choice block
+static CHOICE_CANONICAL_ORDER: OrderMapping = phf_map! {
+    "when" => 1,
+    "if-feature" => 2,
+    "default" => 3,
+    "config" => 4,
+    "mandatory" => 5,
+    "status" => 6,
+    "description" => 7,
+    "reference" => 8,
+    "case" => 9,
+    "choice" => 9,
+    "container" => 9,
+    "leaf" => 9,
+    "leaf-list" => 9,
+    "list" => 9,
+    "anydata" => 9,
+    "anyxml" => 9,
+};
+
+/// Describes the canonical order of statements inside a case block
+static CASE_CANONICAL_ORDER: OrderMapping = phf_map! {
+    "when" => 1,
+    "if-feature" => 2,
+    "status" => 3,
+    "description" => 4,
+    "reference" => 5,
+    "container" => 6,
+    "leaf" => 6,
+    "leaf-list" => 6,
+    "list" => 6,
+    "choice" => 6,
+    "anydata" => 6,
+    "anyxml" => 6,
+    "uses" => 6,
+};
+
+/// Describes the canonical order of statements inside an rpc or action block
+static RPC_CANONICAL_ORDER: OrderMapping = phf_map! {
+    "if-feature" => 1,
+    "status" => 2,
+    "description" => 3,
+    "reference" => 4,
+    "typedef" => 5,
+    "grouping" => 5,
+    "input" => 6,
+    "output" => 7,
+};
+
+/// Describes the canonical order of statements inside an input or output block
+static INPUT_OUTPUT_CANONICAL_ORDER: OrderMapping = phf_map! {
+    "must" => 1,
+    "typedef" => 2,
+    "grouping" => 2,
+    "container" => 3,
+    "leaf" => 3,
+    "leaf-list" => 3,
+    "list" => 3,
+    "choice" => 3,
+    "anydata" => 3,
+    "anyxml" => 3,
+    "uses" => 3,
+};
+
+/// Describes the canonical order of statements inside a notification block
+static NOTIFICATION_CANONICAL_ORDER: OrderMapping = phf_map! {
+    "if-feature" => 1,
+    "must" => 2,
+    "status" => 3,
+    "description" => 4,
+    "reference" => 5,
+    "typedef" => 6,
+    "grouping" => 6,
+    "container" => 7,
+    "leaf" => 7,
+    "leaf-list" => 7,
+    "list" => 7,
+    "choice" => 7,
+    "anydata" => 7,
+    "anyxml" => 7,
+    "uses" => 7,
+};
+
+/// Describes the canonical order of statements inside a grouping block
+static GROUPING_CANONICAL_ORDER: OrderMapping = phf_map! {
+    "status" => 1,
+    "description" => 2,
+    "reference" => 3,
+    "typedef" => 4,
+    "grouping" => 4,
+    "container" => 5,
+    "leaf" => 5,
+    "leaf-list" => 5,
+    "list" => 5,
+    "choice" => 5,
+    "anydata" => 5,
+    "anyxml" => 5,
+    "uses" => 5,
+    "action" => 6,
+    "notification" => 7,
+};
+
+/// Describes the canonical order of the header of a module or submodule block
+///
+/// This covers the header, linkage, meta and revision sections, which come before the body
+/// statements. Revisions keep their relative order, which is usually newest first.
+///
+static MODULE_HEADER_CANONICAL_ORDER: OrderMapping = phf_map! {
+    "yang-version" => 1,
+    "namespace" => 2,
+    "prefix" => 3,
+    "belongs-to" => 3,
+    "import" => 4,
+    "include" => 5,
+    "organization" => 6,
+    "contact" => 7,
+    "description" => 8,
+    "reference" => 9,
+    "revision" => 10,
+};
+
+/// Describes the canonical order of statements inside an anydata or anyxml block
+static ANYDATA_CANONICAL_ORDER: OrderMapping = phf_map! {
+    "when" => 1,
+    "if-feature" => 2,
+    "must" => 3,
+    "config" => 4,
+    "mandatory" => 5,
+    "status" => 6,
+    "description" => 7,
+    "reference" => 8,
+};
+
+/// Describes the canonical order of statements inside an augment block
+static AUGMENT_CANONICAL_ORDER: OrderMapping = phf_map! {
+    "when" => 1,
+    "if-feature" => 2,
+    "status" => 3,
+    "description" => 4,
+    "reference" => 5,
+    "container" => 6,
+    "leaf" => 6,
+    "leaf-list" => 6,
+    "list" => 6,
+    "choice" => 6,
+    "anydata" => 6,
+    "anyxml" => 6,
+    "uses" => 6,
+    "case" => 6,
+    "action" => 6,
+    "notification" => 6,
+};
+
+/// Describes the canonical order of statements inside a uses block
+static USES_CANONICAL_ORDER: OrderMapping = phf_map! {
+    "when" => 1,
+    "if-feature" => 2,
+    "status" => 3,
+    "description" => 4,
+    "reference" => 5,
+    "refine" => 6,
+    "augment" => 7,
+};
+
+/// Describes the canonical order of statements inside an identity block
+static IDENTITY_CANONICAL_ORDER: OrderMapping = phf_map! {
+    "if-feature" => 1,
+    "base" => 2,
+    "status" => 3,
+    "description" => 4,
+    "reference" => 5,
+};
+
+/// Describes the canonical order of statements inside a feature block
+static FEATURE_CANONICAL_ORDER: OrderMapping = phf_map! {
+    "if-feature" => 1,
+    "status" => 2,
+    "description" => 3,
+    "reference" => 4,
+};
+
+/// Describes the canonical order of statements inside an extension block
+static EXTENSION_CANONICAL_ORDER: OrderMapping = phf_map! {
+    "argument" => 1,
+    "status" => 2,
+    "description" => 3,
+    "reference" => 4,
+};
+
+/// Describes the canonical order of statements inside an import block
+static IMPORT_CANONICAL_ORDER: OrderMapping = phf_map! {
+    "prefix" => 1,
+    "revision-date" => 2,
+    "description" => 3,
+    "reference" => 4,
+};
+
+/// Describes the canonical order of statements inside an enum block
+static ENUM_CANONICAL_ORDER: OrderMapping = phf_map! {
+    "if-feature" => 1,
+    "value" => 2,
+    "status" => 3,
+    "description" => 4,
+    "reference" => 5,
+};
+
+/// Describes the canonical order of statements inside a bit block
+static BIT_CANONICAL_ORDER: OrderMapping = phf_map! {
+    "if-feature" => 1,
+    "position" => 2,
+    "status" => 3,
+    "description" => 4,
+    "reference" => 5,
+};
+
+/// Describes the canonical order of statements inside must, range, length and pattern blocks
+static RESTRICTION_CANONICAL_ORDER: OrderMapping = phf_map! {
+    "modifier" => 1,
+    "error-message" => 2,
+    "error-app-tag" => 3,
+    "description" => 4,
+    "reference" => 5,
+};
+
+/// Describes the canonical order of statements inside a type block
+///
+/// Only some of these are valid for any given built-in type, e.g. "length" and "pattern" for
+/// strings. The member types of a union keep their order.
+///
+static TYPE_CANONICAL_ORDER: OrderMapping = phf_map! {
+    "range" => 1,
+    "length" => 1,
+    "pattern" => 2,
+    "fraction-digits" => 3,
+    "path" => 4,
+    "require-instance" => 5,
+    "base" => 6,
+    "enum" => 7,
+    "bit" => 7,
+    "type" => 7,
+};
+
+/// Describes the canonical order of statements inside a refine block, see RFC 7950 section 7.13.2
+static REFINE_CANONICAL_ORDER: OrderMapping = phf_map! {
+    "if-feature" => 1,
+    "must" => 2,
+    "presence" => 3,
+    "default" => 4,
+    "config" => 5,
+    "mandatory" => 6,
+    "min-elements" => 7,
+    "max-elements" => 8,
+    "description" => 9,
+    "reference" => 10,
+};
+
+/// Describes the canonical order of statements inside a deviation block
+static DEVIATION_CANONICAL_ORDER: OrderMapping = phf_map! {
+    "description" => 1,
+    "reference" => 2,
+    "deviate" => 3,
+};
+
+/// Describes the canonical order of statements inside deviate add, replace and delete blocks, see
+/// RFC 7950 section 7.20.3.2
+///
+/// Each kind of deviate only allows some of these, but they share the same relative order.
+///
+static DEVIATE_CANONICAL_ORDER: OrderMapping = phf_map! {
+    "type" => 1,
+    "units" => 2,
+    "must" => 3,
+    "unique" => 4,
+    "default" => 5,
+    "config" => 6,
+    "mandatory" => 7,
+    "min-elements" => 8,
+    "max-elements" => 9,
+};
+
+/// Returns the order mapping for the children of the given statement keyword, if it has one
+fn order_mapping_for(keyword: &str) -> Option<&'static OrderMapping> {
+    let mapping = match keyword {
+        "leaf" | "leaf-list" => &LEAF_CANONICAL_ORDER,
+        "container" => &CONTAINER_CANONICAL_ORDER,
+        "list" => &LIST_CANONICAL_ORDER,
+        "typedef" => &TYPEDEF_CANONICAL_ORDER,
+        "type" => &TYPE_CANONICAL_ORDER,
+        "choice" => &CHOICE_CANONICAL_ORDER,
+        "case" => &CASE_CANONICAL_ORDER,
+        "rpc" | "action" => &RPC_CANONICAL_ORDER,
+        "input" | "output" => &INPUT_OUTPUT_CANONICAL_ORDER,
+        "notification" => &NOTIFICATION_CANONICAL_ORDER,
+        "grouping" => &GROUPING_CANONICAL_ORDER,
+        "anydata" | "anyxml" => &ANYDATA_CANONICAL_ORDER,
+        "augment" => &AUGMENT_CANONICAL_ORDER,
+        "uses" => &USES_CANONICAL_ORDER,
+        "refine" => &REFINE_CANONICAL_ORDER,
+        "deviation" => &DEVIATION_CANONICAL_ORDER,
+        "deviate" => &DEVIATE_CANONICAL_ORDER,
+        "identity" => &IDENTITY_CANONICAL_ORDER,
+        "feature" => &FEATURE_CANONICAL_ORDER,
+        "extension" => &EXTENSION_CANONICAL_ORDER,
+        "import" | "include" => &IMPORT_CANONICAL_ORDER,
+        "enum" => &ENUM_CANONICAL_ORDER,
+        "bit" => &BIT_CANONICAL_ORDER,
+        "must" | "range" | "length" | "pattern" => &RESTRICTION_CANONICAL_ORDER,
+        _ => return None,
+    };
+
+    Some(mapping)
+}
+
+/// Returns the sort value of the statement, or None if it isn't part of the order
+///
+/// Extension statements are placed according to their rule, see "extension_rule". The values of
+/// the order mapping are doubled to make room for the extensions placed after a statement.
+///
+fn order_of(
+    order_mapping: &OrderMapping,
+    extensions: &ExtensionRules,
+    statement: &Statement,
+) -> Option<u16> {
+    if let Some(order) = order_mapping.get(statement.keyword.text()) {
+        return Some(*order as u16 * 2);
+    }
+
+    if !matches!(statement.keyword, StatementKeyword::ExtensionKeyword(_)) {
+        return None;
+    }
+
+    match extension_rule(extensions, statement.keyword.text())?.placement {
+        Placement::Preserve => None,
+        Placement::First => Some(0),
+        Placement::After(ref keyword) => order_mapping
+            .get(keyword.as_str())
+            .map(|order| *order as u16 * 2 + 1),
+        Placement::Last => Some(u16::MAX - 1),
+    }
+}
+
 /// Checks if all the statements in the statement list is sorted
 ///
 /// This ignores line breaks and comments.
 ///
-pub fn is_sorted(order_mapping: &OrderMapping, statements: &mut Vec<Node>) -> bool {
-    let mut previous: Option<u8> = None;
+pub fn is_sorted(
+    order_mapping: &OrderMapping,
+    extensions: &ExtensionRules,
+    statements: &[Node],
+) -> bool {
+    let mut previous: Option<u16> = None;
 
-    for statement in statements.iter().take(statements.len() - 1) {
+    for statement in statements {
         match statement {
             Node::Statement(statement) => {
-                let sort_value = match order_mapping.get(statement.keyword.text()) {
-                    Some(value) => *value,
-                    None => u8::MAX,
-                };
-
-                match previous {
-                    None => previous = Some(sort_value),
-                    Some(previous) => {
-                        if sort_value < previous {
-                            return false;
-                        }
-                    }
+                let sort_value = order_of(order_mapping, extensions, statement).unwrap_or(u16::MAX);
+
+                if previous.is_some_and(|previous| sort_value < previous) {
+                    return false;
                 }
+
+                previous = Some(sort_value);
             }
             _ => continue, // Ignore comments and empty lines
         }
@@ -69,26 +472,1575 @@ pub fn is_sorted(order_mapping: &OrderMapping, statements: &mut Vec<Node>) -> bo
 }
 
 /// Sorts the input statement list following the canonical order from the ABNF
-pub fn sort_statements(_parent_node_name: Option<&str>, _statements: &mut [Node]) {
-    // match parent_node_name {
-    //     Some("leaf") => sort_statements_with(&LEAF_CANONICAL_ORDER, statements),
-    //     Some(_) => (),
-    //     None => (),
-    // }
+///
+/// Statement lists containing raw regions or statements that aren't part of the order (like
+/// extension statements) are left alone, since there's no telling where those belong.
+///
+/// Only lists without comments or empty lines are safe to sort, the rest are left alone unless
+/// "force" is set. In that case, comments are moved along with the statement below them. Returns
+/// the position of the first statement out of order if a list was left alone for that reason, so
+/// it can be reported as needing manual ordering.
+///
+pub fn sort_statements(
+    parent_node_name: Option<&str>,
+    statements: &mut Vec<Node>,
+    force: bool,
+    extensions: &ExtensionRules,
+) -> Option<usize> {
+    if matches!(parent_node_name, Some("module" | "submodule")) {
+        return sort_module_header(statements, force, extensions);
+    }
+
+    let order_mapping = parent_node_name.and_then(order_mapping_for)?;
+    let mut unsorted = sort_with_mapping(order_mapping, extensions, statements, force);
+
+    if matches!(parent_node_name, Some("uses" | "augment" | "case")) {
+        unsorted = unsorted.or(hoist_conditions(statements, force));
+    }
+
+    unsorted
+}
+
+/// Moves the "when" and "if-feature" statements to the top of the statement list
+///
+/// This is done for uses, augment and case blocks even if the rest of the block can't be sorted,
+/// e.g. because of extension statements. Like with sorting, conditions are only moved past
+/// comments and empty lines if "force" is set, otherwise the position of the first condition out
+/// of place is returned.
+///
+fn hoist_conditions(statements: &mut Vec<Node>, force: bool) -> Option<usize> {
+    let is_condition = |node: &Node| {
+        matches!(node, Node::Statement(statement)
+            if matches!(statement.keyword.text(), "when" | "if-feature"))
+    };
+
+    let first_other = statements
+        .iter()
+        .position(|node| matches!(node, Node::Statement(_)) && !is_condition(node))?;
+
+    let misplaced = statements[first_other..]
+        .iter()
+        .find_map(|node| match node {
+            Node::Statement(statement) if is_condition(node) => Some(statement.position),
+            _ => None,
+        })?;
+
+    let last_condition = statements.iter().rposition(is_condition)?;
+
+    let safe = !statements[..last_condition]
+        .iter()
+        .any(|node| node.is_comment() || node.is_empty_line());
+
+    if !safe && !force {
+        return Some(misplaced);
+    }
+
+    let mut conditions: Vec<Node> = vec![];
+    let mut rest: Vec<Node> = vec![];
+    let mut pending: Vec<Node> = vec![];
+
+    for node in statements.drain(..) {
+        if node.is_comment() {
+            pending.push(node);
+        } else if is_condition(&node) || (node.is_empty_line() && rest.is_empty()) {
+            conditions.append(&mut pending);
+            conditions.push(node);
+        } else {
+            rest.append(&mut pending);
+            rest.push(node);
+        }
+    }
+
+    // "when" comes before "if-feature"
+    sort_units_by_key(&mut conditions, |node| match node {
+        Node::Statement(statement) => statement.keyword.text() != "when",
+        _ => false,
+    });
+
+    statements.extend(conditions);
+    statements.extend(rest);
+    statements.extend(pending);
+
+    None
 }
 
-fn sort_statements_with(order_mapping: &OrderMapping, statements: &mut [Node]) {
-    statements.sort_by(|node_a, node_b| {
-        get_order_for(order_mapping, node_a).cmp(&get_order_for(order_mapping, node_b))
+/// Sorts the header statements at the top of a module, up until the first body statement
+///
+/// Body statements are never moved, and neither are header statements below the first body
+/// statement. Empty lines and comments right before the first body statement are kept in place.
+///
+fn sort_module_header(
+    statements: &mut Vec<Node>,
+    force: bool,
+    extensions: &ExtensionRules,
+) -> Option<usize> {
+    let end = module_header_end(statements, extensions);
+    let mut header: Vec<Node> = statements.drain(..end).collect();
+
+    let mut unsorted = sort_with_mapping(
+        &MODULE_HEADER_CANONICAL_ORDER,
+        extensions,
+        &mut header,
+        force,
+    );
+
+    if unsorted.is_none() && is_sorted(&MODULE_HEADER_CANONICAL_ORDER, extensions, &header) {
+        unsorted = group_linkage_statements(&mut header, force);
+    }
+
+    statements.splice(..0, header);
+
+    unsorted
+}
+
+/// Alphabetizes the imports and includes of a sorted module header, and separates the two groups
+/// with an empty line
+///
+/// The statements are sorted like any other list, so unless "force" is set, statements separated
+/// by comments or empty lines aren't moved and the position of the first one out of order is
+/// returned. Once sorted, the empty lines within each group are removed to keep it contiguous.
+///
+fn group_linkage_statements(header: &mut Vec<Node>, force: bool) -> Option<usize> {
+    let is_linkage = |node: &Node| {
+        matches!(node, Node::Statement(statement)
+            if matches!(statement.keyword.text(), "import" | "include"))
+    };
+
+    let (Some(start), Some(last)) = (
+        header.iter().position(is_linkage),
+        header.iter().rposition(is_linkage),
+    ) else {
+        return None;
+    };
+
+    let mut linkage: Vec<Node> = header.drain(start..=last).collect();
+    let positions = statement_positions(&linkage);
+
+    // Imports come before includes
+    let unsorted = sort_by_order(&mut linkage, force, |statement| {
+        Some((
+            statement.keyword.text() != "import",
+            argument(statement).to_string(),
+        ))
+    });
+
+    if statement_positions(&linkage) != positions {
+        linkage.retain(|node| !node.is_empty_line());
+    }
+
+    let first_include = linkage.iter().position(
+        |node| matches!(node, Node::Statement(statement) if statement.keyword.text() == "include"),
+    );
+
+    if let Some(mut index) = first_include.filter(|index| *index > 0) {
+        while index > 0 && linkage[index - 1].is_comment() {
+            index -= 1;
+        }
+
+        if index > 0 && !linkage[index - 1].is_empty_line() {
+            linkage.insert(index, Node::EmptyLine("\n".to_string()));
+        }
+    }
+
+    header.splice(start..start, linkage);
+
+    unsorted
+}
+
+/// Returns the positions of the statements in the list, in the order they're in
+pub fn statement_positions(statements: &[Node]) -> Vec<usize> {
+    statements
+        .iter()
+        .filter_map(|node| match node {
+            Node::Statement(statement) => Some(statement.position),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Returns the index of the first node after the header of a module, see "sort_module_header"
+fn module_header_end(statements: &[Node], extensions: &ExtensionRules) -> usize {
+    let is_body_statement = |node: &Node| match node {
+        Node::Statement(statement) => {
+            order_of(&MODULE_HEADER_CANONICAL_ORDER, extensions, statement).is_none()
+        }
+        _ => false,
+    };
+
+    let mut end = statements
+        .iter()
+        .position(is_body_statement)
+        .unwrap_or(statements.len());
+
+    while end > 0 && matches!(statements[end - 1], Node::EmptyLine(_) | Node::Comment(_)) {
+        end -= 1;
+    }
+
+    end
+}
+
+/// Returns the statements that are out of canonical order, each paired with the first statement
+/// above it that it should come before
+///
+/// Like "sort_statements", only the header of modules is checked. Statements that aren't part of
+/// the order are ignored.
+///
+pub fn find_order_violations<'a>(
+    parent_node_name: Option<&str>,
+    statements: &'a [Node],
+    extensions: &ExtensionRules,
+) -> Vec<(&'a Statement, &'a Statement)> {
+    let (order_mapping, statements) = match parent_node_name {
+        Some("module" | "submodule") => (
+            &MODULE_HEADER_CANONICAL_ORDER,
+            &statements[..module_header_end(statements, extensions)],
+        ),
+        _ => match parent_node_name.and_then(order_mapping_for) {
+            Some(order_mapping) => (order_mapping, statements),
+            None => return vec![],
+        },
+    };
+
+    let mut violations = vec![];
+    let mut seen: Vec<(u16, &Statement)> = vec![];
+
+    for node in statements {
+        let Node::Statement(statement) = node else {
+            continue;
+        };
+
+        let Some(order) = order_of(order_mapping, extensions, statement) else {
+            continue;
+        };
+
+        if let Some((_, before)) = seen.iter().find(|(seen_order, _)| *seen_order > order) {
+            violations.push((statement, *before));
+        }
+
+        seen.push((order, statement));
+    }
+
+    violations
+}
+
+fn sort_with_mapping(
+    order_mapping: &OrderMapping,
+    extensions: &ExtensionRules,
+    statements: &mut Vec<Node>,
+    force: bool,
+) -> Option<usize> {
+    sort_by_order(statements, force, |statement| {
+        order_of(order_mapping, extensions, statement)
     })
 }
 
-fn get_order_for(order_mapping: &OrderMapping, node: &Node) -> u8 {
+/// Sorts the statement list by the order of each statement, see "sort_statements"
+///
+/// Lists with raw regions or statements without an order are left alone. Unless "force" is set,
+/// lists with comments or empty lines are left alone too, and the position of the first statement
+/// out of order is returned.
+///
+fn sort_by_order<K: Ord>(
+    statements: &mut Vec<Node>,
+    force: bool,
+    order: impl Fn(&Statement) -> Option<K>,
+) -> Option<usize> {
+    let mut orders: Vec<(K, usize)> = vec![];
+
+    for node in statements.iter() {
+        match node {
+            Node::Statement(statement) => orders.push((order(statement)?, statement.position)),
+            Node::EmptyLine(_) | Node::Comment(_) => (),
+            Node::Raw(_) => return None,
+        }
+    }
+
+    let misplaced = orders
+        .windows(2)
+        .find(|pair| pair[1].0 < pair[0].0)
+        .map(|pair| pair[1].1)?;
+
+    let safe = !statements
+        .iter()
+        .any(|node| matches!(node, Node::EmptyLine(_) | Node::Comment(_)));
+
+    if !safe && !force {
+        return Some(misplaced);
+    }
+
+    sort_units_by_key(statements, |node| match node {
+        Node::Statement(statement) => order(statement),
+        _ => None,
+    });
+
+    None
+}
+
+/// Stably sorts the statements by the given key, moving each statement together with the
+/// comments above it
+///
+/// Comments after the last statement stay at the end. Empty lines are put back at the same
+/// positions, see "sort_statements".
+///
+fn sort_units_by_key<K: Ord>(statements: &mut Vec<Node>, mut key: impl FnMut(&Node) -> K) {
+    let mut units: Vec<Vec<Node>> = vec![];
+    let mut pending: Vec<Node> = vec![];
+
+    // Empty lines between units, along with the number of units before them
+    let mut separators: Vec<(usize, Node)> = vec![];
+
+    for node in statements.drain(..) {
+        match node {
+            Node::EmptyLine(_) if pending.is_empty() => separators.push((units.len(), node)),
+            Node::Statement(_) => {
+                pending.push(node);
+                units.push(std::mem::take(&mut pending));
+            }
+            _ => pending.push(node),
+        }
+    }
+
+    units.sort_by_key(|unit| key(unit.last().unwrap()));
+
+    // The empty lines are put back at the same positions, so the sizes of blank line separated
+    // groups are preserved. If the groups were only out of order internally, this is the same as
+    // sorting each group on its own.
+    let mut separators = separators.into_iter().peekable();
+
+    for (index, unit) in units.into_iter().enumerate() {
+        while let Some((_, separator)) = separators.next_if(|(before, _)| *before == index) {
+            statements.push(separator);
+        }
+
+        statements.extend(unit);
+    }
+
+    statements.extend(separators.map(|(_, separator)| separator));
+    statements.extend(pending);
+}
+
+fn is_revision(node: &Node) -> bool {
+    matches!(node, Node::Statement(statement) if statement.keyword.text() == "revision")
+}
+
+/// Returns the argument of a statement without quotes, or an empty string if it has none
+fn argument(statement: &Statement) -> &str {
+    match statement.value {
+        Some(
+            NodeValue::Date(ref text)
+            | NodeValue::Number(ref text)
+            | NodeValue::String(ref text)
+            | NodeValue::Other(ref text),
+        ) => text.trim_matches(['"', '\'']),
+        _ => "",
+    }
+}
+
+/// Returns the date of a revision statement, or an empty string for anything else
+fn revision_date(node: &Node) -> &str {
     match node {
-        Node::Statement(statement) => match order_mapping.get(statement.keyword.text()) {
-            Some(order) => *order,
-            None => u8::MAX,
-        },
-        _ => u8::MAX,
+        Node::Statement(statement) if statement.keyword.text() == "revision" => argument(statement),
+        _ => "",
+    }
+}
+
+/// Checks for comments right above the given statements, or empty lines between the first and the
+/// last of them, which may mean they're ordered or grouped on purpose
+fn has_comments_or_empty_lines(statements: &[Node], is_unit: impl Fn(&Node) -> bool) -> bool {
+    let (Some(first), Some(last)) = (
+        statements.iter().position(&is_unit),
+        statements.iter().rposition(&is_unit),
+    ) else {
+        return false;
+    };
+
+    // Empty lines only matter between the statements, where they group them
+    statements
+        .windows(2)
+        .any(|pair| pair[0].is_comment() && is_unit(&pair[1]))
+        || statements[first..last]
+            .iter()
+            .any(|node| node.is_empty_line())
+}
+
+/// Sorts the revision statements of a module by descending date
+///
+/// The comments right above each revision are moved along with it. Revisions are only sorted if
+/// there are no other statements in between them. Unless "force" is set, revisions with comments
+/// or empty lines between them aren't sorted either, and the position of the first revision
+/// that's out of order is returned instead.
+///
+pub fn sort_revisions(statements: &mut Vec<Node>, force: bool) -> Option<Unsorted> {
+    let (Some(mut start), Some(last)) = (
+        statements.iter().position(is_revision),
+        statements.iter().rposition(is_revision),
+    ) else {
+        return None;
+    };
+
+    if statements[start..=last]
+        .iter()
+        .any(|node| matches!(node, Node::Statement(_)) && !is_revision(node))
+    {
+        return None;
+    }
+
+    let revisions: Vec<&Statement> = statements
+        .iter()
+        .filter_map(|node| match node {
+            Node::Statement(statement) if is_revision(node) => Some(statement),
+            _ => None,
+        })
+        .collect();
+
+    let misplaced = revisions
+        .windows(2)
+        .find(|pair| argument(pair[1]) > argument(pair[0]))?[1]
+        .position;
+
+    if has_comments_or_empty_lines(statements, is_revision) && !force {
+        return Some(Unsorted::ManualOrdering(misplaced));
+    }
+
+    while start > 0 && statements[start - 1].is_comment() {
+        start -= 1;
+    }
+
+    let mut revisions: Vec<Node> = statements.drain(start..=last).collect();
+
+    sort_units_by_key(&mut revisions, |node| {
+        std::cmp::Reverse(revision_date(node).to_string())
+    });
+
+    statements.splice(start..start, revisions);
+
+    None
+}
+
+fn get_order_for(order_mapping: &OrderMapping, extensions: &ExtensionRules, node: &Node) -> u16 {
+    match node {
+        Node::Statement(statement) => {
+            order_of(order_mapping, extensions, statement).unwrap_or(u16::MAX)
+        }
+        _ => u16::MAX,
+    }
+}
+
+/// Order of the statements moved by "move_documentation_last"
+static DOCUMENTATION_ORDER: OrderMapping = phf_map! {
+    "status" => 1,
+    "description" => 2,
+    "reference" => 3,
+};
+
+/// Moves the "status", "description" and "reference" statements to the end of the meta section
+/// of the statement list
+///
+/// That's right before the statements the canonical order of the parent puts after them, like
+/// the definitions and data nodes of a container, or the end of the list if there are none. This
+/// is a lightweight alternative to sorting the whole block. The statements are moved along with
+/// the comments above them, so statements with comments above them are only moved if "force" is
+/// set. Empty lines and comments after the last statement stay where they are.
+///
+pub fn move_documentation_last(
+    parent_node_name: Option<&str>,
+    statements: &mut Vec<Node>,
+    force: bool,
+    extensions: &ExtensionRules,
+) {
+    let is_documentation = |node: &Node| {
+        matches!(node, Node::Statement(statement)
+            if DOCUMENTATION_ORDER.contains_key(statement.keyword.text()))
+    };
+
+    let order_mapping = parent_node_name.and_then(order_mapping_for);
+    let documentation_order = order_mapping.and_then(|order_mapping| {
+        DOCUMENTATION_ORDER
+            .keys()
+            .filter_map(|keyword| order_mapping.get(keyword))
+            .min()
+            .map(|order| *order as u16 * 2)
+    });
+
+    let belongs_after = |node: &Node| match (node, order_mapping, documentation_order) {
+        (Node::Statement(statement), Some(order_mapping), Some(documentation_order)) => {
+            order_of(order_mapping, extensions, statement)
+                .is_some_and(|order| order > documentation_order)
+        }
+        _ => false,
+    };
+
+    if !statements.iter().any(is_documentation) {
+        return;
+    }
+
+    let mut seen_documentation = false;
+    let mut seen_after = false;
+    let mut misplaced = false;
+
+    for node in statements.iter() {
+        if is_documentation(node) {
+            misplaced |= seen_after;
+            seen_documentation = true;
+        } else if belongs_after(node) {
+            seen_after = true;
+        } else if matches!(node, Node::Statement(_) | Node::Raw(_)) {
+            misplaced |= seen_documentation;
+        }
+    }
+
+    let sorted = statements
+        .iter()
+        .filter_map(|node| match node {
+            Node::Statement(statement) => DOCUMENTATION_ORDER.get(statement.keyword.text()),
+            _ => None,
+        })
+        .is_sorted();
+
+    if !misplaced && sorted {
+        return;
+    }
+
+    let has_comments = statements
+        .windows(2)
+        .any(|pair| pair[0].is_comment() && is_documentation(&pair[1]));
+
+    if has_comments && !force {
+        return;
+    }
+
+    let mut documentation: Vec<Node> = vec![];
+    let mut rest: Vec<Node> = vec![];
+    let mut pending: Vec<Node> = vec![];
+
+    for node in statements.drain(..) {
+        if node.is_comment() {
+            pending.push(node);
+        } else if is_documentation(&node) {
+            documentation.append(&mut pending);
+            documentation.push(node);
+        } else {
+            rest.append(&mut pending);
+            rest.push(node);
+        }
+    }
+
+    sort_units_by_key(&mut documentation, |node| {
+        get_order_for(&DOCUMENTATION_ORDER, extensions, node)
+    });
+
+    // The documentation goes above the empty lines and comments before the first statement that
+    // comes after it
+    let mut end = rest.iter().position(belongs_after).unwrap_or(rest.len());
+
+    while end > 0 && matches!(rest[end - 1], Node::EmptyLine(_) | Node::Comment(_)) {
+        end -= 1;
+    }
+
+    statements.extend(rest.drain(..end));
+    statements.extend(documentation);
+    statements.extend(rest);
+    statements.extend(pending);
+}
+
+/// Returns the "value" of an enum statement or the "position" of a bit statement, if it has one
+fn explicit_value(node: &Node) -> Option<i64> {
+    let Node::Statement(statement) = node else {
+        return None;
+    };
+
+    let keyword = match statement.keyword.text() {
+        "enum" => "value",
+        "bit" => "position",
+        _ => return None,
+    };
+
+    statement
+        .children
+        .as_ref()?
+        .iter()
+        .find_map(|child| match child {
+            Node::Statement(child) if child.keyword.text() == keyword => Some(argument(child)),
+            _ => None,
+        })?
+        .parse()
+        .ok()
+}
+
+/// Returns the position of the first enum or bit among the statements that doesn't have an
+/// explicit value
+///
+/// Enums and bits without a value get the one after the highest value before them, so moving
+/// them changes their value.
+///
+pub fn find_implicit_value(statements: &[Node]) -> Option<usize> {
+    statements.iter().find_map(|node| match node {
+        Node::Statement(statement)
+            if matches!(statement.keyword.text(), "enum" | "bit")
+                && explicit_value(node).is_none() =>
+        {
+            Some(statement.position)
+        }
+        _ => None,
+    })
+}
+
+/// Returns the index of the first key that's lower than the one before it
+fn first_unsorted<T: PartialOrd>(keys: &[T]) -> Option<usize> {
+    (1..keys.len()).find(|&index| keys[index] < keys[index - 1])
+}
+
+/// Returns the values of the enums, implicit values included
+fn enum_values(statements: &[Node]) -> Vec<i64> {
+    let mut values = vec![];
+    let mut highest: Option<i64> = None;
+
+    for node in statements
+        .iter()
+        .filter(|node| matches!(node, Node::Statement(_)))
+    {
+        let value = explicit_value(node).unwrap_or_else(|| highest.map_or(0, |value| value + 1));
+
+        highest = Some(highest.map_or(value, |highest| highest.max(value)));
+        values.push(value);
+    }
+
+    values
+}
+
+/// Sorts the enum statements of an enumeration type by value or name
+///
+/// The comments right above each enum are moved along with it. Enums are only moved if every enum
+/// has an explicit value, since moving the others changes their value, and unless "force" is set,
+/// if there are no comments or empty lines between them. Otherwise the reason they should be
+/// sorted but aren't is returned.
+///
+pub fn sort_enums(
+    statements: &mut Vec<Node>,
+    sorting: EnumSorting,
+    force: bool,
+) -> Option<Unsorted> {
+    let is_enum = |node: &Node| matches!(node, Node::Statement(statement) if statement.keyword.text() == "enum");
+
+    if !statements.iter().any(is_enum)
+        || statements
+            .iter()
+            .any(|node| matches!(node, Node::Statement(_)) && !is_enum(node))
+    {
+        return None;
+    }
+
+    let names = || {
+        statements.iter().filter_map(|node| match node {
+            Node::Statement(statement) => Some(argument(statement)),
+            _ => None,
+        })
+    };
+
+    // The index of the first enum that's out of order
+    let misplaced = match sorting {
+        EnumSorting::None => None,
+        EnumSorting::Value => first_unsorted(&enum_values(statements)),
+        EnumSorting::Name => first_unsorted(&names().collect::<Vec<_>>()),
+    }?;
+
+    if let Some(position) = find_implicit_value(statements) {
+        return Some(Unsorted::ImplicitValue(position));
+    }
+
+    if has_comments_or_empty_lines(statements, is_enum) && !force {
+        let position = statements
+            .iter()
+            .filter_map(|node| match node {
+                Node::Statement(statement) => Some(statement.position),
+                _ => None,
+            })
+            .nth(misplaced)?;
+
+        return Some(Unsorted::ManualOrdering(position));
+    }
+
+    match sorting {
+        EnumSorting::None => (),
+        EnumSorting::Value => sort_units_by_key(statements, explicit_value),
+        EnumSorting::Name => sort_units_by_key(statements, |node| match node {
+            Node::Statement(statement) => argument(statement).to_string(),
+            _ => String::new(),
+        }),
+    }
+
+    None
+}
+
+/// Why statements that are out of order weren't sorted, with the position of the statement to
+/// report
+#[derive(Debug, PartialEq, Eq)]
+pub enum Unsorted {
+    /// The statements have comments or empty lines between them, see "force_sort"
+    ManualOrdering(usize),
+    /// Moving the statements would change the value of an enum or bit, see "find_implicit_value"
+    ImplicitValue(usize),
+}
+
+/// Sorts the definitions with the given keyword by name, e.g. all the identities of a module or
+/// the typedefs of a container
+///
+/// The children are partitioned into the definitions and everything else. The other statements,
+/// like data definitions, stay where they are and the definitions only swap places with each
+/// other.
+/// The comments right above each definition are moved along with it, so unless "force" is set,
+/// definitions with comments or empty lines between them aren't sorted and the position of the
+/// first definition that's out of order is returned instead. Enums and bits are never sorted
+/// unless all of them have an explicit value.
+///
+pub fn sort_by_name(statements: &mut Vec<Node>, keyword: &str, force: bool) -> Option<Unsorted> {
+    let is_definition = |node: &Node| matches!(node, Node::Statement(statement) if statement.keyword.text() == keyword);
+
+    let mut previous = "";
+    let mut misplaced = None;
+
+    for node in statements.iter().filter(|node| is_definition(node)) {
+        let Node::Statement(statement) = node else {
+            unreachable!();
+        };
+
+        if argument(statement) < previous {
+            misplaced = Some(statement.position);
+            break;
+        }
+
+        previous = argument(statement);
+    }
+
+    let misplaced = misplaced?;
+
+    if matches!(keyword, "enum" | "bit") {
+        if let Some(position) = find_implicit_value(statements) {
+            return Some(Unsorted::ImplicitValue(position));
+        }
+    }
+
+    if has_comments_or_empty_lines(statements, is_definition) && !force {
+        return Some(Unsorted::ManualOrdering(misplaced));
+    }
+
+    // The definitions are taken out along with their comments, leaving a slot where each one was
+    let mut definitions: Vec<Vec<Node>> = vec![];
+    let mut layout: Vec<Option<Node>> = vec![];
+    let mut pending: Vec<Node> = vec![];
+
+    for node in statements.drain(..) {
+        if node.is_comment() {
+            pending.push(node);
+        } else if is_definition(&node) {
+            pending.push(node);
+            definitions.push(std::mem::take(&mut pending));
+            layout.push(None);
+        } else {
+            layout.extend(pending.drain(..).map(Some));
+            layout.push(Some(node));
+        }
+    }
+
+    layout.extend(pending.drain(..).map(Some));
+
+    definitions.sort_by(|a, b| {
+        let name = |unit: &[Node]| match unit.last() {
+            Some(Node::Statement(statement)) => argument(statement).to_string(),
+            _ => String::new(),
+        };
+
+        name(a).cmp(&name(b))
+    });
+
+    let mut definitions = definitions.into_iter();
+
+    for slot in layout {
+        match slot {
+            Some(node) => statements.push(node),
+            None => statements.extend(definitions.next().unwrap()),
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::extensions::ExtensionRule;
+    use yangfmt_parsing::{parse, ArgumentKind};
+
+    /// Parses the source and force-sorts the children of its first statement
+    fn sorted_keywords(source: &str) -> Vec<String> {
+        let mut tree = parse(source.as_bytes()).unwrap();
+
+        let Some(Node::Statement(statement)) = tree.children.first_mut() else {
+            panic!("Expected a statement");
+        };
+
+        let keyword = statement.keyword.text().to_string();
+        let children = statement.children.as_mut().unwrap();
+
+        sort_statements(Some(&keyword), children, true, &ExtensionRules::new());
+
+        children
+            .iter()
+            .map(|node| match node {
+                Node::Statement(statement) => statement.keyword.text().to_string(),
+                Node::Comment(_) => "(comment)".to_string(),
+                Node::EmptyLine(_) => "(empty)".to_string(),
+                Node::Raw(_) => "(raw)".to_string(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_sort_leaf() {
+        assert_eq!(
+            vec!["when", "type", "mandatory", "description"],
+            sorted_keywords("leaf a { description x; mandatory true; type string; when y; }"),
+        );
+    }
+
+    #[test]
+    fn test_sort_container() {
+        assert_eq!(
+            vec![
+                "must",
+                "presence",
+                "description",
+                "leaf",
+                "container",
+                "leaf",
+                "action"
+            ],
+            sorted_keywords(
+                "container a { leaf b; action c; container d; description x; leaf e; must y;
+                 presence z; }"
+            ),
+        );
+    }
+
+    #[test]
+    fn test_sort_list() {
+        assert_eq!(
+            vec![
+                "key",
+                "ordered-by",
+                "description",
+                "typedef",
+                "leaf",
+                "notification"
+            ],
+            sorted_keywords(
+                "list a { notification b; leaf c; typedef d; description x; ordered-by user;
+                 key c; }"
+            ),
+        );
+    }
+
+    #[test]
+    fn test_sort_typedef() {
+        assert_eq!(
+            vec!["type", "units", "default", "description"],
+            sorted_keywords("typedef a { description x; default 1; units s; type uint8; }"),
+        );
+    }
+
+    #[test]
+    fn test_sort_choice_and_case() {
+        assert_eq!(
+            vec!["default", "mandatory", "description", "case", "leaf"],
+            sorted_keywords(
+                "choice a { case b; description x; leaf c; mandatory false; default b; }"
+            ),
+        );
+        assert_eq!(
+            vec!["when", "description", "leaf", "uses"],
+            sorted_keywords("case a { leaf b; description x; uses c; when y; }"),
+        );
+    }
+
+    #[test]
+    fn test_sort_rpc_and_action() {
+        assert_eq!(
+            vec!["description", "grouping", "input", "output"],
+            sorted_keywords("rpc a { output; input; grouping b; description x; }"),
+        );
+        assert_eq!(
+            vec!["if-feature", "input", "output"],
+            sorted_keywords("action a { output; input; if-feature b; }"),
+        );
+    }
+
+    #[test]
+    fn test_sort_notification() {
+        assert_eq!(
+            vec!["if-feature", "status", "leaf", "container"],
+            sorted_keywords(
+                "notification a { leaf b; status current; container c; if-feature d; }"
+            ),
+        );
+    }
+
+    #[test]
+    fn test_sort_grouping() {
+        assert_eq!(
+            vec!["description", "typedef", "leaf", "uses", "action"],
+            sorted_keywords("grouping a { leaf b; action c; uses d; typedef e; description x; }"),
+        );
+    }
+
+    #[test]
+    fn test_sort_type() {
+        assert_eq!(
+            vec!["length", "pattern", "pattern"],
+            sorted_keywords("type string { pattern a; pattern b; length 1..8; }"),
+        );
+        assert_eq!(
+            vec!["range", "fraction-digits"],
+            sorted_keywords("type decimal64 { fraction-digits 2; range 0..1; }"),
+        );
+        assert_eq!(
+            vec!["path", "require-instance"],
+            sorted_keywords("type leafref { require-instance false; path ../a; }"),
+        );
+        assert_eq!(
+            vec!["enum", "enum", "enum"],
+            sorted_keywords("type enumeration { enum c; enum a; enum b; }"),
+        );
+    }
+
+    #[test]
+    fn test_sort_refine() {
+        assert_eq!(
+            vec![
+                "if-feature",
+                "must",
+                "default",
+                "config",
+                "max-elements",
+                "description"
+            ],
+            sorted_keywords(
+                "refine a { description x; max-elements 3; config false; default b; must c; \
+                 if-feature d; }"
+            ),
+        );
+    }
+
+    #[test]
+    fn test_sort_deviation_and_deviate() {
+        assert_eq!(
+            vec!["description", "reference", "deviate"],
+            sorted_keywords(
+                "deviation /a:b { deviate not-supported; reference x; description y; }"
+            ),
+        );
+        assert_eq!(
+            vec!["units", "must", "default", "mandatory"],
+            sorted_keywords("deviate add { mandatory true; default 1; must a; units s; }"),
+        );
+        assert_eq!(
+            vec!["type", "units", "config"],
+            sorted_keywords("deviate replace { config false; units s; type uint8; }"),
+        );
+    }
+
+    #[test]
+    fn test_hoist_conditions() {
+        assert_eq!(
+            vec!["when", "if-feature", "ex:foo", "refine"],
+            sorted_keywords("uses a { ex:foo; if-feature c; refine b; when d; }"),
+        );
+        assert_eq!(
+            vec!["if-feature", "leaf", "ex:foo", "leaf"],
+            sorted_keywords("augment /a { leaf b; ex:foo; leaf c; if-feature d; }"),
+        );
+        assert_eq!(
+            vec!["(comment)", "when", "ex:foo", "leaf"],
+            sorted_keywords(
+                "case a {
+                   ex:foo;
+                   leaf b;
+                   // Only for c
+                   when c;
+                 }"
+            ),
+        );
+
+        // Without "force", conditions aren't moved past comments or empty lines
+        let source = "augment /a {\n  leaf b;\n\n  if-feature c;\n}";
+        let mut tree = parse(source.as_bytes()).unwrap();
+
+        let Some(Node::Statement(augment)) = tree.children.first_mut() else {
+            panic!("Expected a statement");
+        };
+
+        let children = augment.children.as_mut().unwrap();
+
+        assert_eq!(
+            Some(source.find("if-feature").unwrap()),
+            sort_statements(Some("augment"), children, false, &ExtensionRules::new()),
+        );
+        assert!(matches!(children[0], Node::Statement(ref s) if s.keyword.text() == "leaf"));
+    }
+
+    #[test]
+    fn test_move_documentation_last() {
+        let moved = |source: &str, force| {
+            let mut tree = parse(source.as_bytes()).unwrap();
+
+            let Some(Node::Statement(statement)) = tree.children.first_mut() else {
+                panic!("Expected a statement");
+            };
+
+            let children = statement.children.as_mut().unwrap();
+            move_documentation_last(
+                Some(statement.keyword.text()),
+                children,
+                force,
+                &ExtensionRules::new(),
+            );
+
+            children
+                .iter()
+                .map(|node| match node {
+                    Node::Statement(statement) => statement.keyword.text().to_string(),
+                    Node::Comment(_) => "(comment)".to_string(),
+                    _ => "(empty)".to_string(),
+                })
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            vec![
+                "status",
+                "description",
+                "reference",
+                "leaf",
+                "(empty)",
+                "ex:foo"
+            ],
+            moved(
+                "container a {
+                   description x;
+                   leaf b;
+
+                   reference y;
+                   ex:foo;
+                   status current;
+                 }",
+                false
+            ),
+        );
+
+        let source = "leaf a {
+                        // Explains the leaf
+                        description x;
+                        type string;
+                      }";
+
+        assert_eq!(
+            vec!["(comment)", "description", "type"],
+            moved(source, false)
+        );
+        assert_eq!(
+            vec!["type", "(comment)", "description"],
+            moved(source, true)
+        );
+    }
+
+    #[test]
+    fn test_move_documentation_last_with_extensions() {
+        let extensions = ExtensionRules::from([(
+            "ex:foo".to_string(),
+            ExtensionRule {
+                argument: ArgumentKind::Unknown,
+                placement: Placement::Last,
+                single_line: false,
+                children: false,
+            },
+        )]);
+
+        let mut tree = parse("container a {\n  ex:foo;\n  description x;\n}".as_bytes()).unwrap();
+
+        let Some(Node::Statement(container)) = tree.children.first_mut() else {
+            panic!("Expected a statement");
+        };
+
+        let children = container.children.as_mut().unwrap();
+
+        // Without the rule there's no telling where "ex:foo" goes, so it would be left alone
+        move_documentation_last(Some("container"), children, false, &extensions);
+
+        assert!(matches!(children[0], Node::Statement(ref s) if s.keyword.text() == "description"));
+    }
+
+    #[test]
+    fn test_sort_module_header() {
+        assert_eq!(
+            vec![
+                "yang-version",
+                "namespace",
+                "prefix",
+                "import",
+                "(empty)",
+                "include",
+                "organization",
+                "description",
+                "revision",
+                "revision",
+                "(empty)",
+                "leaf",
+                "description",
+                "typedef",
+            ],
+            sorted_keywords(
+                "module a {
+                   revision 2024-02-01;
+                   import d;
+                   prefix f;
+                   description x;
+
+                   revision 2024-01-01;
+                   include g;
+                   organization y;
+                   namespace e;
+                   yang-version 1.1;
+
+                   leaf b;
+                   description z;
+                   typedef c;
+                 }"
+            ),
+        );
+    }
+
+    #[test]
+    fn test_sort_leaves_unknown_statements_alone() {
+        assert_eq!(
+            vec!["description", "type", "ex:foo"],
+            sorted_keywords("leaf a { description x; type string; ex:foo; }"),
+        );
+    }
+
+    #[test]
+    fn test_sort_moves_comments_with_statements() {
+        assert_eq!(
+            vec![
+                "(comment)",
+                "(comment)",
+                "type",
+                "(empty)",
+                "(comment)",
+                "description",
+                "(comment)"
+            ],
+            sorted_keywords(
+                "leaf a {
+                   // About the description
+                   description x;
+
+                   // About the type
+                   /* Really */
+                   type string; // Post-comment
+                   // Trailing comment
+                 }"
+            ),
+        );
+    }
+
+    #[test]
+    fn test_sort_preserves_blank_line_groups() {
+        assert_eq!(
+            vec![
+                "when",
+                "type",
+                "(empty)",
+                "default",
+                "description",
+                "reference"
+            ],
+            sorted_keywords(
+                "leaf a {
+                   type string;
+                   when x;
+
+                   reference y;
+                   default z;
+                   description w;
+                 }"
+            ),
+        );
+        assert_eq!(
+            vec!["type", "mandatory", "(empty)", "description"],
+            sorted_keywords(
+                "leaf a {
+                   type string;
+                   description x;
+
+                   mandatory true;
+                 }"
+            ),
+        );
+    }
+
+    #[test]
+    fn test_find_order_violations() {
+        let tree =
+            parse(b"leaf a { description x; type string; ex:foo; units s; status current; }")
+                .unwrap();
+
+        let Some(Node::Statement(leaf)) = tree.children.first() else {
+            panic!("Expected a statement");
+        };
+
+        let violations: Vec<(&str, &str)> = find_order_violations(
+            Some("leaf"),
+            leaf.children.as_ref().unwrap(),
+            &ExtensionRules::new(),
+        )
+        .into_iter()
+        .map(|(statement, before)| (statement.keyword.text(), before.keyword.text()))
+        .collect();
+
+        assert_eq!(
+            vec![
+                ("type", "description"),
+                ("units", "description"),
+                ("status", "description")
+            ],
+            violations
+        );
+    }
+
+    #[test]
+    fn test_sort_only_safe_lists_unless_forced() {
+        let mut tree = parse(b"leaf a { description x; type string; }").unwrap();
+
+        let Some(Node::Statement(leaf)) = tree.children.first_mut() else {
+            panic!("Expected a statement");
+        };
+
+        assert_eq!(
+            None,
+            sort_statements(
+                Some("leaf"),
+                leaf.children.as_mut().unwrap(),
+                false,
+                &ExtensionRules::new()
+            )
+        );
+        assert!(is_sorted(
+            &LEAF_CANONICAL_ORDER,
+            &ExtensionRules::new(),
+            leaf.children.as_ref().unwrap()
+        ));
+
+        let source = "leaf a {\n  description x;\n\n  type string;\n}";
+        let mut tree = parse(source.as_bytes()).unwrap();
+
+        let Some(Node::Statement(leaf)) = tree.children.first_mut() else {
+            panic!("Expected a statement");
+        };
+
+        assert_eq!(
+            Some(source.find("type").unwrap()),
+            sort_statements(
+                Some("leaf"),
+                leaf.children.as_mut().unwrap(),
+                false,
+                &ExtensionRules::new()
+            )
+        );
+        assert!(!is_sorted(
+            &LEAF_CANONICAL_ORDER,
+            &ExtensionRules::new(),
+            leaf.children.as_ref().unwrap()
+        ));
+    }
+
+    #[test]
+    fn test_group_imports_and_includes() {
+        assert_eq!(
+            vec!["prefix", "import", "import", "(empty)", "include", "include", "revision"],
+            sorted_keywords(
+                "module a { include c; import z; prefix a; include b; import y; revision 1; }"
+            ),
+        );
+
+        let mut tree =
+            parse(b"module a { prefix a; include c; import z { prefix z; } include b; import y; }")
+                .unwrap();
+
+        let Some(Node::Statement(module)) = tree.children.first_mut() else {
+            panic!("Expected a statement");
+        };
+
+        let children = module.children.as_mut().unwrap();
+
+        sort_statements(Some("module"), children, false, &ExtensionRules::new());
+
+        let names: Vec<String> = children
+            .iter()
+            .filter_map(|node| match node.node_value() {
+                Some(NodeValue::Other(text)) => Some(text.clone()),
+                _ => None,
+            })
+            .collect();
+
+        assert_eq!(vec!["a", "y", "z", "b", "c"], names);
+    }
+
+    #[test]
+    fn test_group_imports_and_includes_only_if_safe() {
+        let source = "module a {\n  prefix a;\n  import z;\n\n  import y;\n}";
+        let mut tree = parse(source.as_bytes()).unwrap();
+
+        let Some(Node::Statement(module)) = tree.children.first_mut() else {
+            panic!("Expected a statement");
+        };
+
+        let children = module.children.as_mut().unwrap();
+
+        assert_eq!(
+            Some(source.find("import y").unwrap()),
+            sort_statements(Some("module"), children, false, &ExtensionRules::new())
+        );
+        assert_eq!(
+            vec![
+                source.find("prefix").unwrap(),
+                source.find("import z").unwrap(),
+                source.find("import y").unwrap(),
+            ],
+            statement_positions(children)
+        );
+
+        // Forced, the empty line between the imports is removed to keep the group together
+        assert_eq!(
+            vec!["prefix", "import", "import"],
+            sorted_keywords("module a {\n  prefix a;\n  import z;\n\n  import y;\n}"),
+        );
+    }
+
+    #[test]
+    fn test_sort_revisions() {
+        let mut tree = parse(
+            b"module a {
+                revision 2020-01-01 { description old; }
+                // Newest
+                revision 2024-01-01 { description new; }
+
+                revision 2022-01-01;
+                leaf b;
+              }",
+        )
+        .unwrap();
+
+        let Some(Node::Statement(module)) = tree.children.first_mut() else {
+            panic!("Expected a statement");
+        };
+
+        let children = module.children.as_mut().unwrap();
+
+        let Node::Statement(ref misplaced) = children[2] else {
+            panic!("Expected a statement");
+        };
+        let position = misplaced.position;
+
+        // The comment and the empty line may mean the revisions are ordered on purpose
+        assert_eq!(
+            Some(Unsorted::ManualOrdering(position)),
+            sort_revisions(children, false)
+        );
+        assert_eq!(None, sort_revisions(children, true));
+
+        let nodes: Vec<&str> = children
+            .iter()
+            .map(|node| match node {
+                Node::Comment(_) => "(comment)",
+                Node::EmptyLine(_) => "(empty)",
+                Node::Statement(statement) if statement.keyword.text() == "leaf" => "leaf",
+                node => revision_date(node),
+            })
+            .collect();
+
+        assert_eq!(
+            vec![
+                "(comment)",
+                "2024-01-01",
+                "2022-01-01",
+                "(empty)",
+                "2020-01-01",
+                "leaf"
+            ],
+            nodes
+        );
+    }
+
+    #[test]
+    fn test_sort_enums() {
+        let source = "type enumeration {
+                        enum b { value 3; }
+                        // One
+                        enum c { value 1; }
+                        enum a { value 2; }
+                      }";
+
+        let sorted = |sorting| {
+            let mut tree = parse(source.as_bytes()).unwrap();
+
+            let Some(Node::Statement(statement)) = tree.children.first_mut() else {
+                panic!("Expected a statement");
+            };
+
+            let children = statement.children.as_mut().unwrap();
+            sort_enums(children, sorting, true);
+
+            children
+                .iter()
+                .map(|node| match node {
+                    Node::Statement(statement) => argument(statement).to_string(),
+                    _ => "(comment)".to_string(),
+                })
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(vec!["b", "(comment)", "c", "a"], sorted(EnumSorting::None));
+        assert_eq!(vec!["(comment)", "c", "a", "b"], sorted(EnumSorting::Value));
+        assert_eq!(vec!["a", "b", "(comment)", "c"], sorted(EnumSorting::Name));
+
+        // The comment may mean the enums are ordered on purpose
+        let mut tree = parse(source.as_bytes()).unwrap();
+
+        let Some(Node::Statement(statement)) = tree.children.first_mut() else {
+            panic!("Expected a statement");
+        };
+
+        let children = statement.children.as_mut().unwrap();
+
+        let Node::Statement(ref misplaced) = children[2] else {
+            panic!("Expected a statement");
+        };
+        let position = misplaced.position;
+
+        assert_eq!(
+            Some(Unsorted::ManualOrdering(position)),
+            sort_enums(children, EnumSorting::Value, false)
+        );
+    }
+
+    #[test]
+    fn test_sort_by_name() {
+        let source = "module a {
+                        identity c;
+                        feature z;
+                        identity a;
+
+                        identity b;
+                      }";
+
+        let mut tree = parse(source.as_bytes()).unwrap();
+
+        let Some(Node::Statement(module)) = tree.children.first_mut() else {
+            panic!("Expected a statement");
+        };
+
+        let children = module.children.as_mut().unwrap();
+
+        let Node::Statement(ref misplaced) = children[2] else {
+            panic!("Expected a statement");
+        };
+        let position = misplaced.position;
+
+        // The empty line may group the identities on purpose
+        assert_eq!(
+            Some(Unsorted::ManualOrdering(position)),
+            sort_by_name(children, "identity", false)
+        );
+        assert_eq!(None, sort_by_name(children, "identity", true));
+
+        let nodes: Vec<String> = children
+            .iter()
+            .map(|node| match node {
+                Node::Statement(statement) => {
+                    format!("{} {}", statement.keyword.text(), argument(statement))
+                }
+                _ => "(empty)".to_string(),
+            })
+            .collect();
+
+        assert_eq!(
+            vec![
+                "identity a",
+                "feature z",
+                "identity b",
+                "(empty)",
+                "identity c"
+            ],
+            nodes
+        );
+    }
+
+    #[test]
+    fn test_sort_by_name_with_comments() {
+        let source = "module a {
+                        // Cee
+                        identity c;
+                        identity a;
+                      }";
+
+        let mut tree = parse(source.as_bytes()).unwrap();
+
+        let Some(Node::Statement(module)) = tree.children.first_mut() else {
+            panic!("Expected a statement");
+        };
+
+        let children = module.children.as_mut().unwrap();
+        let Node::Statement(ref misplaced) = children[2] else {
+            panic!("Expected a statement");
+        };
+        let position = misplaced.position;
+
+        assert_eq!(
+            Some(Unsorted::ManualOrdering(position)),
+            sort_by_name(children, "identity", false)
+        );
+        assert!(children[0].is_comment());
+
+        assert_eq!(None, sort_by_name(children, "identity", true));
+        assert!(children[1].is_comment());
+        assert!(matches!(children[2], Node::Statement(ref s) if argument(s) == "c"));
+    }
+
+    #[test]
+    fn test_sort_by_name_with_implicit_values() {
+        let source = "type enumeration {
+                        enum up {
+                          value 1;
+                        }
+                        enum down;
+                      }";
+
+        let mut tree = parse(source.as_bytes()).unwrap();
+
+        let Some(Node::Statement(enumeration)) = tree.children.first_mut() else {
+            panic!("Expected a statement");
+        };
+
+        let children = enumeration.children.as_mut().unwrap();
+        let Node::Statement(ref implicit) = children[1] else {
+            panic!("Expected a statement");
+        };
+        let position = implicit.position;
+
+        // Even with "force", since moving "down" first would change its value from 2 to 0
+        assert_eq!(
+            Some(Unsorted::ImplicitValue(position)),
+            sort_by_name(children, "enum", true)
+        );
+        assert!(matches!(children[0], Node::Statement(ref s) if argument(s) == "up"));
     }
 }
@@ -0,0 +1,164 @@
+//! Helpers for normalizing the text of comments.
+//!
+//! The functions in this module operate on the full comment text, including the comment markers.
+
+use std::borrow::Cow;
+
+/// Characters used to draw banners and boxes in comments
+const DECORATION_CHARS: &[char] = &['/', '*', '=', '#', '-', '+', '~', '_', '|'];
+
+/// Returns true if the comment is (part of) a decorative banner or box, such as:
+///
+///     ////////////////////////
+///     //==== Section ====
+///     // Section           //
+///
+///     /*********************
+///      * Section           *
+///      *********************/
+///
+/// Banners are exempt from all comment normalization and reflowing.
+///
+pub fn is_banner(comment: &str) -> bool {
+    if comment.lines().any(is_decoration_line) {
+        return true;
+    }
+
+    let Some(content) = comment.strip_prefix("//") else {
+        return false;
+    };
+
+    let content = content.trim();
+
+    // A title between decorations, like "==== Section ===="
+    let is_title = content.starts_with(DECORATION_CHARS)
+        && content.ends_with(DECORATION_CHARS)
+        && content
+            .chars()
+            .any(|c| !c.is_whitespace() && !DECORATION_CHARS.contains(&c));
+
+    content.starts_with('/') || content.ends_with("//") || is_title
+}
+
+/// Returns true for lines made up of at least five decoration characters and whitespace, not
+/// counting the comment markers, e.g. "////////" or "/* ===== */", but not "/**", "/**/" or a
+/// short separator like "// ---"
+fn is_decoration_line(line: &str) -> bool {
+    let line = line.trim();
+    let line = line.strip_suffix("*/").unwrap_or(line);
+    let line = line
+        .strip_prefix("//")
+        .or_else(|| line.strip_prefix("/*"))
+        .unwrap_or(line);
+
+    let chars: Vec<char> = line.chars().filter(|c| !c.is_whitespace()).collect();
+
+    chars.len() >= 5 && chars.iter().all(|c| DECORATION_CHARS.contains(c))
+}
+
+/// Makes sure there's a space after the "//" or "/*" comment marker, and before "*/"
+///
+/// Banners (see "is_banner") and comments that look like commented-out code are left alone.
+///
+pub fn space_comment_markers(comment: &str) -> Cow<'_, str> {
+    if is_banner(comment) {
+        return Cow::Borrowed(comment);
+    }
+
+    if let Some(content) = comment.strip_prefix("//") {
+        if !needs_spacing(content) || content.starts_with(char::is_whitespace) {
+            return Cow::Borrowed(comment);
+        }
+
+        return Cow::Owned(format!("// {content}"));
+    }
+
+    let Some(content) = comment
+        .strip_prefix("/*")
+        .and_then(|text| text.strip_suffix("*/"))
+    else {
+        return Cow::Borrowed(comment);
+    };
+
+    if !needs_spacing(content) {
+        return Cow::Borrowed(comment);
+    }
+
+    let spaced = |c: char| c.is_whitespace() || c == '*';
+    let open = if content.starts_with(spaced) {
+        "/*"
+    } else {
+        "/* "
+    };
+    let close = if content.ends_with(spaced) {
+        "*/"
+    } else {
+        " */"
+    };
+
+    if open.len() == 2 && close.len() == 2 {
+        return Cow::Borrowed(comment);
+    }
+
+    Cow::Owned(format!("{open}{content}{close}"))
+}
+
+/// Returns false for empty comments and comments that look like commented-out code
+fn needs_spacing(content: &str) -> bool {
+    let trimmed = content.trim();
+
+    !trimmed.is_empty() && !trimmed.ends_with([';', '{', '}'])
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_space_comment_markers() {
+        assert_eq!("// comment", space_comment_markers("//comment"));
+        assert_eq!("// comment", space_comment_markers("// comment"));
+        assert_eq!("/* text */", space_comment_markers("/*text*/"));
+        assert_eq!("/* text */", space_comment_markers("/* text*/"));
+        assert_eq!("/** text */", space_comment_markers("/** text*/"));
+        assert_eq!("/* a\n   b */", space_comment_markers("/*a\n   b*/"));
+        assert_eq!("/*\n * a\n */", space_comment_markers("/*\n * a\n */"));
+    }
+
+    #[test]
+    fn test_is_banner() {
+        assert!(is_banner("////////"));
+        assert!(is_banner("// -------"));
+        assert!(is_banner("//==== Section ===="));
+        assert!(is_banner("/////// Section"));
+        assert!(is_banner("// Section          //"));
+        assert!(is_banner("/*******/"));
+        assert!(is_banner("/* ===== */"));
+        assert!(is_banner("/********\n * Section *\n ********/"));
+
+        assert!(!is_banner("// Comment"));
+        assert!(!is_banner("//"));
+        assert!(!is_banner("/**/"));
+        assert!(!is_banner("// - Bullet"));
+        assert!(!is_banner("// ---"));
+        assert!(!is_banner("/* -- */"));
+        assert!(!is_banner("/* Comment */"));
+        assert!(!is_banner("/**\n * Doc comment\n */"));
+    }
+
+    #[test]
+    fn test_space_comment_markers_ignored() {
+        assert_eq!("////////", space_comment_markers("////////"));
+        assert_eq!("//-------", space_comment_markers("//-------"));
+        assert_eq!("/////// Banner", space_comment_markers("/////// Banner"));
+        assert_eq!("/*******/", space_comment_markers("/*******/"));
+        assert_eq!("//leaf foo;", space_comment_markers("//leaf foo;"));
+        assert_eq!(
+            "//container bar {",
+            space_comment_markers("//container bar {")
+        );
+        assert_eq!("/*}*/", space_comment_markers("/*}*/"));
+        assert_eq!("//", space_comment_markers("//"));
+    }
+}
@@ -0,0 +1,59 @@
+//! Helpers for the date arguments of "revision" and "revision-date" statements.
+
+use regex::Regex;
+
+lazy_static! {
+    /// A loosely written date, e.g. "2024-1-05"
+    static ref LOOSE_DATE_PATTERN: Regex = Regex::new(r"^(\d{4})-(\d{1,2})-(\d{1,2})$").unwrap();
+}
+
+/// Normalizes a date into the YYYY-MM-DD format, padding the month and day with zeros
+///
+/// Returns an error message if the text isn't a date, or if it's not a valid calendar date.
+///
+pub fn normalize_date(text: &str) -> Result<String, String> {
+    let Some(captures) = LOOSE_DATE_PATTERN.captures(text) else {
+        return Err(format!("{text:?} is not a date in the YYYY-MM-DD format"));
+    };
+
+    let year: u32 = captures[1].parse().unwrap();
+    let month: u32 = captures[2].parse().unwrap();
+    let day: u32 = captures[3].parse().unwrap();
+
+    if !(1..=12).contains(&month) || day < 1 || day > days_in_month(year, month) {
+        return Err(format!("{text:?} is not a valid calendar date"));
+    }
+
+    Ok(format!("{year:04}-{month:02}-{day:02}"))
+}
+
+fn days_in_month(year: u32, month: u32) -> u32 {
+    match month {
+        2 if year.is_multiple_of(4) && (!year.is_multiple_of(100) || year.is_multiple_of(400)) => {
+            29
+        }
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_normalize_date() {
+        assert_eq!(Ok("2024-01-05".to_string()), normalize_date("2024-1-05"));
+        assert_eq!(Ok("2024-02-29".to_string()), normalize_date("2024-02-29"));
+        assert_eq!(
+            Err("\"2023-02-29\" is not a valid calendar date".to_string()),
+            normalize_date("2023-02-29")
+        );
+        assert!(normalize_date("1900-02-29").is_err());
+        assert!(normalize_date("2024-13-01").is_err());
+        assert!(normalize_date("2024-04-31").is_err());
+        assert!(normalize_date("yesterday").is_err());
+    }
+}
@@ -0,0 +1,163 @@
+//! Documentation of the diagnostics reported while formatting, printed by "yangfmt explain".
+
+/// Documentation of a diagnostic code
+#[derive(Debug)]
+pub struct Explanation {
+    /// The code shown along with the diagnostic, e.g. "manual-ordering"
+    pub code: &'static str,
+    /// One line description of the problem
+    pub summary: &'static str,
+    /// Why it's reported and what to do about it
+    pub rationale: &'static str,
+    /// Source with the problem, and the same source with the problem fixed
+    pub example: Option<(&'static str, &'static str)>,
+}
+
+/// The codes of every diagnostic reported while formatting
+pub const EXPLANATIONS: &[Explanation] = &[
+    Explanation {
+        code: "canonical-order",
+        summary: "A statement is out of the canonical order",
+        rationale: "RFC 7950 gives an order for the sub-statements of each statement, and the \
+                    IETF guidelines ask for it to be followed. This is reported by \
+                    --check-canonical-order, run with --canonical-order to fix it.",
+        example: Some((
+            "leaf mtu {\n  description \"The MTU\";\n  type uint16;\n}",
+            "leaf mtu {\n  type uint16;\n  description \"The MTU\";\n}",
+        )),
+    },
+    Explanation {
+        code: "manual-ordering",
+        summary: "Statements should be sorted, but have comments or empty lines between them",
+        rationale: "Comments and empty lines usually belong to the statement below them, but not \
+                    always, so statements separated by them aren't sorted automatically. Move \
+                    the statements by hand, or run with --force-sort to sort them anyway with \
+                    the comments attached to the statement below.",
+        example: Some((
+            "// Base identity\nidentity zeta;\nidentity alpha;",
+            "identity alpha;\n// Base identity\nidentity zeta;",
+        )),
+    },
+    Explanation {
+        code: "implicit-values",
+        summary: "Enums or bits should be sorted, but some don't have an explicit value",
+        rationale: "An enum without a \"value\" statement, or a bit without a \"position\" \
+                    statement, gets the value after the highest one before it. Moving it would \
+                    silently change its value, and with it what's sent on the wire. Give every \
+                    enum or bit an explicit value to sort them.",
+        example: Some((
+            "enum zeta;\nenum alpha;",
+            "enum alpha {\n  value 1;\n}\nenum zeta {\n  value 0;\n}",
+        )),
+    },
+    Explanation {
+        code: "invalid-date",
+        summary: "A revision date isn't a valid calendar date",
+        rationale: "Dates are normalized to the YYYY-MM-DD format, but dates that don't exist, \
+                    like February 29th in a year that isn't a leap year, can't be fixed automatically.",
+        example: Some((
+            "revision 2023-02-29;",
+            "revision 2023-02-28;",
+        )),
+    },
+    Explanation {
+        code: "non-printable-character",
+        summary: "A string contains a non-printable character",
+        rationale: "Invisible characters, like non-breaking spaces and control characters, are \
+                    usually pasted in by accident and cause confusing differences between \
+                    strings that look the same. Run with --non-printable replace to replace the \
+                    invisible ones with their printable look-alikes.",
+        example: Some((
+            "description \"Maximum\u{a0}size\"; // U+00A0 between the words",
+            "description \"Maximum size\";",
+        )),
+    },
+    Explanation {
+        code: "no-sub-statements",
+        summary: "An extension statement has sub-statements, but its rule says it can't",
+        rationale: "The extension rules in the config file say which extension statements take \
+                    sub-statements. Empty blocks are removed automatically, anything else has \
+                    to be moved or the rule corrected.",
+        example: Some((
+            "md:annotation last-modified {\n  type string;\n}",
+            "md:annotation last-modified;",
+        )),
+    },
+    Explanation {
+        code: "long-line",
+        summary: "A line is still longer than the max width after formatting",
+        rationale: "Strings are only wrapped between words, so a long pattern, URL or other \
+                    string without spaces can't be made to fit. Split the string with \"+\", \
+                    raise the max width of the keyword, or run with --fold-long-lines to fold \
+                    the lines per RFC 8792. The line numbers refer to the formatted output.",
+        example: Some((
+            "pattern '[a-zA-Z_][a-zA-Z0-9_.-]*(:[a-zA-Z_][a-zA-Z0-9_.-]*)?(/[a-zA-Z0-9_.-]+)*';",
+            "pattern '[a-zA-Z_][a-zA-Z0-9_.-]*(:[a-zA-Z_][a-zA-Z0-9_.-]*)?'\n      + '(/[a-zA-Z0-9_.-]+)*';",
+        )),
+    },
+    Explanation {
+        code: "unfoldable-lines",
+        summary: "The long lines can't be folded per RFC 8792",
+        rationale: "Single backslash folding marks folded lines with a backslash at the end, so \
+                    it can't be used when a line already ends with one. Continuation lines \
+                    can't start with whitespace either, so a line with nothing but whitespace \
+                    where it would be folded can't be folded. The output is written without \
+                    folding.",
+        example: None,
+    },
+    Explanation {
+        code: "blank-lines-removed",
+        summary: "Blank lines were removed while sorting statements (with --verbose)",
+        rationale: "Sorting keeps blank lines where they were when it can, but with \
+                    --force-sort blank lines between statements that are grouped while \
+                    sorting, like imports, are dropped. Check that no meaningful grouping was \
+                    lost.",
+        example: Some((
+            "import b { prefix b; }\n\nimport a { prefix a; }",
+            "import a { prefix a; }\nimport b { prefix b; }",
+        )),
+    },
+    Explanation {
+        code: "comment-moved",
+        summary: "A comment inside a statement was moved (with --verbose)",
+        rationale: "Comments between the keyword and the value of a statement, or between the \
+                    value and the opening brace, can't stay where they are when the statement \
+                    is reformatted. They're moved after the opening brace or the semicolon.",
+        example: Some((
+            "leaf mtu /* bytes */ {\n  type uint16;\n}",
+            "leaf mtu { /* bytes */\n  type uint16;\n}",
+        )),
+    },
+    Explanation {
+        code: "string-emptied",
+        summary: "A string with only whitespace was emptied (with --verbose)",
+        rationale: "Leading and trailing whitespace is stripped from strings, so a string with \
+                    nothing but whitespace becomes an empty string. Use --preserve-strings if \
+                    the whitespace matters.",
+        example: Some(("description \"   \";", "description \"\";")),
+    },
+];
+
+/// Returns the documentation of the diagnostic code
+pub fn explanation(code: &str) -> Option<&'static Explanation> {
+    EXPLANATIONS
+        .iter()
+        .find(|explanation| explanation.code == code)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn every_code_is_explained_once() {
+        for explanation in EXPLANATIONS {
+            let count = EXPLANATIONS
+                .iter()
+                .filter(|other| other.code == explanation.code)
+                .count();
+
+            assert_eq!(1, count, "{} is explained {count} times", explanation.code);
+        }
+    }
+}
@@ -0,0 +1,181 @@
+//! Formatting rules for the extension statements of well-known vendor modules.
+//!
+//! Extension statements can take any kind of argument and go anywhere, so by default they're
+//! treated conservatively: their arguments are left alone and blocks containing them aren't sorted.
+//! The rules here describe common extensions so they can be formatted like built-in statements.
+//!
+//! Rules are looked up by the full keyword, so they only apply when the extension module is
+//! imported with its usual prefix, e.g. "tailf" for tailf-common. Rules given in
+//! "FormatConfig::extensions" take precedence over the built-in ones, which lets users describe
+//! the extensions of their own modules too.
+
+use std::collections::HashMap;
+
+use yangfmt_parsing::ArgumentKind;
+use ArgumentKind::{Identifier, NoArgument, Path, Text, Unknown, XPath};
+
+/// Where an extension statement goes when its siblings are sorted in canonical order
+#[derive(Debug, Clone, PartialEq)]
+pub enum Placement {
+    /// Keep the statement where it is, which prevents its siblings from being sorted
+    Preserve,
+    First,
+    /// Right after the statements with the given keyword, e.g. "after:description"
+    After(String),
+    Last,
+}
+
+impl std::str::FromStr for Placement {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "preserve" => Ok(Self::Preserve),
+            "first" => Ok(Self::First),
+            "last" => Ok(Self::Last),
+            _ => match s.strip_prefix("after:") {
+                Some(keyword) if !keyword.is_empty() => Ok(Self::After(keyword.to_string())),
+                _ => Err(format!(
+                    "invalid placement {s:?}, expected preserve, first, last or after:KEYWORD"
+                )),
+            },
+        }
+    }
+}
+
+/// How the statements of an extension keyword are formatted
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExtensionRule {
+    /// Decides how the argument is quoted, normalized and wrapped, like for built-in statements
+    pub argument: ArgumentKind,
+    pub placement: Placement,
+    /// Write the statement on one line when it fits, see "FormatConfig::single_line_blocks"
+    pub single_line: bool,
+    /// Whether the statement may have sub-statements, empty blocks are removed when it can't
+    pub children: bool,
+}
+
+/// Extension rules by keyword, a keyword ending with "*" matches any keyword with that prefix
+pub type ExtensionRules = HashMap<String, ExtensionRule>;
+
+/// Rules for common extensions of Cisco (tailf-common), OpenConfig and Juniper modules
+///
+/// Each rule is a keyword, the kind of argument, the placement and whether it may be written on
+/// one line.
+///
+const BUILTIN_RULES: &[(&str, ArgumentKind, &str, bool)] = &[
+    ("tailf:info", Text, "after:description", false),
+    ("tailf:callpoint", Identifier, "last", true),
+    ("tailf:actionpoint", Identifier, "last", true),
+    ("tailf:hidden", Identifier, "last", false),
+    ("tailf:key-default", Text, "after:default", false),
+    ("tailf:display-when", XPath, "after:when", false),
+    ("tailf:link", Path, "last", false),
+    ("tailf:dependency", XPath, "last", false),
+    ("tailf:cli-*", Unknown, "last", false),
+    (
+        "oc-ext:openconfig-version",
+        Text,
+        "after:description",
+        false,
+    ),
+    ("oc-ext:regexp-posix", NoArgument, "after:reference", false),
+    (
+        "oc-ext:catalog-organization",
+        Text,
+        "after:reference",
+        false,
+    ),
+    ("oc-ext:origin", Text, "after:reference", false),
+    ("oc-ext:posix-pattern", Text, "after:pattern", false),
+    ("oc-ext:telemetry-on-change", NoArgument, "last", false),
+    ("oc-ext:telemetry-atomic", NoArgument, "last", false),
+    ("oc-ext:operational", NoArgument, "last", false),
+    ("junos:must", Text, "after:must", false),
+    ("junos:must-message", Text, "after:must", false),
+    ("junos:posix-pattern", Text, "after:pattern", false),
+    ("junos:pattern-message", Text, "after:pattern", false),
+];
+
+lazy_static! {
+    static ref BUILTIN_EXTENSIONS: ExtensionRules = BUILTIN_RULES
+        .iter()
+        .map(|(keyword, argument, placement, single_line)| {
+            let rule = ExtensionRule {
+                argument: *argument,
+                placement: placement.parse().unwrap(),
+                single_line: *single_line,
+                children: true,
+            };
+
+            (keyword.to_string(), rule)
+        })
+        .collect();
+}
+
+/// Returns the rule for the extension keyword, from the given rules or the built-in ones
+pub fn extension_rule<'a>(rules: &'a ExtensionRules, keyword: &str) -> Option<&'a ExtensionRule> {
+    find_rule(rules, keyword).or_else(|| find_rule(&BUILTIN_EXTENSIONS, keyword))
+}
+
+/// Looks up the rule for the keyword, preferring exact matches over the longest matching prefix
+fn find_rule<'a>(rules: &'a ExtensionRules, keyword: &str) -> Option<&'a ExtensionRule> {
+    if let Some(rule) = rules.get(keyword) {
+        return Some(rule);
+    }
+
+    rules
+        .iter()
+        .filter_map(|(pattern, rule)| {
+            let prefix = pattern.strip_suffix('*')?;
+            keyword.starts_with(prefix).then_some((prefix.len(), rule))
+        })
+        .max_by_key(|(length, _)| *length)
+        .map(|(_, rule)| rule)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_extension_rule() {
+        let rules = ExtensionRules::new();
+
+        assert_eq!(
+            Some(ArgumentKind::Text),
+            extension_rule(&rules, "tailf:info").map(|rule| rule.argument)
+        );
+        assert_eq!(
+            Some(&Placement::Last),
+            extension_rule(&rules, "tailf:cli-drop-node-name").map(|rule| &rule.placement)
+        );
+        assert_eq!(None, extension_rule(&rules, "ex:foo"));
+
+        let rules = ExtensionRules::from([(
+            "tailf:info".to_string(),
+            ExtensionRule {
+                argument: ArgumentKind::Unknown,
+                placement: Placement::Preserve,
+                single_line: false,
+                children: true,
+            },
+        )]);
+
+        assert_eq!(
+            Some(&Placement::Preserve),
+            extension_rule(&rules, "tailf:info").map(|rule| &rule.placement)
+        );
+    }
+
+    #[test]
+    fn test_parse_placement() {
+        assert_eq!(Ok(Placement::First), "first".parse());
+        assert_eq!(
+            Ok(Placement::After("description".to_string())),
+            "after:description".parse()
+        );
+        assert!("after:".parse::<Placement>().is_err());
+        assert!("middle".parse::<Placement>().is_err());
+    }
+}
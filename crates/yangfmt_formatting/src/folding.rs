@@ -0,0 +1,136 @@
+//! Folding of long lines with the single backslash strategy of RFC 8792.
+//!
+//! This is done as a last step on the written output, for lines that still don't fit within the
+//! max width, e.g. long patterns and unbreakable strings in modules that go into an RFC. The folded
+//! output is artwork for a document rather than YANG, so it can't be formatted again.
+
+/// Text of the header line, which is padded with "=" to the max width
+const HEADER: &str = "NOTE: '\\' line wrapping per RFC 8792";
+
+/// Why a text can't be folded, with the number of the offending line
+#[derive(Debug, PartialEq)]
+pub enum FoldError {
+    /// The line already ends with a backslash, which would be taken for a fold when unfolding
+    TrailingBackslash(usize),
+    /// The line only has whitespace where it would be folded, and continuation lines can't start
+    /// with whitespace
+    NoFoldingPoint(usize),
+}
+
+impl std::fmt::Display for FoldError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::TrailingBackslash(line) => write!(f, "line {line} already ends with a backslash"),
+            Self::NoFoldingPoint(line) => {
+                write!(
+                    f,
+                    "line {line} only has whitespace where it would be folded"
+                )
+            }
+        }
+    }
+}
+
+/// Folds all the lines longer than the width, and adds the RFC 8792 header to the text
+///
+/// Each folded line ends with a backslash and continues at the start of the next line. Returns
+/// None if no line is too long.
+///
+pub fn fold_long_lines(text: &str, width: usize) -> Result<Option<String>, FoldError> {
+    if text.lines().all(|line| line.chars().count() <= width) {
+        return Ok(None);
+    }
+
+    if let Some(index) = text.lines().position(|line| line.ends_with('\\')) {
+        return Err(FoldError::TrailingBackslash(index + 1));
+    }
+
+    let mut folded = header(width);
+
+    for (index, line) in text.split_inclusive('\n').enumerate() {
+        let mut chars: Vec<char> = line.chars().collect();
+        let line_break = if chars.last() == Some(&'\n') {
+            chars.pop();
+            "\n"
+        } else {
+            ""
+        };
+
+        while chars.len() > width {
+            // Leading whitespace is removed from continuation lines when unfolding, so they can't
+            // start with a space
+            let mut split = width - 1;
+
+            while split > 0 && chars[split].is_whitespace() {
+                split -= 1;
+            }
+
+            if split == 0 {
+                return Err(FoldError::NoFoldingPoint(index + 1));
+            }
+
+            folded.extend(&chars[..split]);
+            folded.push_str("\\\n");
+            chars.drain(..split);
+        }
+
+        folded.extend(&chars);
+        folded.push_str(line_break);
+    }
+
+    Ok(Some(folded))
+}
+
+/// Returns the header line, followed by the required empty line
+fn header(width: usize) -> String {
+    let filler = width.saturating_sub(HEADER.len() + 2);
+    let left = "=".repeat(filler / 2);
+    let right = "=".repeat(filler - filler / 2);
+
+    if filler == 0 {
+        format!("{HEADER}\n\n")
+    } else {
+        format!("{left} {HEADER} {right}\n\n")
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_fold_long_lines() {
+        assert_eq!(Ok(None), fold_long_lines("short\nlines\n", 10));
+        assert_eq!(
+            Err(FoldError::TrailingBackslash(2)),
+            fold_long_lines("short\na long line \\\n", 10)
+        );
+        assert_eq!(
+            Err(FoldError::NoFoldingPoint(1)),
+            fold_long_lines("a          b\n", 10)
+        );
+
+        assert_eq!(
+            Ok(Some(
+                "NOTE: '\\' line wrapping per RFC 8792\n\nabcdefghi\\\njklmn\nshort\n".to_string()
+            )),
+            fold_long_lines("abcdefghijklmn\nshort\n", 10),
+        );
+
+        // The continuation line mustn't start with a space
+        assert_eq!(
+            Ok(Some(
+                "NOTE: '\\' line wrapping per RFC 8792\n\nabcdefg  \\\nhijk\n".to_string()
+            )),
+            fold_long_lines("abcdefg  hijk\n", 10),
+        );
+    }
+
+    #[test]
+    fn test_header() {
+        assert_eq!(
+            "=============== NOTE: '\\' line wrapping per RFC 8792 ================\n\n",
+            header(69),
+        );
+    }
+}
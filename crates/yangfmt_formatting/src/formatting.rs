@@ -1,839 +1,6808 @@
-mod canonical_order;
-
-use yangfmt_parsing::{parse, Node, NodeHelpers, NodeValue, ParseError, StatementKeyword};
-
-use crate::canonical_order::sort_statements;
+#[macro_use]
+extern crate lazy_static;
 
+mod canonical_order;
+mod comments;
+mod dates;
+mod explanations;
+mod extensions;
+mod folding;
+mod if_feature;
+mod long_lines;
+mod pragmas;
+mod reflow;
+mod strings;
+mod xpath;
+
+use std::borrow::Cow;
+use std::collections::HashMap;
+
+use regex::Regex;
+use yangfmt_lexing::TokenType;
+use yangfmt_parsing::{
+    argument_kind, is_pragma_comment, parse, ArgumentKind, Diagnostic, Node, NodeHelpers,
+    NodeValue, ParseError, Statement, StatementKeyword,
+};
+
+pub use crate::explanations::{explanation, Explanation, EXPLANATIONS};
+pub use crate::extensions::{extension_rule, ExtensionRule, ExtensionRules, Placement};
+pub use crate::long_lines::find_long_lines;
+pub use crate::pragmas::{apply_pragmas, has_skip_file_pragma};
+
+use crate::canonical_order::{
+    find_order_violations, move_documentation_last, sort_by_name, sort_enums, sort_revisions,
+    sort_statements, statement_positions, Unsorted,
+};
+use crate::comments::{is_banner, space_comment_markers};
+use crate::dates::normalize_date;
+use crate::folding::fold_long_lines;
+use crate::if_feature::normalize_if_feature;
+use crate::reflow::{looks_preformatted, reflow_text};
+use crate::strings::{
+    escape_double_quoted, invisible_replacement, is_non_printable, replace_invisible,
+    split_path_segments, split_string_content, trim_line_ends, unescape_quotes_and_backslashes,
+};
+use crate::xpath::{normalize_whitespace, wrap_at_operators, wrap_xpath};
+
+#[derive(Clone)]
 pub enum Indent {
     // Tab,
     Spaces(u8),
 }
 
-pub struct FormatConfig {
-    pub indent: Indent,
-    pub line_length: u16,
-    pub fix_canonical_order: bool,
+/// Suggested keywords for "FormatConfig::blank_line_before"
+pub const DEFAULT_BLANK_LINE_BEFORE: &[&str] = &[
+    "grouping",
+    "container",
+    "list",
+    "rpc",
+    "notification",
+    "augment",
+];
+
+/// Default keywords for "FormatConfig::never_wrap"
+pub const DEFAULT_NEVER_WRAP: &[&str] = &["pattern"];
+
+/// Default keywords for "FormatConfig::verbatim"
+pub const DEFAULT_VERBATIM: &[&str] = &["contact", "organization"];
+
+/// Controls whether a statement value is written on the same line as the keyword
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ValuePlacement {
+    /// Move the value to the next line only if it doesn't fit within the max width
+    Auto,
+    AlwaysNewline,
+    NeverNewline,
 }
 
-impl FormatConfig {
-    fn indent_width(&self) -> u8 {
-        match self.indent {
-            // Indent::Tab
-            Indent::Spaces(num) => num,
+impl std::str::FromStr for ValuePlacement {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "auto" => Ok(Self::Auto),
+            "always-newline" => Ok(Self::AlwaysNewline),
+            "never-newline" => Ok(Self::NeverNewline),
+            _ => Err(format!(
+                "invalid value placement {s:?}, expected auto, always-newline or never-newline"
+            )),
         }
     }
 }
 
-#[derive(Debug)]
-pub enum Error {
-    ParseError(ParseError),
-    IOError(String),
+/// Controls where the terminating semicolon goes when a value has been moved to the next line
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SemicolonPlacement {
+    /// Right after the value
+    Attached,
+    /// Alone on the following line, indented like the value
+    NextLine,
+    /// Alone on the following line, aligned with the keyword
+    UnderKeyword,
 }
 
-impl std::fmt::Display for Error {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            Error::ParseError(parse_error) => write!(f, "{}", parse_error.message),
-            Error::IOError(text) => write!(f, "{}", text),
+impl std::str::FromStr for SemicolonPlacement {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "attached" => Ok(Self::Attached),
+            "next-line" => Ok(Self::NextLine),
+            "under-keyword" => Ok(Self::UnderKeyword),
+            _ => Err(format!(
+                "invalid semicolon placement {s:?}, expected attached, next-line or under-keyword"
+            )),
         }
     }
 }
 
-impl From<ParseError> for Error {
-    fn from(value: ParseError) -> Self {
-        Self::ParseError(value)
-    }
+/// Controls where the closing quote of a multi-line string goes
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ClosingQuotePlacement {
+    /// Right after the last line of text
+    Attached,
+    /// Alone on the following line, aligned with the opening quote. Note that this adds a line
+    /// break to the end of the string value.
+    OwnLine,
 }
 
-impl From<std::io::Error> for Error {
-    fn from(error: std::io::Error) -> Self {
-        Self::IOError(format!("I/O Error: {}", error))
+impl std::str::FromStr for ClosingQuotePlacement {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "attached" => Ok(Self::Attached),
+            "own-line" => Ok(Self::OwnLine),
+            _ => Err(format!(
+                "invalid closing quote placement {s:?}, expected attached or own-line"
+            )),
+        }
     }
 }
 
-/// Formats an input buffer of YANG source into the given output
-pub fn format_yang<T: std::io::Write>(
-    out: &mut T,
-    buffer: &[u8],
-    config: &FormatConfig,
-) -> Result<(), Error> {
-    let mut tree = parse(buffer)?;
+/// Where the plus operator goes in multi-line string concatenations
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ConcatOperatorStyle {
+    /// Start each continuation line with the plus operator
+    Leading,
+    /// End each line except the last with the plus operator
+    Trailing,
+}
 
-    process_statements(None, &mut tree.children, config);
+impl std::str::FromStr for ConcatOperatorStyle {
+    type Err = String;
 
-    for node in tree.children {
-        write_node(out, &node, config, 0)?;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "leading" => Ok(Self::Leading),
+            "trailing" => Ok(Self::Trailing),
+            _ => Err(format!(
+                "invalid concatenation operator style {s:?}, expected leading or trailing"
+            )),
+        }
     }
+}
 
-    Ok(())
+/// How the continuation lines of a multi-line string concatenation are aligned
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ConcatAlignment {
+    /// Align each string with the opening quote of the first string
+    Quote,
+    /// Indent the continuation lines by the continuation indent
+    Continuation,
+    /// Indent the continuation lines by 2 spaces relative to the keyword
+    Keyword,
 }
 
-/// Applies auto-formatting rules recursively to the input statement list
-fn process_statements(
-    parent_node_name: Option<&str>,
-    statements: &mut Vec<Node>,
-    config: &FormatConfig,
-) {
-    for node in statements.as_mut_slice() {
-        if let Node::Statement(ref mut statement) = node {
-            // Recurse into the block node's children
-            if let Some(ref mut children) = statement.children {
-                process_statements(Some(statement.keyword.text()), children, config);
-            }
+impl std::str::FromStr for ConcatAlignment {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "quote" => Ok(Self::Quote),
+            "continuation" => Ok(Self::Continuation),
+            "keyword" => Ok(Self::Keyword),
+            _ => Err(format!(
+                "invalid concatenation alignment {s:?}, expected quote, continuation or keyword"
+            )),
         }
+    }
+}
 
-        convert_to_double_quotes(node);
-        strip_string(node);
+/// Which quotes string values are normalized to
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum QuoteStyle {
+    /// Use double quotes, except where that would require escaping quotes
+    Double,
+    /// Use single quotes for strings that don't need escape sequences or contain single quotes
+    Single,
+    /// Keep the quotes as written
+    Preserve,
+}
 
-        // Multi-lined quoted strings get stripped and dedented
-        dedent_multilined_string(node);
+impl std::str::FromStr for QuoteStyle {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "double" => Ok(Self::Double),
+            "single" => Ok(Self::Single),
+            "preserve" => Ok(Self::Preserve),
+            _ => Err(format!(
+                "invalid quote style {s:?}, expected double, single or preserve"
+            )),
+        }
     }
+}
 
-    trim_line_breaks(statements);
-    squash_line_breaks(statements);
-    relocate_pre_block_comments(statements);
+/// What to do about non-printable and invisible characters in strings
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum NonPrintableCharacters {
+    /// Report them as diagnostics
+    Warn,
+    /// Replace invisible spaces with regular spaces and remove zero-width characters, report the
+    /// rest as diagnostics
+    Replace,
+    /// Leave them alone
+    Ignore,
+}
 
-    if config.fix_canonical_order {
-        sort_statements(parent_node_name, statements);
+impl std::str::FromStr for NonPrintableCharacters {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "warn" => Ok(Self::Warn),
+            "replace" => Ok(Self::Replace),
+            "ignore" => Ok(Self::Ignore),
+            _ => Err(format!(
+                "invalid non-printable character handling {s:?}, expected warn, replace or ignore"
+            )),
+        }
     }
 }
 
-/// Relocates keyword- and value comments somewhere more acceptable
-///
-/// See tests at the bottom of the file for example results.
-///
-fn relocate_pre_block_comments(nodes: &mut [Node]) {
-    for node in nodes.iter_mut() {
-        if let Node::Statement(stmt) = node {
-            // Move all keyword comments and value comments into the post comments
-            stmt.post_comments.append(&mut stmt.keyword_comments);
-            stmt.post_comments.append(&mut stmt.value_comments);
+/// How long leafref "path" arguments are wrapped
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PathWrapping {
+    /// Like any other value, see "FormatConfig::value_placement"
+    Auto,
+    /// Split paths that can't fit within the max width into concatenations at "/", like augment
+    /// targets
+    Slash,
+    /// Never move paths to the next line, regardless of the max width
+    Never,
+}
+
+impl std::str::FromStr for PathWrapping {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "auto" => Ok(Self::Auto),
+            "slash" => Ok(Self::Slash),
+            "never" => Ok(Self::Never),
+            _ => Err(format!(
+                "invalid path wrapping {s:?}, expected auto, slash or never"
+            )),
         }
     }
 }
 
-/// Removes leading and trailing line breaks from the statement list
-///
-/// Essentially converts:
-///
-///     foo {
-///
-///         bar {
-///
-///             description "Test";
-///
-///             reference "Test";
-///
-///
-///         }
-///
-///     }
-///
-/// Into:
-///
-///     foo {
-///         bar {
-///             description "Test";
-///
-///             reference "Test";
-///         }
-///     }
-///
-fn trim_line_breaks(statements: &mut Vec<Node>) {
-    while statements.get(0).is_empty_line() {
-        statements.remove(0);
-    }
+/// Whether the identifier arguments of "prefix", "belongs-to", "base", "type" and "uses" are
+/// quoted
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum IdentifierQuoting {
+    Preserve,
+    /// Always put double quotes around the identifier
+    Quoted,
+    /// Remove the quotes around the identifier
+    Bare,
+}
 
-    while statements.last().is_empty_line() {
-        statements.pop();
+impl std::str::FromStr for IdentifierQuoting {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "preserve" => Ok(Self::Preserve),
+            "quoted" => Ok(Self::Quoted),
+            "bare" => Ok(Self::Bare),
+            _ => Err(format!(
+                "invalid identifier quoting {s:?}, expected preserve, quoted or bare"
+            )),
+        }
     }
 }
 
-/// Squashes any occurrance of 3 or more line breaks down to 2 line breaks
-///
-/// Essentially converts:
-///
-///     module foo {
-///         foo "123";
-///
-///
-///
-///         bar "123";
-///     }
-///
-/// Into:
-///
-///     module foo {
-///         foo "123";
-///
-///         bar "123";
-///     }
-///
-fn squash_line_breaks(statements: &mut Vec<Node>) {
-    let mut i = 1;
+/// How the enums of an enumeration type are ordered
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum EnumSorting {
+    /// Keep the enums as written
+    None,
+    /// Sort by the numeric "value" statements
+    Value,
+    /// Sort alphabetically by name
+    Name,
+}
 
-    while let Some(node) = statements.get(i) {
-        if node.is_empty_line() && statements.get(i - 1).is_empty_line() {
-            statements.remove(i);
-            continue;
+impl std::str::FromStr for EnumSorting {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "none" => Ok(Self::None),
+            "value" => Ok(Self::Value),
+            "name" => Ok(Self::Name),
+            _ => Err(format!(
+                "invalid enum sorting {s:?}, expected none, value or name"
+            )),
         }
+    }
+}
 
-        i += 1;
+/// Output conventions of the writer, which take precedence over some of the other options
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Style {
+    Yangfmt,
+    /// Mimic the output of "pyang -f yang", to minimize the diff when switching tools
+    ///
+    /// Values are never moved to the next line, except for the texts of "description",
+    /// "reference", "contact" and "organization" which always are. String arguments are quoted,
+    /// and the statements of a module are separated into sections by blank lines.
+    Pyang,
+}
+
+impl std::str::FromStr for Style {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "yangfmt" => Ok(Self::Yangfmt),
+            "pyang" => Ok(Self::Pyang),
+            _ => Err(format!("invalid style {s:?}, expected yangfmt or pyang")),
+        }
     }
 }
 
-/// Converts single-quoted strings to double quoted strings
+/// Keywords whose values are always written on the next line by pyang
+const PYANG_NEWLINE_KEYWORDS: &[&str] = &["description", "reference", "contact", "organization"];
+
+/// Named presets of formatting options matching common YANG styles
 ///
-/// The only exception is if the string contains double-quotes.
+/// Options that are given explicitly take precedence over the profile.
 ///
-fn convert_to_double_quotes(node: &mut Node) {
-    let is_single_quoted = |str: &str| str.bytes().next().map_or(false, |byte| byte == b'\'');
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Profile {
+    Default,
+    /// IETF drafts and RFCs: narrow lines, canonical order and newest revision first
+    Ietf,
+    /// Like "Ietf", but fits modules within the 69 columns available in an RFC, folding the lines
+    /// that can't be wrapped as described in RFC 8792
+    Rfc,
+    /// The OpenConfig style guide: 80 columns and a blank line before large definitions
+    OpenConfig,
+}
 
-    let contains_quote = |str: &str| {
-        let mut content = str.chars();
-        content.next();
-        content.next_back();
+impl Profile {
+    /// Returns the default config with the profile's options applied
+    pub fn config(self) -> FormatConfig {
+        let default = FormatConfig::default();
 
-        let content = content.as_str();
+        match self {
+            Self::Default => default,
+            Self::Ietf => FormatConfig {
+                line_length: 70,
+                fix_canonical_order: true,
+                sort_revisions: true,
+                separate_definitions: true,
+                quote_style: QuoteStyle::Double,
+                ..default
+            },
+            Self::Rfc => FormatConfig {
+                line_length: 69,
+                fold_long_lines: true,
+                ..Self::Ietf.config()
+            },
+            Self::OpenConfig => FormatConfig {
+                line_length: 80,
+                quote_style: QuoteStyle::Double,
+                blank_line_before: DEFAULT_BLANK_LINE_BEFORE
+                    .iter()
+                    .map(|keyword| keyword.to_string())
+                    .collect(),
+                ..default
+            },
+        }
+    }
+}
 
-        content.contains('\"')
-    };
+impl std::str::FromStr for Profile {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "default" => Ok(Self::Default),
+            "ietf" => Ok(Self::Ietf),
+            "rfc" => Ok(Self::Rfc),
+            "openconfig" => Ok(Self::OpenConfig),
+            _ => Err(format!(
+                "invalid profile {s:?}, expected default, ietf, rfc or openconfig"
+            )),
+        }
+    }
+}
 
-    let set_double_quotes = |str: &mut String| {
-        str.replace_range(0..1, "\"");
-        str.replace_range(str.len() - 1.., "\"");
-    };
+/// Where simple values that are moved to the next line start
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum WrappedValueIndent {
+    /// One continuation indent deeper than the keyword
+    Indent,
+    /// Under the position the value would have had on the keyword line
+    Aligned,
+    /// At a fixed column, or one continuation indent deeper than the keyword if that's further right
+    Column(u16),
+}
 
-    if let Some(NodeValue::String(string)) = node.node_value_mut() {
-        if !is_single_quoted(string) || contains_quote(string) {
-            return;
-        }
+impl std::str::FromStr for WrappedValueIndent {
+    type Err = String;
 
-        set_double_quotes(string);
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "indent" => Ok(Self::Indent),
+            "aligned" => Ok(Self::Aligned),
+            _ => s.parse().map(Self::Column).map_err(|_| {
+                format!("invalid wrapped value indent {s:?}, expected indent, aligned or a column")
+            }),
+        }
     }
+}
 
-    if let Some(NodeValue::StringConcatenation(strings)) = node.node_value_mut() {
-        for (ref mut string, _) in strings {
-            if !is_single_quoted(string) || contains_quote(string) {
-                continue;
-            }
+/// Which line breaks to write
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LineEnding {
+    /// Use the line ending most common in the input, "\n" if there are no line breaks
+    Auto,
+    Lf,
+    Crlf,
+}
+
+impl LineEnding {
+    /// Returns the concrete line ending to use for the given input
+    pub fn resolve(self, buffer: &[u8]) -> Self {
+        match self {
+            Self::Auto => {
+                let (crlf, lf) = count_line_endings(buffer);
 
-            set_double_quotes(string);
+                if crlf > lf {
+                    Self::Crlf
+                } else {
+                    Self::Lf
+                }
+            }
+            line_ending => line_ending,
         }
     }
 }
 
-/// Strips all leading and trailing whitespace from string values
-fn strip_string(node: &mut Node) {
-    if let Some(NodeValue::String(ref mut text)) = node.node_value_mut() {
-        let slice = text.as_str();
-        let slice = &slice[1..slice.len() - 1]; // Without the quotes
+impl std::str::FromStr for LineEnding {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "auto" => Ok(Self::Auto),
+            "lf" => Ok(Self::Lf),
+            "crlf" => Ok(Self::Crlf),
+            _ => Err(format!(
+                "invalid line ending {s:?}, expected auto, lf or crlf"
+            )),
+        }
+    }
+}
 
-        let text_start = 1 + match slice.find(|c: char| !c.is_ascii_whitespace()) {
-            Some(pos) => pos,
-            None => {
-                // None means the string doesn't contain any non-whitespace characters, just
-                // replace it with an empty string
-                text.clear();
-                text.push_str("\"\"");
-                return;
-            }
-        };
+/// Formatting options that can be overridden for specific statement keywords
+#[derive(Debug, Clone, Default)]
+pub struct KeywordOverrides {
+    pub value_placement: Option<ValuePlacement>,
+    /// Wrap values of this keyword at this column instead of "FormatConfig::line_length"
+    pub max_width: Option<u16>,
+}
 
-        let text_end = text.len()
-            - slice
-                .chars()
-                .rev()
-                .position(|c| !c.is_whitespace())
-                .unwrap_or(0)
-            - 2;
+#[derive(Clone)]
+pub struct FormatConfig {
+    pub indent: Indent,
+    /// Number of spaces used when a value is pushed to the next line, defaults to one indent level.
+    /// Also used for the continuation lines of string concatenations with
+    /// "ConcatAlignment::Continuation".
+    pub continuation_indent: Option<u8>,
+    pub wrapped_value_indent: WrappedValueIndent,
+    pub line_length: u16,
+    pub style: Style,
+    /// Rules for extension statements, taking precedence over the built-in rules for common
+    /// vendor extensions like "tailf:info"
+    pub extensions: ExtensionRules,
+    /// Fold lines that are still longer than the max width after formatting, using the single
+    /// backslash strategy of RFC 8792
+    ///
+    /// The folded output is meant to be pasted into a document, it isn't valid YANG anymore.
+    ///
+    pub fold_long_lines: bool,
+    pub fix_canonical_order: bool,
+    /// Also sort statement lists with comments or empty lines between the statements
+    pub force_sort: bool,
+    /// Order revisions by descending date, unsorted revisions are otherwise left to the
+    /// "revision-order" lint rule
+    pub sort_revisions: bool,
+    /// Order of the enums in enumeration types
+    pub enum_sorting: EnumSorting,
+    /// Sort the definitions with these keywords by name, e.g. "identity", "feature", "typedef" and
+    /// "grouping". Definitions are sorted among the siblings with the same keyword, everything else
+    /// stays in place. Like other sorting, definitions with comments or empty lines between them
+    /// are only sorted with "force_sort".
+    pub sort_by_name: Vec<String>,
+    /// Move "status", "description" and "reference" to the end of the meta section of every block
+    /// except modules, before any definitions and data nodes, without sorting anything else
+    pub documentation_last: bool,
+    /// Render blocks without any children as "foo {}" rather than splitting them over two lines
+    pub collapse_empty_blocks: bool,
+    /// Guarantee a blank line between consecutive top-level definitions inside a module
+    pub separate_definitions: bool,
+    /// Remove blank lines between the children of blocks with fewer than this many child
+    /// statements
+    pub compact_blocks: Option<usize>,
+    /// Write blocks of these keywords on one line when they fit, e.g. "bit up { position 0; }"
+    pub single_line_blocks: Vec<String>,
+    /// Maximum number of child statements of blocks written on one line
+    pub single_line_max_children: usize,
+    /// Maximum width of blocks written on one line, not counting the indentation
+    pub single_line_max_width: Option<u16>,
+    /// Always insert a blank line before statements with these keywords
+    pub blank_line_before: Vec<String>,
+    /// End the output with a line break
+    pub final_newline: bool,
+    /// Remove trailing whitespace from the lines of multi-line double-quoted strings and comments.
+    /// Single-quoted strings are left alone, since the whitespace is part of their value.
+    pub strip_trailing_whitespace: bool,
+    /// Line breaks of the output, including those inside strings and comments
+    pub line_ending: LineEnding,
+    pub value_placement: ValuePlacement,
+    /// Per-keyword formatting options, taking precedence over the global options
+    pub keyword_overrides: HashMap<String, KeywordOverrides>,
+    pub semicolon_placement: SemicolonPlacement,
+    /// Has no effect if strings are preserved, see "FormatConfig::reindents_strings"
+    pub closing_quote: ClosingQuotePlacement,
+    pub concat_operator: ConcatOperatorStyle,
+    pub concat_alignment: ConcatAlignment,
+    /// Re-wrap the text of description, reference and contact strings to fit the max width
+    pub reflow_text: bool,
+    /// Re-wrap paragraphs of consecutive "//" comment lines to fit the max width
+    pub reflow_comments: bool,
+    /// Convert single-line block comments like "/* note */" to line comments like "// note"
+    pub prefer_line_comments: bool,
+    /// Put a space after "//" and "/*" and before "*/", e.g. "//note" becomes "// note"
+    pub space_comment_markers: bool,
+    /// Never change the contents of strings, including whitespace and indentation
+    pub preserve_strings: bool,
+    /// Keep the internal indentation of multi-lined strings, but still strip them
+    pub preserve_string_indent: bool,
+    /// Split single-line strings that can't fit within the max width into concatenations
+    pub split_long_strings: bool,
+    /// Split augment, deviation and refine paths that can't fit within the max width at "/"
+    pub split_long_paths: bool,
+    /// Join string concatenations that fit on one line into a single string
+    pub merge_short_concatenations: bool,
+    /// Keywords whose values are never moved to the next line or rewritten to fit within the max
+    /// width
+    pub never_wrap: Vec<String>,
+    /// Values matching any of these patterns are never moved to the next line or split, since
+    /// breaking them up gains nothing (e.g. URLs). Quotes are stripped before matching.
+    pub never_wrap_patterns: Vec<Regex>,
+    /// Keywords whose string values keep their line layout exactly, like strings marked with a
+    /// "yangfmt: verbatim" comment
+    pub verbatim: Vec<String>,
+    /// Wrap long "when" and "must" expressions at logical operators
+    pub wrap_xpath: bool,
+    pub quote_style: QuoteStyle,
+    /// Put quotes around unquoted string arguments, such as enum names, key lists and defaults
+    pub quote_string_arguments: bool,
+    /// Remove the quotes around identifiers, numbers and dates where YANG allows it, has no effect
+    /// if "quote_string_arguments" is enabled
+    pub unquote_simple_arguments: bool,
+    pub non_printable: NonPrintableCharacters,
+    /// Remove plus signs and superfluous zeros from the numeric arguments of statements like
+    /// "value" and "min-elements"
+    pub normalize_numbers: bool,
+    /// How long leafref paths are wrapped, use a "path" keyword override to give them a different
+    /// max width
+    pub path_wrapping: PathWrapping,
+    /// Quoting of "prefix", "belongs-to", "base", "type" and "uses" arguments, takes precedence
+    /// over "quote_string_arguments" and "unquote_simple_arguments"
+    pub identifier_quoting: IdentifierQuoting,
+    /// Line up the trailing comments of consecutive single-line statements
+    pub align_trailing_comments: bool,
+    /// Pad keywords so the values of consecutive single-line statements line up in a column
+    pub align_values: bool,
+    /// Write enums that only have a "value" substatement on one line, e.g. "enum up { value 1; }",
+    /// and line up their blocks so enumerations read like a table
+    pub align_enum_values: bool,
+    /// Report what the formatter deliberately discards or moves, like blank lines removed by
+    /// sorting, as diagnostics with the codes in "LOSSY_CHANGE_CODES"
+    pub report_lossy_changes: bool,
+}
 
-        if text_end < (text.len() - 2) {
-            text.drain(text_end + 1..text.len() - 1);
+/// Codes of the diagnostics reported with "FormatConfig::report_lossy_changes", which are notes
+/// about what changed rather than problems
+pub const LOSSY_CHANGE_CODES: &[&str] = &["blank-lines-removed", "comment-moved", "string-emptied"];
+
+impl Default for FormatConfig {
+    fn default() -> Self {
+        Self {
+            indent: Indent::Spaces(2),
+            continuation_indent: None,
+            wrapped_value_indent: WrappedValueIndent::Indent,
+            line_length: 79,
+            style: Style::Yangfmt,
+            extensions: ExtensionRules::new(),
+            fold_long_lines: false,
+            fix_canonical_order: false,
+            force_sort: false,
+            sort_revisions: false,
+            enum_sorting: EnumSorting::None,
+            sort_by_name: vec![],
+            documentation_last: false,
+            collapse_empty_blocks: false,
+            separate_definitions: false,
+            compact_blocks: None,
+            single_line_blocks: vec![],
+            single_line_max_children: 1,
+            single_line_max_width: None,
+            blank_line_before: vec![],
+            final_newline: true,
+            strip_trailing_whitespace: true,
+            line_ending: LineEnding::Auto,
+            value_placement: ValuePlacement::Auto,
+            keyword_overrides: HashMap::new(),
+            semicolon_placement: SemicolonPlacement::Attached,
+            closing_quote: ClosingQuotePlacement::Attached,
+            concat_operator: ConcatOperatorStyle::Leading,
+            concat_alignment: ConcatAlignment::Quote,
+            reflow_text: false,
+            reflow_comments: false,
+            prefer_line_comments: false,
+            space_comment_markers: false,
+            preserve_strings: false,
+            preserve_string_indent: false,
+            split_long_strings: false,
+            split_long_paths: false,
+            merge_short_concatenations: false,
+            never_wrap: DEFAULT_NEVER_WRAP
+                .iter()
+                .map(|keyword| keyword.to_string())
+                .collect(),
+            never_wrap_patterns: vec![],
+            verbatim: DEFAULT_VERBATIM
+                .iter()
+                .map(|keyword| keyword.to_string())
+                .collect(),
+            wrap_xpath: false,
+            quote_style: QuoteStyle::Double,
+            quote_string_arguments: false,
+            unquote_simple_arguments: false,
+            non_printable: NonPrintableCharacters::Warn,
+            normalize_numbers: false,
+            path_wrapping: PathWrapping::Auto,
+            identifier_quoting: IdentifierQuoting::Preserve,
+            align_trailing_comments: false,
+            align_values: false,
+            align_enum_values: false,
+            report_lossy_changes: false,
         }
+    }
+}
 
-        if text_start > 1 {
-            text.drain(1..text_start);
+impl FormatConfig {
+    fn indent_width(&self) -> u8 {
+        match self.indent {
+            // Indent::Tab
+            Indent::Spaces(num) => num,
         }
     }
-}
 
-/// Dedents multi-lined strings
-///
-/// Multi-lined strings in YANG are practically always indented to match the context. Since we
-/// might completely change the indent around strings, we might as well dedent the strings and
-/// recalculate the indentation later during formatting.
-///
-/// This function assumes any strings have already been stripped, see "strip_string".
-///
-fn dedent_multilined_string(node: &mut Node) {
-    let value = if let Some(value) = node.node_value() {
-        value
-    } else {
-        return;
-    };
+    fn continuation_width(&self) -> u8 {
+        self.continuation_indent.unwrap_or(self.indent_width())
+    }
 
-    let text = if let NodeValue::String(text) = value {
-        text
-    } else {
-        return;
-    };
+    /// Number of spaces before the continuation lines of a string concatenation, relative to the
+    /// indentation of the statement, see "concat_alignment"
+    fn concat_offset(&self, keyword: &str) -> usize {
+        let leading = self.concat_operator == ConcatOperatorStyle::Leading;
 
-    let quotechar = text.chars().next().unwrap();
+        match self.concat_alignment {
+            ConcatAlignment::Quote if leading => (keyword.len() - 1).max(1),
+            ConcatAlignment::Quote => keyword.len() + 1,
+            ConcatAlignment::Continuation => self.continuation_width() as usize,
+            ConcatAlignment::Keyword => 2,
+        }
+    }
 
-    // Strips off the quote characters
-    let text = &text[1..text.len() - 1];
-    let lines: Vec<_> = text.lines().collect();
+    /// Whether multi-lined strings get dedented and re-indented to match their new position
+    fn reindents_strings(&self) -> bool {
+        !(self.preserve_strings || self.preserve_string_indent)
+    }
 
-    if lines.len() < 2 {
-        return;
+    fn never_wraps(&self, keyword: &str) -> bool {
+        if self.path_wrapping == PathWrapping::Never && argument_kind(keyword) == ArgumentKind::Path
+        {
+            return true;
+        }
+
+        self.never_wrap.iter().any(|kw| kw == keyword)
     }
 
-    // The first line is often right at the opening quote, so it doesn't make sense to include it
-    // in the text that gets dedented
-    let first_line = lines.first().unwrap();
+    fn is_unbreakable(&self, value: &str) -> bool {
+        if self.never_wrap_patterns.is_empty() {
+            return false;
+        }
 
-    let rest = lines.get(1..).unwrap().join("\n");
-    let rest = textwrap::dedent(&rest);
+        let value = match value.chars().next() {
+            Some(quote @ ('"' | '\'')) if value.len() > 1 && value.ends_with(quote) => {
+                &value[1..value.len() - 1]
+            }
+            _ => value,
+        };
 
-    let new_text = format!("{}{}\n{}{}", quotechar, first_line, rest, quotechar);
+        self.never_wrap_patterns
+            .iter()
+            .any(|pattern| pattern.is_match(value))
+    }
 
-    match node {
-        Node::Statement(ref mut node) => node.value = Some(NodeValue::String(new_text)),
-        _ => unreachable!("If node isn't a statement, how did we get the mutable value?"),
-    };
-}
-
-/// Writes the node tree to the given writeable object
-///
-/// This automatically handles indentation and spacing between nodes. However, it does not process
-/// node order, line breaks and things like that. That is handled by a pre-processing step.
-///
-/// (This function leaves no trailing line break)
-///
-fn write_node<T: std::io::Write>(
-    out: &mut T,
-    node: &Node,
-    config: &FormatConfig,
-    depth: u16,
-) -> Result<(), Error> {
-    macro_rules! indent {
-        ($depth:expr) => {
-            for _ in 0..$depth {
-                match config.indent {
-                    // Indent::Tab => {
-                    //     write!(out, "\t")?;
-                    // }
-                    Indent::Spaces(spaces) => {
-                        for _ in 0..spaces {
-                            write!(out, " ")?;
-                        }
-                    }
-                }
-            }
-        };
+    fn overrides_for(&self, keyword: &str) -> Option<&KeywordOverrides> {
+        self.keyword_overrides.get(keyword)
     }
 
-    macro_rules! write_keyword {
-        ($node:expr) => {
-            match $node.keyword {
-                StatementKeyword::Keyword(ref text) => write!(out, "{text}")?,
-                StatementKeyword::ExtensionKeyword(ref text) => write!(out, "{text}")?,
-                StatementKeyword::Invalid(ref text) => write!(out, "{text}")?,
-            };
+    fn max_width(&self, keyword: &str) -> u16 {
+        self.overrides_for(keyword)
+            .and_then(|overrides| overrides.max_width)
+            .unwrap_or(self.line_length)
+    }
 
-            for comment in $node.keyword_comments.as_slice() {
-                write!(out, " {comment}")?;
-            }
+    /// Returns the rule for the statement keyword if it's an extension, see "extensions"
+    fn extension_rule(&self, keyword: &StatementKeyword) -> Option<&ExtensionRule> {
+        match keyword {
+            StatementKeyword::ExtensionKeyword(text) => extension_rule(&self.extensions, text),
+            _ => None,
+        }
+    }
 
-            // This is where keyword comment would be written, but since the formatting rules will
-            // move them all, there will never be anything to write.
-        };
+    /// Returns the kind of argument the statement takes, including extensions with a rule
+    fn argument_kind(&self, keyword: &StatementKeyword) -> ArgumentKind {
+        match self.extension_rule(keyword) {
+            Some(rule) => rule.argument,
+            None => keyword.argument_kind(),
+        }
     }
 
-    macro_rules! write_simple_value {
-        ($line_pos:expr, $value:expr) => {{
-            // Checks if the line will be longer than the configured max width
-            //
-            // Line length = indent + keyword + value + a space + a semicolon
-            if ($line_pos + ($value.len() as u16) + 2 > config.line_length) {
-                writeln!(out)?;
-                indent!(depth + 1);
-            } else {
-                write!(out, " ")?;
+    fn value_placement(&self, keyword: &str) -> ValuePlacement {
+        let default = match self.style {
+            Style::Yangfmt => self.value_placement,
+            Style::Pyang if PYANG_NEWLINE_KEYWORDS.contains(&keyword) => {
+                ValuePlacement::AlwaysNewline
             }
+            Style::Pyang => ValuePlacement::NeverNewline,
+        };
 
-            write!(out, "{}", $value)?;
-        }};
+        self.overrides_for(keyword)
+            .and_then(|overrides| overrides.value_placement)
+            .unwrap_or(default)
     }
+}
 
-    macro_rules! write_value {
-        ($node:expr) => {
-            let kw_text = $node.keyword.text();
-            let line_pos: u16 = (config.indent_width() as u16) * depth + (kw_text.len() as u16);
-
-            match $node.value.as_ref().unwrap() {
-                NodeValue::Date(text) => write_simple_value!(line_pos, text),
-                NodeValue::Number(text) => write_simple_value!(line_pos, text),
-                NodeValue::Other(text) => write_simple_value!(line_pos, text),
-                NodeValue::String(text) => {
-                    if (text.contains('\n')) {
-                        // Multi-lined strings need to be indented
-                        writeln!(out)?;
-                        indent!(depth + 1);
+#[derive(Debug)]
+pub enum Error {
+    ParseError(ParseError),
+    IOError(String),
+}
 
-                        let mut lines = text.lines();
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::ParseError(parse_error) => write!(f, "{}", parse_error.message),
+            Error::IOError(text) => write!(f, "{}", text),
+        }
+    }
+}
 
-                        // The first line is written normally
-                        write!(out, "{}", lines.next().unwrap())?;
+impl From<ParseError> for Error {
+    fn from(value: ParseError) -> Self {
+        Self::ParseError(value)
+    }
+}
 
-                        // Each subsequent non-empty line are indented to match the starting column
-                        // of the first line, i.e. right after the quote
-                        let extra_indent = config.indent_width() + 1;
+impl From<std::io::Error> for Error {
+    fn from(error: std::io::Error) -> Self {
+        Self::IOError(format!("I/O Error: {}", error))
+    }
+}
 
-                        while let Some(line) = lines.next() {
-                            writeln!(out)?;
+/// Formats an input buffer of YANG source into the given output
+///
+/// Files with a "yangfmt: skip-file" comment at the top are written unchanged.
+///
+pub fn format_yang<T: std::io::Write>(
+    out: &mut T,
+    buffer: &[u8],
+    config: &FormatConfig,
+) -> Result<Vec<Diagnostic>, Error> {
+    if has_skip_file_pragma(buffer) {
+        out.write_all(buffer)?;
+        return Ok(vec![]);
+    }
 
-                            if !line.is_empty() {
-                                indent!(depth);
+    let mut tree = parse(buffer)?;
+    let mut diagnostics = vec![];
 
-                                for _ in 0..extra_indent {
-                                    write!(out, " ")?;
-                                }
-                            }
+    if config.non_printable != NonPrintableCharacters::Ignore {
+        diagnostics.extend(find_non_printable_characters(buffer, config));
+    }
 
-                            write!(out, "{}", line)?;
-                        }
-                    } else {
-                        write_simple_value!(line_pos, text);
-                    }
-                }
-                NodeValue::StringConcatenation(concat) => {
-                    let kwlen = kw_text.len();
-                    let pad = if kwlen >= 2 { kwlen - 2 } else { 0 };
+    process_statements(None, &mut tree.children, config, 0, &mut diagnostics);
 
-                    // The first string gets written on the same line as the keywords
-                    write!(out, " {}", concat[0].0)?;
+    let mut output: Vec<u8> = vec![];
 
-                    for comment in &concat[0].1 {
-                        write!(out, " {}", comment)?;
-                    }
+    write_nodes(&mut output, &tree.children, config, 0)?;
 
-                    // The rest get displayed on new lines, padded to align with the first string
-                    if let Some(rest) = concat.get(1..) {
-                        for (ref string, ref comments) in rest {
-                            writeln!(out)?;
-                            indent!(depth);
+    apply_final_newline(&mut output, config);
 
-                            for _ in 0..pad {
-                                write!(out, " ")?
-                            }
+    if config.fold_long_lines {
+        diagnostics.extend(apply_folding(&mut output, config));
+    }
 
-                            write!(out, " + {}", string)?;
+    apply_line_ending(&mut output, config.line_ending.resolve(buffer));
 
-                            for comment in comments {
-                                write!(out, " {}", comment)?;
-                            }
-                        }
-                    }
-                }
-            };
+    out.write_all(&output)?;
 
-            for comment in $node.value_comments.as_slice() {
-                write!(out, " {comment}")?;
-            }
-        };
-    }
+    Ok(diagnostics)
+}
 
-    match node {
-        Node::Statement(node) => {
-            indent!(depth);
-            write_keyword!(node);
+/// Reports statements that aren't in canonical order, without formatting anything
+///
+/// Each statement out of order is reported at its keyword, along with the statement it should
+/// come before.
+///
+pub fn find_canonical_order_violations(
+    buffer: &[u8],
+    extensions: &ExtensionRules,
+) -> Result<Vec<Diagnostic>, Error> {
+    fn find_in(
+        parent_node_name: Option<&str>,
+        nodes: &[Node],
+        extensions: &ExtensionRules,
+        diagnostics: &mut Vec<Diagnostic>,
+    ) {
+        for (statement, before) in find_order_violations(parent_node_name, nodes, extensions) {
+            diagnostics.push(
+                Diagnostic::new(
+                    format!(
+                        "\"{}\" statement is out of canonical order, it should come before \"{}\"",
+                        statement.keyword.text(),
+                        before.keyword.text(),
+                    ),
+                    statement.position,
+                )
+                .with_code("canonical-order"),
+            );
+        }
 
-            if node.value.is_some() {
-                write_value!(node);
+        for node in nodes {
+            if let Node::Statement(Statement {
+                keyword,
+                children: Some(children),
+                ..
+            }) = node
+            {
+                find_in(Some(keyword.text()), children, extensions, diagnostics);
             }
+        }
+    }
 
-            if let Some(ref children) = node.children {
-                write!(out, " {{")?;
+    let tree = parse(buffer)?;
+    let mut diagnostics = vec![];
 
-                for comment in &node.post_comments {
-                    write!(out, " {}", comment)?;
-                }
+    find_in(None, &tree.children, extensions, &mut diagnostics);
+    diagnostics.sort_by_key(|diagnostic| diagnostic.position);
 
-                writeln!(out)?;
+    Ok(diagnostics)
+}
 
-                for child in children.as_slice() {
-                    write_node(out, child, config, depth + 1)?;
-                }
+/// Reports non-printable characters in the quoted strings of the input
+///
+/// Characters that will be replaced by the formatter aren't reported.
+///
+fn find_non_printable_characters(buffer: &[u8], config: &FormatConfig) -> Vec<Diagnostic> {
+    let replaces = config.non_printable == NonPrintableCharacters::Replace;
+    let mut diagnostics = vec![];
 
-                indent!(depth);
-                write!(out, "}}")?;
-            } else {
-                write!(out, ";")?;
+    for token in yangfmt_lexing::scan_iter(buffer).flatten() {
+        if token.token_type != TokenType::String {
+            continue;
+        }
 
-                for comment in &node.post_comments {
-                    write!(out, " {}", comment)?;
-                }
+        for (index, c) in token.text.char_indices() {
+            if !is_non_printable(c) || (replaces && invisible_replacement(c).is_some()) {
+                continue;
             }
 
-            write!(out, "\n")?; // All statements implicitly end with a line break
+            diagnostics.push(
+                Diagnostic::new(
+                    format!("Non-printable character U+{:04X} in string", c as u32),
+                    token.span.0 + index,
+                )
+                .with_code("non-printable-character"),
+            );
         }
+    }
 
-        Node::Comment(text) => {
-            indent!(depth);
-            writeln!(out, "{text}")?;
+    diagnostics
+}
+
+/// Makes sure the output ends with exactly one line break, or none if "final_newline" is disabled
+///
+/// Any trailing whitespace at the end of the output is removed as well. Empty output is left
+/// empty.
+///
+fn apply_final_newline(output: &mut Vec<u8>, config: &FormatConfig) {
+    while output.last().is_some_and(|c| c.is_ascii_whitespace()) {
+        output.pop();
+    }
+
+    if config.final_newline && !output.is_empty() {
+        output.push(b'\n');
+    }
+}
+
+/// Folds the lines of the output that are longer than the max width, see "fold_long_lines"
+fn apply_folding(output: &mut Vec<u8>, config: &FormatConfig) -> Option<Diagnostic> {
+    let text = std::str::from_utf8(output).ok()?;
+
+    match fold_long_lines(text, config.line_length as usize) {
+        Ok(Some(folded)) => {
+            *output = folded.into_bytes();
+            None
         }
+        Ok(None) => None,
+        Err(error) => Some(
+            Diagnostic::new(
+                format!("Can't fold the long lines per RFC 8792, {error} in the output"),
+                0,
+            )
+            .with_code("unfoldable-lines"),
+        ),
+    }
+}
 
-        Node::EmptyLine(_) => {
-            writeln!(out)?;
+/// Counts the "\r\n" and lone "\n" line breaks in the buffer, in that order
+fn count_line_endings(buffer: &[u8]) -> (usize, usize) {
+    let mut crlf = 0;
+    let mut lf = 0;
+
+    for (index, c) in buffer.iter().enumerate() {
+        if *c == b'\n' {
+            if index > 0 && buffer[index - 1] == b'\r' {
+                crlf += 1;
+            } else {
+                lf += 1;
+            }
         }
     }
 
-    Ok(())
+    (crlf, lf)
 }
 
-#[cfg(test)]
-mod test {
-    use super::*;
-    use pretty_assertions::assert_eq;
+/// Checks if the buffer contains both "\r\n" and "\n" line breaks
+pub fn has_mixed_line_endings(buffer: &[u8]) -> bool {
+    let (crlf, lf) = count_line_endings(buffer);
 
-    fn dedent(text: &str) -> String {
-        let mut text = textwrap::dedent(text).trim().to_string();
-        text.push('\n');
-        text
+    crlf > 0 && lf > 0
+}
+
+/// Converts every line break of the output to the given line ending
+///
+/// The output is written with "\n" line breaks, but strings, comments and raw regions may still
+/// contain the "\r\n" line breaks of the input.
+///
+fn apply_line_ending(output: &mut Vec<u8>, line_ending: LineEnding) {
+    if !output.contains(&b'\r') && line_ending != LineEnding::Crlf {
+        return;
     }
 
-    /// Formats the input file into a String
-    fn format_yang_str(buffer: &[u8], config: &FormatConfig) -> Result<String, Error> {
-        let mut output: Vec<u8> = vec![];
+    let mut converted = Vec::with_capacity(output.len());
 
-        format_yang(&mut output, buffer, config)?;
+    for (index, c) in output.iter().enumerate() {
+        if *c == b'\r' && output.get(index + 1) == Some(&b'\n') {
+            continue;
+        }
 
-        Ok(String::from_utf8(output).expect("Invalid UTF-8 in input file"))
+        if *c == b'\n' && line_ending == LineEnding::Crlf {
+            converted.push(b'\r');
+        }
+
+        converted.push(*c);
     }
 
-    #[test]
-    fn test_write_node() {
-        let input_string = dedent(
-            r#"
-                module foo {
-                bar "testing" ;
-                foo 123.45    ;
+    *output = converted;
+}
 
+/// Applies auto-formatting rules recursively to the input statement list
+fn process_statements(
+    parent_node_name: Option<&str>,
+    statements: &mut Vec<Node>,
+    config: &FormatConfig,
+    depth: u16,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    for index in 0..statements.len() {
+        let (previous, rest) = statements.split_at_mut(index);
+        let node = &mut rest[0];
 
-                        revision 2022-02-02 {description "qwerty";} oh "dear";
+        if let Node::Statement(ref mut statement) = node {
+            // Recurse into the block node's children
+            if let Some(ref mut children) = statement.children {
+                process_statements(
+                    Some(statement.keyword.text()),
+                    children,
+                    config,
+                    depth + 1,
+                    diagnostics,
+                );
+            }
 
+            if config
+                .extension_rule(&statement.keyword)
+                .is_some_and(|rule| !rule.children)
+            {
+                match statement.children {
+                    Some(ref children) if children.is_empty() => statement.children = None,
+                    Some(_) => diagnostics.push(
+                        Diagnostic::new(
+                            format!(
+                                "\"{}\" statements don't take sub-statements",
+                                statement.keyword.text()
+                            ),
+                            statement.position,
+                        )
+                        .with_code("no-sub-statements"),
+                    ),
+                    None => (),
                 }
-                "#,
-        );
-
-        let tree = parse(input_string.as_bytes()).expect("Failed to parse input");
-        let module_node = tree.children.get(0).expect("Failed to get module node");
-
-        let mut out: Vec<u8> = vec![];
+            }
+        }
 
-        let config = FormatConfig {
-            indent: Indent::Spaces(4),
-            line_length: 80,
-            fix_canonical_order: false,
+        let kind = match node {
+            Node::Statement(ref statement) => config.argument_kind(&statement.keyword),
+            _ => ArgumentKind::NoArgument,
         };
 
-        write_node(&mut out, module_node, &config, 0).expect("Formatting failed");
+        normalize_boolean(node, kind);
+        diagnostics.extend(normalize_date_argument(node, kind));
 
-        let result = String::from_utf8(out).unwrap();
+        if config.normalize_numbers {
+            normalize_number_argument(node, kind);
+        }
 
-        assert_eq!(
-            dedent(
-                r#"
-                module foo {
-                    bar "testing";
-                    foo 123.45;
+        if config.non_printable == NonPrintableCharacters::Replace {
+            replace_invisible_characters(node);
+        }
 
+        if !config.preserve_strings {
+            normalize_list_argument(node, kind);
+        }
 
-                    revision 2022-02-02 {
-                        description "qwerty";
-                    }
-                    oh "dear";
+        if config.style == Style::Pyang {
+            // Unlike the IETF style, pyang never quotes identifiers
+            quote_string_argument(node, kind, &[]);
+        } else if config.quote_string_arguments {
+            quote_string_argument(node, kind, QUOTED_IDENTIFIER_KEYWORDS);
+        } else if config.unquote_simple_arguments {
+            unquote_simple_argument(node, kind);
+        }
 
-                }
-                "#
-            ),
-            result,
-        );
-    }
+        if config.identifier_quoting != IdentifierQuoting::Preserve {
+            normalize_identifier_quoting(node, config.identifier_quoting);
+        }
 
-    #[test]
+        match config.quote_style {
+            QuoteStyle::Double => convert_to_double_quotes(node),
+            QuoteStyle::Single => convert_to_single_quotes(node),
+            QuoteStyle::Preserve => (),
+        }
+
+        if config.quote_style != QuoteStyle::Preserve {
+            prefer_single_quoted_regex(node);
+        }
+
+        let verbatim = is_verbatim(previous.last(), node, config);
+
+        let never_wrap = verbatim
+            || matches!(node, Node::Statement(stmt) if config.never_wraps(stmt.keyword.text()));
+
+        if !config.preserve_strings && !verbatim {
+            let emptied = strip_string(node);
+
+            if let (true, true, Node::Statement(stmt)) =
+                (emptied, config.report_lossy_changes, &*node)
+            {
+                diagnostics.push(
+                    Diagnostic::new(
+                        "String with only whitespace replaced by an empty string",
+                        stmt.value_position.unwrap_or(stmt.position),
+                    )
+                    .with_code("string-emptied"),
+                );
+            }
+
+            if config.strip_trailing_whitespace {
+                strip_trailing_whitespace(node);
+            }
+        }
+
+        if config.reindents_strings() {
+            // Multi-lined quoted strings get stripped and dedented
+            dedent_multilined_string(node);
+
+            if config.reflow_text && !never_wrap {
+                reflow_prose(node, config, depth);
+            }
+        }
+
+        if !never_wrap {
+            wrap_expression(node, config, depth);
+        }
+
+        if config.merge_short_concatenations {
+            merge_short_concatenation(node, config, depth);
+        }
+
+        if !never_wrap {
+            split_long_string(node, config, depth);
+        }
+    }
+
+    trim_line_breaks(statements);
+    squash_line_breaks(statements);
+
+    if config.report_lossy_changes {
+        diagnostics.extend(find_pre_block_comments(statements));
+    }
+
+    relocate_pre_block_comments(statements);
+
+    if let Some(size) = config.compact_blocks {
+        if parent_node_name.is_some() {
+            remove_blank_lines_in_small_block(statements, size);
+        }
+    }
+
+    if config.prefer_line_comments {
+        convert_block_comments(statements);
+    }
+
+    if config.reflow_comments {
+        reflow_line_comments(statements, config, depth);
+    }
+
+    if !config.blank_line_before.is_empty() {
+        insert_blank_lines_before(statements, &config.blank_line_before);
+    }
+
+    if config.separate_definitions && matches!(parent_node_name, Some("module" | "submodule")) {
+        separate_definitions(statements);
+    }
+
+    if config.style == Style::Pyang && matches!(parent_node_name, Some("module" | "submodule")) {
+        separate_pyang_sections(statements);
+    }
+
+    let blank_lines_before_sorting = count_blank_lines(statements);
+    let positions_before_sorting = statement_positions(statements);
+
+    if config.sort_revisions && matches!(parent_node_name, Some("module" | "submodule")) {
+        if let Some(Unsorted::ManualOrdering(position)) =
+            sort_revisions(statements, config.force_sort)
+        {
+            diagnostics.push(
+                Diagnostic::new(
+                    "Revisions need manual ordering, they're not listed newest first but have \
+                     comments or empty lines between them",
+                    position,
+                )
+                .with_code("manual-ordering"),
+            );
+        }
+    }
+
+    if parent_node_name.is_some() {
+        for keyword in &config.sort_by_name {
+            match sort_by_name(statements, keyword, config.force_sort) {
+                Some(Unsorted::ManualOrdering(position)) => diagnostics.push(
+                    Diagnostic::new(
+                        format!(
+                            "\"{keyword}\" statements need manual ordering, they're not sorted by \
+                             name but have comments or empty lines between them"
+                        ),
+                        position,
+                    )
+                    .with_code("manual-ordering"),
+                ),
+                Some(Unsorted::ImplicitValue(position)) => diagnostics.push(
+                    Diagnostic::new(
+                        format!(
+                            "\"{keyword}\" statements can't be sorted by name without changing \
+                             the value of the ones without an explicit value"
+                        ),
+                        position,
+                    )
+                    .with_code("implicit-values"),
+                ),
+                None => (),
+            }
+        }
+    }
+
+    if config.enum_sorting != EnumSorting::None && parent_node_name == Some("type") {
+        match sort_enums(statements, config.enum_sorting, config.force_sort) {
+            Some(Unsorted::ManualOrdering(position)) => diagnostics.push(
+                Diagnostic::new(
+                    "Enums need manual ordering, they're not sorted but have comments or empty \
+                     lines between them",
+                    position,
+                )
+                .with_code("manual-ordering"),
+            ),
+            Some(Unsorted::ImplicitValue(position)) => diagnostics.push(
+                Diagnostic::new(
+                    "Enums can't be sorted without changing the value of the ones without a \
+                     \"value\" statement",
+                    position,
+                )
+                .with_code("implicit-values"),
+            ),
+            None => (),
+        }
+    }
+
+    if config.documentation_last
+        && parent_node_name.is_some_and(|name| !matches!(name, "module" | "submodule"))
+    {
+        move_documentation_last(
+            parent_node_name,
+            statements,
+            config.force_sort,
+            &config.extensions,
+        );
+    }
+
+    if config.fix_canonical_order {
+        if let Some(position) = sort_statements(
+            parent_node_name,
+            statements,
+            config.force_sort,
+            &config.extensions,
+        ) {
+            diagnostics.push(
+                Diagnostic::new(
+                    "Statements need manual ordering, they're out of canonical order but have \
+                     comments or empty lines between them",
+                    position,
+                )
+                .with_code("manual-ordering"),
+            );
+        }
+    }
+
+    let removed = blank_lines_before_sorting.saturating_sub(count_blank_lines(statements));
+
+    // The note goes on the first statement that was moved, where the blank lines went missing
+    let positions = statement_positions(statements);
+    let moved = positions
+        .iter()
+        .zip(&positions_before_sorting)
+        .find(|(after, before)| after != before)
+        .map(|(after, _)| *after)
+        .or(positions.first().copied());
+
+    if let (true, Some(position)) = (config.report_lossy_changes && removed > 0, moved) {
+        diagnostics.push(
+            Diagnostic::new(
+                format!(
+                    "{removed} blank line(s) removed while sorting the statements of this block"
+                ),
+                position,
+            )
+            .with_code("blank-lines-removed"),
+        );
+    }
+}
+
+fn count_blank_lines(statements: &[Node]) -> usize {
+    statements
+        .iter()
+        .filter(|node| node.is_empty_line())
+        .count()
+}
+
+/// Comment marking the string value of the next statement as verbatim, see "is_verbatim"
+const VERBATIM_MARKER: &str = "yangfmt: verbatim";
+
+/// Checks if the string value of the statement must keep its internal line layout exactly
+///
+/// This is the case for multi-line strings that look like ASCII diagrams or tables, the values
+/// of "FormatConfig::verbatim" keywords and strings of statements marked with a "yangfmt:
+/// verbatim" comment, either on the line above or on the statement itself. Verbatim strings are
+/// still dedented uniformly, but never stripped, reflowed or split.
+///
+fn is_verbatim(previous: Option<&Node>, node: &Node, config: &FormatConfig) -> bool {
+    let Node::Statement(stmt) = node else {
+        return false;
+    };
+
+    if config.verbatim.iter().any(|kw| kw == stmt.keyword.text()) {
+        return true;
+    }
+
+    if matches!(previous, Some(Node::Comment(text)) if is_pragma_comment(text, VERBATIM_MARKER)) {
+        return true;
+    }
+
+    if stmt
+        .keyword_comments
+        .iter()
+        .chain(&stmt.value_comments)
+        .chain(&stmt.post_comments)
+        .any(|comment| is_pragma_comment(comment, VERBATIM_MARKER))
+    {
+        return true;
+    }
+
+    matches!(stmt.value, Some(NodeValue::String(ref text)) if looks_preformatted(text))
+}
+
+/// Returns notes about the comments "relocate_pre_block_comments" moves
+fn find_pre_block_comments(nodes: &[Node]) -> Vec<Diagnostic> {
+    nodes
+        .iter()
+        .filter_map(|node| match node {
+            Node::Statement(stmt)
+                if !stmt.keyword_comments.is_empty() || !stmt.value_comments.is_empty() =>
+            {
+                Some(
+                    Diagnostic::new(
+                        format!(
+                            "Comment inside the \"{}\" statement moved after its opening brace or \
+                             semicolon",
+                            stmt.keyword.text()
+                        ),
+                        stmt.position,
+                    )
+                    .with_code("comment-moved"),
+                )
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// Relocates keyword- and value comments somewhere more acceptable
+///
+/// See tests at the bottom of the file for example results.
+///
+fn relocate_pre_block_comments(nodes: &mut [Node]) {
+    for node in nodes.iter_mut() {
+        if let Node::Statement(stmt) = node {
+            // Move all keyword comments and value comments into the post comments
+            stmt.post_comments.append(&mut stmt.keyword_comments);
+            stmt.post_comments.append(&mut stmt.value_comments);
+        }
+    }
+}
+
+/// Converts single-line block comments into line comments
+///
+/// Essentially converts:
+///
+///     /* Short note */
+///     leaf foo;  /* Another note */
+///
+/// Into:
+///
+///     // Short note
+///     leaf foo;  // Another note
+///
+/// Only standalone comments and the last trailing comment of a statement are converted, since a
+/// line comment would swallow anything after it on the same line. Banners are left alone.
+///
+fn convert_block_comments(statements: &mut [Node]) {
+    for node in statements.iter_mut() {
+        let comment = match node {
+            Node::Comment(text) => text,
+            Node::Statement(stmt) => match stmt.post_comments.last_mut() {
+                Some(text) => text,
+                None => continue,
+            },
+            Node::EmptyLine(_) | Node::Raw(_) => continue,
+        };
+
+        if is_banner(comment) {
+            continue;
+        }
+
+        if let Some(content) = comment
+            .strip_prefix("/*")
+            .and_then(|text| text.strip_suffix("*/"))
+            .filter(|content| !content.contains('\n'))
+        {
+            *comment = format!("// {}", content.trim()).trim_end().to_string();
+        }
+    }
+}
+
+/// Re-wraps runs of consecutive "//" comment lines as paragraphs to fit within the max width
+///
+/// Blank comment lines separate paragraphs, and list items are wrapped separately, like in
+/// reflowed descriptions. Banner lines such as "////////" or "//== Section ==" are left alone and
+/// end the run, and so are block comments.
+///
+fn reflow_line_comments(statements: &mut Vec<Node>, config: &FormatConfig, depth: u16) {
+    // Leaves room for the comment marker and the space after it
+    let width = (config.line_length as usize)
+        .saturating_sub(depth as usize * config.indent_width() as usize + 3);
+
+    let mut i = 0;
+
+    while i < statements.len() {
+        let lines: Vec<&str> = statements[i..]
+            .iter()
+            .map_while(reflowable_comment)
+            .collect();
+
+        if lines.is_empty() {
+            i += 1;
+            continue;
+        }
+
+        let run_length = lines.len();
+
+        // Trailing blank lines would be lost by the reflow
+        let trailing_blanks = lines
+            .iter()
+            .rev()
+            .take_while(|line| line.is_empty())
+            .count();
+
+        let mut reflowed: Vec<String> = reflow_text(&lines.join("\n"), width)
+            .lines()
+            .map(|line| line.to_string())
+            .collect();
+
+        reflowed.extend(std::iter::repeat_n(String::new(), trailing_blanks));
+
+        let comments: Vec<Node> = reflowed
+            .into_iter()
+            .map(|line| match line.is_empty() {
+                true => Node::Comment("//".to_string()),
+                false => Node::Comment(format!("// {line}")),
+            })
+            .collect();
+
+        let count = comments.len();
+        statements.splice(i..i + run_length, comments);
+        i += count;
+    }
+}
+
+/// Returns the text of a "//" comment line, unless it's a banner line or a "yangfmt:" pragma
+///
+/// Pragmas like "// yangfmt: off" have to stay on a line of their own, so they end the run of
+/// comments instead of being merged into the paragraph above them.
+///
+fn reflowable_comment(node: &Node) -> Option<&str> {
+    let Node::Comment(text) = node else {
+        return None;
+    };
+
+    let content = text.strip_prefix("//")?.trim_end();
+
+    if is_banner(text) || content.trim_start().starts_with("yangfmt:") {
+        return None;
+    }
+
+    Some(content.strip_prefix(' ').unwrap_or(content))
+}
+
+/// Removes all blank lines from the block if it has fewer than the given number of child
+/// statements
+///
+/// Essentially converts:
+///
+///     leaf foo {
+///         type string;
+///
+///         description "Foo";
+///     }
+///
+/// Into:
+///
+///     leaf foo {
+///         type string;
+///         description "Foo";
+///     }
+///
+fn remove_blank_lines_in_small_block(statements: &mut Vec<Node>, size: usize) {
+    let count = statements
+        .iter()
+        .filter(|node| matches!(node, Node::Statement(_)))
+        .count();
+
+    if count < size {
+        statements.retain(|node| !node.is_empty_line());
+    }
+}
+
+/// Removes leading and trailing line breaks from the statement list
+///
+/// Essentially converts:
+///
+///     foo {
+///
+///         bar {
+///
+///             description "Test";
+///
+///             reference "Test";
+///
+///
+///         }
+///
+///     }
+///
+/// Into:
+///
+///     foo {
+///         bar {
+///             description "Test";
+///
+///             reference "Test";
+///         }
+///     }
+///
+fn trim_line_breaks(statements: &mut Vec<Node>) {
+    while statements.first().is_empty_line() {
+        statements.remove(0);
+    }
+
+    while statements.last().is_empty_line() {
+        statements.pop();
+    }
+}
+
+/// Squashes any occurrance of 3 or more line breaks down to 2 line breaks
+///
+/// Essentially converts:
+///
+///     module foo {
+///         foo "123";
+///
+///
+///
+///         bar "123";
+///     }
+///
+/// Into:
+///
+///     module foo {
+///         foo "123";
+///
+///         bar "123";
+///     }
+///
+fn squash_line_breaks(statements: &mut Vec<Node>) {
+    let mut i = 1;
+
+    while let Some(node) = statements.get(i) {
+        if node.is_empty_line() && statements.get(i - 1).is_empty_line() {
+            statements.remove(i);
+            continue;
+        }
+
+        i += 1;
+    }
+}
+
+/// Statements that are separated by a blank line when "separate_definitions" is enabled
+const TOP_LEVEL_DEFINITIONS: &[&str] = &["container", "grouping", "typedef", "rpc", "augment"];
+
+/// Ensures there is a blank line between consecutive top-level definition blocks
+///
+/// Essentially converts:
+///
+///     module foo {
+///         container foo {
+///             ...
+///         }
+///         // Comment about bar
+///         grouping bar {
+///             ...
+///         }
+///     }
+///
+/// Into:
+///
+///     module foo {
+///         container foo {
+///             ...
+///         }
+///
+///         // Comment about bar
+///         grouping bar {
+///             ...
+///         }
+///     }
+///
+fn separate_definitions(statements: &mut Vec<Node>) {
+    let is_definition = |node: &Node| match node {
+        Node::Statement(stmt) => {
+            stmt.children.is_some() && TOP_LEVEL_DEFINITIONS.contains(&stmt.keyword.text())
+        }
+        _ => false,
+    };
+
+    let mut i = 1;
+
+    while i < statements.len() {
+        if is_definition(&statements[i]) {
+            let start = leading_comments_start(statements, i);
+
+            if start > 0 && is_definition(&statements[start - 1]) {
+                statements.insert(start, Node::EmptyLine("\n".to_string()));
+                i += 1;
+            }
+        }
+
+        i += 1;
+    }
+}
+
+/// Returns the section of a module level statement in the output of pyang
+fn pyang_section(keyword: &str) -> u8 {
+    match keyword {
+        "yang-version" | "namespace" | "prefix" | "belongs-to" => 0,
+        "import" | "include" => 1,
+        "organization" | "contact" | "description" | "reference" => 2,
+        "revision" => 3,
+        _ => 4,
+    }
+}
+
+/// Replaces the blank lines between the statements of a module with the ones pyang writes
+///
+/// That is a blank line between the header, linkage, meta, revision and body sections, and between
+/// body statements with blocks. Essentially converts:
+///
+///     module foo {
+///         namespace "urn:foo";
+///         prefix foo;
+///         import bar { prefix bar; }
+///
+///         description "Foo";
+///         container baz { ... }
+///         leaf qux { ... }
+///     }
+///
+/// Into:
+///
+///     module foo {
+///         namespace "urn:foo";
+///         prefix foo;
+///
+///         import bar { prefix bar; }
+///
+///         description "Foo";
+///
+///         container baz { ... }
+///
+///         leaf qux { ... }
+///     }
+///
+fn separate_pyang_sections(statements: &mut Vec<Node>) {
+    statements.retain(|node| !node.is_empty_line());
+
+    let mut previous: Option<(u8, bool)> = None;
+    let mut i = 0;
+
+    while i < statements.len() {
+        if let Node::Statement(stmt) = &statements[i] {
+            let section = pyang_section(stmt.keyword.text());
+            let is_block = stmt.children.is_some();
+
+            if let Some((previous_section, previous_is_block)) = previous {
+                if section != previous_section || (section == 4 && (is_block || previous_is_block))
+                {
+                    let start = leading_comments_start(statements, i);
+
+                    statements.insert(start, Node::EmptyLine("\n".to_string()));
+                    i += 1;
+                }
+            }
+
+            previous = Some((section, is_block));
+        }
+
+        i += 1;
+    }
+}
+
+/// Inserts a blank line above every statement using one of the given keywords
+///
+/// The first statement in a block is left alone, since a blank line there would be removed by
+/// "trim_line_breaks" anyway.
+///
+fn insert_blank_lines_before(statements: &mut Vec<Node>, keywords: &[String]) {
+    let mut i = 1;
+
+    while i < statements.len() {
+        if let Node::Statement(stmt) = &statements[i] {
+            if keywords.iter().any(|kw| kw == stmt.keyword.text()) {
+                let start = leading_comments_start(statements, i);
+
+                if start > 0 && !statements[start - 1].is_empty_line() {
+                    statements.insert(start, Node::EmptyLine("\n".to_string()));
+                    i += 1;
+                }
+            }
+        }
+
+        i += 1;
+    }
+}
+
+/// Returns the index of the first comment in the run of comments directly above the given node
+///
+/// Comments right above a statement are considered to belong to it, so any blank line inserted
+/// "before" a statement has to go above its comments.
+///
+fn leading_comments_start(statements: &[Node], index: usize) -> usize {
+    let mut start = index;
+
+    while start > 0 && statements[start - 1].is_comment() {
+        start -= 1;
+    }
+
+    start
+}
+
+/// Keywords whose arguments are regular expressions
+const REGEX_KEYWORDS: &[&str] = &["pattern"];
+
+/// Lowercases boolean arguments, such as "config True"
+///
+/// YANG booleans are case sensitive, so any other casing is invalid anyway.
+///
+fn normalize_boolean(node: &mut Node, kind: ArgumentKind) {
+    let Node::Statement(stmt) = node else {
+        return;
+    };
+
+    if kind != ArgumentKind::Boolean {
+        return;
+    }
+
+    let text = match stmt.value {
+        Some(NodeValue::Other(ref mut text)) => text,
+        Some(NodeValue::String(ref mut text)) => text,
+        _ => return,
+    };
+
+    let content = text.trim_matches(['"', '\'']);
+
+    if content.eq_ignore_ascii_case("true") || content.eq_ignore_ascii_case("false") {
+        *text = text.to_ascii_lowercase();
+    }
+}
+
+/// Rewrites the date arguments of "revision" and "revision-date" into bare YYYY-MM-DD tokens
+///
+/// Dates are unquoted, and single digit months and days are zero-padded. Returns a diagnostic if
+/// the argument isn't a valid date, in which case it's left alone.
+///
+fn normalize_date_argument(node: &mut Node, kind: ArgumentKind) -> Option<Diagnostic> {
+    let Node::Statement(stmt) = node else {
+        return None;
+    };
+
+    if kind != ArgumentKind::Date {
+        return None;
+    }
+
+    let text = match stmt.value {
+        Some(NodeValue::Date(ref text)) => text,
+        Some(NodeValue::Other(ref text)) => text,
+        Some(NodeValue::String(ref text)) => text[1..text.len() - 1].trim(),
+        _ => return None,
+    };
+
+    match normalize_date(text) {
+        Ok(date) => {
+            stmt.value = Some(NodeValue::Date(date));
+            None
+        }
+        Err(message) => Some(
+            Diagnostic::new(
+                format!("Invalid {}: {}", stmt.keyword.text(), message),
+                stmt.value_position.unwrap_or(stmt.position),
+            )
+            .with_code("invalid-date"),
+        ),
+    }
+}
+
+lazy_static! {
+    /// Matches numbers with an optional sign and any number of leading and trailing zeros
+    static ref LOOSE_NUMBER_PATTERN: Regex =
+        Regex::new(r"^([+-]?)0*([0-9]+?)(?:\.([0-9]*?)0*)?$").unwrap();
+}
+
+/// Normalizes the numeric arguments of statements like "value" and "min-elements"
+///
+/// Plus signs, leading zeros and trailing decimal zeros are removed, e.g. "+007" becomes "7" and
+/// "2.50" becomes "2.5". Quoted numbers stay quoted.
+///
+fn normalize_number_argument(node: &mut Node, kind: ArgumentKind) {
+    let Node::Statement(stmt) = node else {
+        return;
+    };
+
+    if kind != ArgumentKind::Number {
+        return;
+    }
+
+    let (text, quotechar) = match stmt.value {
+        Some(NodeValue::Number(ref text)) | Some(NodeValue::Other(ref text)) => (text.as_str(), ""),
+        Some(NodeValue::String(ref text)) => (&text[1..text.len() - 1], &text[..1]),
+        _ => return,
+    };
+
+    let Some(captures) = LOOSE_NUMBER_PATTERN.captures(text) else {
+        return;
+    };
+
+    let integer = &captures[2];
+    let fraction = captures.get(3).map(|fraction| match fraction.as_str() {
+        "" => "0",
+        digits => digits,
+    });
+
+    let is_zero = integer == "0" && fraction.is_none_or(|fraction| fraction == "0");
+    let sign = if &captures[1] == "-" && !is_zero {
+        "-"
+    } else {
+        ""
+    };
+
+    let number = match fraction {
+        Some(fraction) => format!("{sign}{integer}.{fraction}"),
+        None => format!("{sign}{integer}"),
+    };
+
+    stmt.value = Some(if quotechar.is_empty() {
+        NodeValue::Number(number)
+    } else {
+        NodeValue::String(format!("{quotechar}{number}{quotechar}"))
+    });
+}
+
+/// Collapses the whitespace between the items of space separated arguments into single spaces
+///
+/// Essentially converts:
+///
+///     key "name   id";
+///
+/// Into:
+///
+///     key "name id";
+///
+/// This applies to "key", "unique" and "if-feature" arguments. If-feature expressions are also
+/// stripped of the spaces right inside parentheses, see "normalize_if_feature".
+///
+fn normalize_list_argument(node: &mut Node, kind: ArgumentKind) {
+    let Node::Statement(stmt) = node else {
+        return;
+    };
+
+    if !matches!(
+        kind,
+        ArgumentKind::IdentifierList | ArgumentKind::IfFeatureExpr
+    ) {
+        return;
+    }
+
+    let is_if_feature = kind == ArgumentKind::IfFeatureExpr;
+
+    if let Some(NodeValue::String(ref mut text)) = stmt.value {
+        let quotechar = &text[..1];
+        let content = &text[1..text.len() - 1];
+
+        let normalized = match is_if_feature.then(|| normalize_if_feature(content)) {
+            Some(Some(expr)) => expr,
+            _ => content.split_whitespace().collect::<Vec<_>>().join(" "),
+        };
+
+        *text = format!("{quotechar}{normalized}{quotechar}");
+    }
+}
+
+/// Replaces invisible characters in string values, see "strings::replace_invisible"
+fn replace_invisible_characters(node: &mut Node) {
+    match node.node_value_mut() {
+        Some(NodeValue::String(string)) => *string = replace_invisible(string),
+        Some(NodeValue::StringConcatenation(strings)) => {
+            for (string, _) in strings {
+                *string = replace_invisible(string);
+            }
+        }
+        _ => (),
+    }
+}
+
+/// Removes trailing whitespace from the lines of multi-line double-quoted strings
+///
+/// YANG drops whitespace before line breaks from double-quoted strings anyway, so this doesn't
+/// change their value.
+///
+fn strip_trailing_whitespace(node: &mut Node) {
+    let strip = |string: &mut String| {
+        if string.starts_with('"') {
+            *string = trim_line_ends(string);
+        }
+    };
+
+    match node.node_value_mut() {
+        Some(NodeValue::String(string)) => strip(string),
+        Some(NodeValue::StringConcatenation(strings)) => {
+            for (string, _) in strings {
+                strip(string);
+            }
+        }
+        _ => (),
+    }
+}
+
+/// Converts single-quoted strings to double quoted strings
+///
+/// Backslashes are escaped, since double-quoted strings have escape processing. Strings are left
+/// alone if they contain double-quotes, if they're regular expressions containing backslashes (see
+/// "prefer_single_quoted_regex") or if converting them would change their value, which is the case
+/// for multi-line strings with trailing whitespace on a line.
+///
+fn convert_to_double_quotes(node: &mut Node) {
+    let is_regex =
+        matches!(node, Node::Statement(stmt) if REGEX_KEYWORDS.contains(&stmt.keyword.text()));
+
+    let convert = |string: &mut String| {
+        if !string.starts_with('\'') {
+            return;
+        }
+
+        let content = &string[1..string.len() - 1];
+
+        // Trailing whitespace on a line is dropped from double-quoted strings
+        let has_trailing_whitespace = content
+            .lines()
+            .rev()
+            .skip(1)
+            .any(|line| line.ends_with(char::is_whitespace));
+
+        if content.contains('"') || (is_regex && content.contains('\\')) || has_trailing_whitespace
+        {
+            return;
+        }
+
+        *string = format!("\"{}\"", escape_double_quoted(content));
+    };
+
+    match node.node_value_mut() {
+        Some(NodeValue::String(string)) => convert(string),
+        Some(NodeValue::StringConcatenation(strings)) => {
+            for (string, _) in strings {
+                convert(string);
+            }
+        }
+        _ => (),
+    }
+}
+
+/// Converts double-quoted strings to single quoted strings
+///
+/// Only strings without any backslashes or single quotes are converted, since single-quoted
+/// strings have no escape processing. Multi-line strings are also left alone, since the
+/// whitespace of double-quoted strings is processed differently.
+///
+fn convert_to_single_quotes(node: &mut Node) {
+    let convert = |string: &mut String| {
+        if !string.starts_with('"') {
+            return;
+        }
+
+        let content = &string[1..string.len() - 1];
+
+        if content.contains(['\\', '\'', '\n']) {
+            return;
+        }
+
+        *string = format!("'{content}'");
+    };
+
+    match node.node_value_mut() {
+        Some(NodeValue::String(string)) => convert(string),
+        Some(NodeValue::StringConcatenation(strings)) => {
+            for (string, _) in strings {
+                convert(string);
+            }
+        }
+        _ => (),
+    }
+}
+
+/// Keywords taking an identifier as argument that are quoted anyway when "quote_string_arguments"
+/// is enabled, following the IETF style
+const QUOTED_IDENTIFIER_KEYWORDS: &[&str] = &["prefix"];
+
+/// Puts double quotes around unquoted string arguments
+///
+/// Only arguments that are strings in nature are quoted, such as enum names, key lists, paths and
+/// default values. Identifiers, numbers, booleans and keywords like "unbounded" are left alone.
+///
+fn quote_string_argument(node: &mut Node, kind: ArgumentKind, quoted_identifiers: &[&str]) {
+    let Node::Statement(stmt) = node else {
+        return;
+    };
+
+    let Some(NodeValue::Other(ref text)) = stmt.value else {
+        return;
+    };
+
+    let is_string = match kind {
+        ArgumentKind::Text
+        | ArgumentKind::IdentifierList
+        | ArgumentKind::SchemaNodeId
+        | ArgumentKind::XPath
+        | ArgumentKind::Path
+        | ArgumentKind::IfFeatureExpr => true,
+        ArgumentKind::Identifier => quoted_identifiers.contains(&stmt.keyword.text()),
+        _ => false,
+    };
+
+    if !is_string || text == "true" || text == "false" {
+        return;
+    }
+
+    // Unquoted strings have no escape processing
+    let quoted = format!("\"{}\"", escape_double_quoted(text));
+
+    stmt.value = Some(NodeValue::String(quoted));
+}
+
+lazy_static! {
+    /// Matches the string contents that are always safe to write without quotes
+    static ref SIMPLE_ARGUMENT_PATTERN: Regex = Regex::new(concat!(
+        r"^(?:",
+        r"(?P<number>-?(?:0|[1-9][0-9]*)(?:\.[0-9]+)?)",
+        r"|(?P<date>[0-9]{4}-[0-9]{2}-[0-9]{2})",
+        r"|(?:[A-Za-z_][A-Za-z0-9_.-]*:)?[A-Za-z_][A-Za-z0-9_.-]*",
+        r")$",
+    ))
+    .unwrap();
+}
+
+/// Keywords with free-form text arguments that are unquoted by "unquote_simple_argument"
+const UNQUOTED_TEXT_KEYWORDS: &[&str] = &["default", "enum"];
+
+/// Removes the quotes around simple identifiers, numbers and dates
+///
+/// Essentially converts:
+///
+///     prefix "inet";
+///     default "5";
+///
+/// Into:
+///
+///     prefix inet;
+///     default 5;
+///
+/// Prose, expressions and the arguments of extensions without a rule are always left quoted, even
+/// if they consist of a single word.
+///
+fn unquote_simple_argument(node: &mut Node, kind: ArgumentKind) {
+    let Node::Statement(stmt) = node else {
+        return;
+    };
+
+    let Some(NodeValue::String(ref text)) = stmt.value else {
+        return;
+    };
+
+    let may_unquote = match kind {
+        ArgumentKind::Identifier
+        | ArgumentKind::IdentifierRef
+        | ArgumentKind::IdentifierList
+        | ArgumentKind::Boolean
+        | ArgumentKind::Date
+        | ArgumentKind::Number
+        | ArgumentKind::Keyword => true,
+        ArgumentKind::Text => UNQUOTED_TEXT_KEYWORDS.contains(&stmt.keyword.text()),
+        _ => false,
+    };
+
+    if !may_unquote {
+        return;
+    }
+
+    let content = &text[1..text.len() - 1];
+
+    let Some(captures) = SIMPLE_ARGUMENT_PATTERN.captures(content) else {
+        return;
+    };
+
+    let content = content.to_string();
+
+    stmt.value = Some(if captures.name("number").is_some() {
+        NodeValue::Number(content)
+    } else if captures.name("date").is_some() {
+        NodeValue::Date(content)
+    } else {
+        NodeValue::Other(content)
+    });
+}
+
+/// Keywords whose identifier argument is affected by "FormatConfig::identifier_quoting"
+const IDENTIFIER_QUOTING_KEYWORDS: &[&str] = &["prefix", "belongs-to", "base", "type", "uses"];
+
+/// Quotes or unquotes the identifier arguments of "prefix", "type" and friends
+///
+/// Quoted values are only unquoted if they're plain, possibly prefixed, identifiers.
+///
+fn normalize_identifier_quoting(node: &mut Node, quoting: IdentifierQuoting) {
+    let Node::Statement(stmt) = node else {
+        return;
+    };
+
+    if !IDENTIFIER_QUOTING_KEYWORDS.contains(&stmt.keyword.text()) {
+        return;
+    }
+
+    stmt.value = match (quoting, stmt.value.take()) {
+        (IdentifierQuoting::Quoted, Some(NodeValue::Other(text))) => Some(NodeValue::String(
+            format!("\"{}\"", escape_double_quoted(&text)),
+        )),
+        (IdentifierQuoting::Bare, Some(NodeValue::String(text))) => {
+            let content = &text[1..text.len() - 1];
+
+            match SIMPLE_ARGUMENT_PATTERN.captures(content) {
+                Some(captures)
+                    if captures.name("number").is_none() && captures.name("date").is_none() =>
+                {
+                    Some(NodeValue::Other(content.to_string()))
+                }
+                _ => Some(NodeValue::String(text)),
+            }
+        }
+        (_, value) => value,
+    };
+}
+
+/// Converts double-quoted regular expressions with escaped backslashes to single quotes
+///
+/// Single-quoted strings have no escape processing, so this converts:
+///
+///     pattern "\\d+(\\.\\d+)?";
+///
+/// Into:
+///
+///     pattern '\d+(\.\d+)?';
+///
+/// Strings using any other escape sequences, or containing single quotes, are left alone. The
+/// parts of a string concatenation are either all converted or all left alone, so the quoting
+/// within a pattern stays consistent.
+///
+fn prefer_single_quoted_regex(node: &mut Node) {
+    let Node::Statement(stmt) = node else {
+        return;
+    };
+
+    if !REGEX_KEYWORDS.contains(&stmt.keyword.text()) {
+        return;
+    }
+
+    let single_quoted = |string: &str| {
+        if string.starts_with('\'') {
+            return Some(string.to_string());
+        }
+
+        match unescape_quotes_and_backslashes(&string[1..string.len() - 1]) {
+            Some(literal) if !literal.contains('\'') => Some(format!("'{literal}'")),
+            _ => None,
+        }
+    };
+
+    let strings: Vec<&mut String> = match stmt.value {
+        Some(NodeValue::String(ref mut string)) => vec![string],
+        Some(NodeValue::StringConcatenation(ref mut strings)) => {
+            strings.iter_mut().map(|(string, _)| string).collect()
+        }
+        _ => return,
+    };
+
+    if !strings.iter().any(|string| string.contains('\\')) {
+        return;
+    }
+
+    let Some(converted) = strings
+        .iter()
+        .map(|string| single_quoted(string))
+        .collect::<Option<Vec<_>>>()
+    else {
+        return;
+    };
+
+    for (string, converted) in strings.into_iter().zip(converted) {
+        *string = converted;
+    }
+}
+
+/// Strips all leading and trailing whitespace from string values
+///
+/// Returns true if the string only had whitespace, which is all removed.
+///
+fn strip_string(node: &mut Node) -> bool {
+    if let Some(NodeValue::String(ref mut text)) = node.node_value_mut() {
+        let slice = text.as_str();
+        let slice = &slice[1..slice.len() - 1]; // Without the quotes
+
+        let text_start = 1 + match slice.find(|c: char| !c.is_ascii_whitespace()) {
+            Some(pos) => pos,
+            None => {
+                // None means the string doesn't contain any non-whitespace characters, just
+                // replace it with an empty string
+                let quotechar = text.remove(0);
+                let emptied = text.len() > 1;
+
+                text.clear();
+                text.push(quotechar);
+                text.push(quotechar);
+                return emptied;
+            }
+        };
+
+        let text_end = text.len()
+            - slice
+                .chars()
+                .rev()
+                .position(|c| !c.is_whitespace())
+                .unwrap_or(0)
+            - 2;
+
+        if text_end < (text.len() - 2) {
+            text.drain(text_end + 1..text.len() - 1);
+        }
+
+        if text_start > 1 {
+            text.drain(1..text_start);
+        }
+    }
+
+    false
+}
+
+/// Dedents multi-lined strings
+///
+/// Multi-lined strings in YANG are practically always indented to match the context. Since we
+/// might completely change the indent around strings, we might as well dedent the strings and
+/// recalculate the indentation later during formatting.
+///
+/// This function assumes any strings have already been stripped, see "strip_string".
+///
+fn dedent_multilined_string(node: &mut Node) {
+    let value = if let Some(value) = node.node_value() {
+        value
+    } else {
+        return;
+    };
+
+    let text = if let NodeValue::String(text) = value {
+        text
+    } else {
+        return;
+    };
+
+    let quotechar = text.chars().next().unwrap();
+
+    // Strips off the quote characters
+    let text = &text[1..text.len() - 1];
+    let lines: Vec<_> = text.lines().collect();
+
+    if lines.len() < 2 {
+        return;
+    }
+
+    // The first line is often right at the opening quote, so it doesn't make sense to include it
+    // in the text that gets dedented
+    let first_line = lines.first().unwrap();
+
+    let rest = lines.get(1..).unwrap().join("\n");
+    let rest = textwrap::dedent(&rest);
+
+    let new_text = format!("{}{}\n{}{}", quotechar, first_line, rest, quotechar);
+
+    match node {
+        Node::Statement(ref mut node) => node.value = Some(NodeValue::String(new_text)),
+        _ => unreachable!("If node isn't a statement, how did we get the mutable value?"),
+    };
+}
+
+/// Statements whose string values are considered prose, and are re-wrapped by "reflow_prose"
+const PROSE_KEYWORDS: &[&str] = &[
+    "description",
+    "reference",
+    "contact",
+    "error-message",
+    "error-app-tag",
+];
+
+/// Re-wraps the text of prose strings to fit within the max width
+///
+/// The width available to the text is calculated as if the string was moved to the line below
+/// the keyword, which is always the case for multi-lined strings.
+///
+/// This function assumes any strings have already been stripped and dedented.
+///
+fn reflow_prose(node: &mut Node, config: &FormatConfig, depth: u16) {
+    let Node::Statement(stmt) = node else {
+        return;
+    };
+
+    if !PROSE_KEYWORDS.contains(&stmt.keyword.text()) {
+        return;
+    }
+
+    if let Some(NodeValue::String(ref mut text)) = stmt.value {
+        let quotechar = &text[..1];
+        let content = &text[1..text.len() - 1];
+
+        let text_column =
+            depth as usize * config.indent_width() as usize + config.continuation_width() as usize;
+
+        // Leaves room for the opening quote, closing quote and semicolon
+        let max_width = config.max_width(stmt.keyword.text()) as usize;
+        let width = max_width.saturating_sub(text_column + 3);
+
+        let reflowed = reflow_text(content, width);
+
+        *text = format!("{quotechar}{reflowed}{quotechar}");
+    }
+}
+
+/// Splits single-line strings that can't fit within the max width into string concatenations
+///
+/// Strings are only split if they'd overflow even when moved to the line below the keyword.
+/// Essentially converts:
+///
+///     description
+///         "This string is far too long to fit on any line, no matter how it's indented";
+///
+/// Into:
+///
+///     description "This string is far too long to fit on any line, "
+///               + "no matter how it's indented";
+///
+fn split_long_string(node: &mut Node, config: &FormatConfig, depth: u16) {
+    let Node::Statement(stmt) = node else {
+        return;
+    };
+
+    let (is_path, enabled) = match config.argument_kind(&stmt.keyword) {
+        ArgumentKind::SchemaNodeId => (true, config.split_long_strings || config.split_long_paths),
+        ArgumentKind::Path => (
+            true,
+            config.split_long_strings || config.path_wrapping == PathWrapping::Slash,
+        ),
+        _ => (false, config.split_long_strings),
+    };
+
+    if !enabled {
+        return;
+    }
+
+    let Some(NodeValue::String(ref text)) = stmt.value else {
+        return;
+    };
+
+    if text.contains('\n') || config.is_unbreakable(text) {
+        return;
+    }
+
+    let indent = depth as usize * config.indent_width() as usize;
+    let line_length = config.max_width(stmt.keyword.text()) as usize;
+
+    // The string, plus a semicolon, on the line below the keyword. Like in "find_long_lines", a
+    // line as long as the max width fits.
+    let next_line_length = indent + config.continuation_width() as usize + text.chars().count() + 1;
+
+    if next_line_length <= line_length {
+        return;
+    }
+
+    // The first string is right after the keyword, and the continuation strings are placed as
+    // configured, after the plus if it's leading. Every chunk has to fit in the narrower of the
+    // two. Leaves room for the quotes, and a trailing plus or semicolon.
+    let operator_width = match config.concat_operator {
+        ConcatOperatorStyle::Leading => 2,
+        ConcatOperatorStyle::Trailing => 0,
+    };
+    let text_column = indent
+        + (stmt.keyword.text().len() + 1)
+            .max(config.concat_offset(stmt.keyword.text()) + operator_width);
+    let width = line_length.saturating_sub(text_column + 4);
+
+    if width < 10 {
+        return;
+    }
+
+    let quotechar = &text[..1];
+    let content = &text[1..text.len() - 1];
+
+    // Paths are only ever split between node identifiers, the way hand-written models do it
+    let chunks = if is_path {
+        split_path_segments(content, width)
+    } else {
+        split_string_content(content, width)
+    };
+
+    if chunks.len() < 2 {
+        return;
+    }
+
+    let concat = chunks
+        .into_iter()
+        .map(|chunk| (format!("{quotechar}{chunk}{quotechar}"), vec![]))
+        .collect();
+
+    stmt.value = Some(NodeValue::StringConcatenation(concat));
+}
+
+/// Wraps long XPath expressions in "when" and "must" statements, and long if-feature expressions,
+/// at logical operators
+///
+/// XPath expressions are only wrapped if "wrap_xpath" is enabled. Essentially converts:
+///
+///     must "../type = 'ethernet' or ../type = 'loopback' or ../type = 'tunnel'";
+///
+/// Into:
+///
+///     must
+///       "../type = 'ethernet'
+///          or ../type = 'loopback'
+///          or ../type = 'tunnel'";
+///
+/// Expressions that fit on one line are collapsed into one line.
+///
+fn wrap_expression(node: &mut Node, config: &FormatConfig, depth: u16) {
+    let Node::Statement(stmt) = node else {
+        return;
+    };
+
+    let is_xpath = match config.argument_kind(&stmt.keyword) {
+        ArgumentKind::XPath if config.wrap_xpath => true,
+        ArgumentKind::IfFeatureExpr if !config.preserve_strings => false,
+        _ => return,
+    };
+
+    let Some(NodeValue::String(ref mut text)) = stmt.value else {
+        return;
+    };
+
+    let quotechar = &text[..1];
+
+    let content = &text[1..text.len() - 1];
+
+    let normalized = if is_xpath {
+        normalize_whitespace(content)
+    } else {
+        normalize_if_feature(content)
+    };
+
+    let Some(expr) = normalized else {
+        return;
+    };
+
+    let indent = depth as usize * config.indent_width() as usize;
+
+    // Indent, keyword, a space, the quoted expression and a semicolon
+    let single_line_length = indent + stmt.keyword.text().len() + expr.chars().count() + 4;
+
+    let max_width = config.max_width(stmt.keyword.text()) as usize;
+
+    let lines = if single_line_length <= max_width {
+        vec![expr]
+    } else {
+        // Leaves room for the opening quote, closing quote and semicolon
+        let text_column = indent + config.continuation_width() as usize;
+        let width = max_width.saturating_sub(text_column + 3);
+
+        if is_xpath {
+            wrap_xpath(&expr, width)
+        } else {
+            wrap_at_operators(&expr, width)
+        }
+    };
+
+    *text = format!("{quotechar}{}{quotechar}", lines.join("\n"));
+}
+
+/// Joins string concatenations that fit on the keyword line back into a single string
+///
+/// Concatenations are left alone if any of the strings have comments attached, if the strings
+/// use different quote characters or if the statement has a "// keep" comment. Essentially
+/// converts:
+///
+///     pattern "[a-z]"
+///           + "[0-9]";
+///
+/// Into:
+///
+///     pattern "[a-z][0-9]";
+///
+fn merge_short_concatenation(node: &mut Node, config: &FormatConfig, depth: u16) {
+    let Node::Statement(stmt) = node else {
+        return;
+    };
+
+    let Some(NodeValue::StringConcatenation(ref concat)) = stmt.value else {
+        return;
+    };
+
+    // Only the marker itself, so comments like "// keeping this for compat" don't count
+    let is_keep_comment = |comment: &String| {
+        let text = comment
+            .trim_start_matches(['/', '*'])
+            .trim_end_matches(['/', '*'])
+            .trim();
+
+        text == "keep" || text == "yangfmt: keep"
+    };
+
+    if stmt.post_comments.iter().any(is_keep_comment)
+        || stmt.value_comments.iter().any(is_keep_comment)
+        || concat.iter().any(|(_, comments)| !comments.is_empty())
+    {
+        return;
+    }
+
+    let quotechar = &concat[0].0[..1];
+
+    if concat
+        .iter()
+        .any(|(string, _)| !string.starts_with(quotechar) || string.contains('\n'))
+    {
+        return;
+    }
+
+    let mut merged = String::from(quotechar);
+
+    for (string, _) in concat {
+        merged.push_str(&string[1..string.len() - 1]);
+    }
+
+    merged.push_str(quotechar);
+
+    // Indent, keyword, a space, the string and a semicolon
+    let line_length = depth as usize * config.indent_width() as usize
+        + stmt.keyword.text().len()
+        + merged.chars().count()
+        + 2;
+
+    if line_length > config.max_width(stmt.keyword.text()) as usize {
+        return;
+    }
+
+    stmt.value = Some(NodeValue::String(merged));
+}
+
+/// Writes the node tree to the given writeable object
+///
+/// This automatically handles indentation and spacing between nodes. However, it does not process
+/// node order, line breaks and things like that. That is handled by a pre-processing step.
+///
+/// (This function leaves no trailing line break)
+///
+fn write_node<T: std::io::Write>(
+    out: &mut T,
+    node: &Node,
+    config: &FormatConfig,
+    depth: u16,
+) -> Result<(), Error> {
+    macro_rules! indent {
+        ($depth:expr) => {
+            for _ in 0..$depth {
+                match config.indent {
+                    // Indent::Tab => {
+                    //     write!(out, "\t")?;
+                    // }
+                    Indent::Spaces(spaces) => {
+                        for _ in 0..spaces {
+                            write!(out, " ")?;
+                        }
+                    }
+                }
+            }
+        };
+    }
+
+    macro_rules! continuation_indent {
+        ($depth:expr) => {
+            indent!($depth);
+
+            for _ in 0..config.continuation_width() {
+                write!(out, " ")?;
+            }
+        };
+    }
+
+    // Writes a comment after a space, with the continuation lines of multi-line block comments
+    // indented like the statement
+    macro_rules! write_comment {
+        ($comment:expr) => {
+            let text = comment_text($comment, config);
+            let mut lines = text.split('\n');
+
+            write!(out, " {}", lines.next().unwrap_or_default())?;
+
+            for line in lines {
+                writeln!(out)?;
+
+                if !line.trim().is_empty() {
+                    indent!(depth);
+                    write!(out, "{line}")?;
+                }
+            }
+        };
+    }
+
+    macro_rules! write_keyword {
+        ($node:expr) => {
+            match $node.keyword {
+                StatementKeyword::Keyword(ref text) => write!(out, "{text}")?,
+                StatementKeyword::ExtensionKeyword(ref text) => write!(out, "{text}")?,
+                StatementKeyword::Invalid(ref text) => write!(out, "{text}")?,
+            };
+
+            for comment in $node.keyword_comments.as_slice() {
+                write_comment!(comment);
+            }
+
+            // This is where keyword comment would be written, but since the formatting rules will
+            // move them all, there will never be anything to write.
+        };
+    }
+
+    macro_rules! write_simple_value {
+        ($keyword:expr, $line_pos:expr, $value:expr) => {{
+            // Checks if the line will be longer than the configured max width
+            //
+            // Line length = indent + keyword + value + a space + a semicolon
+            let wrap = match config.value_placement($keyword) {
+                ValuePlacement::Auto => {
+                    !config.never_wraps($keyword)
+                        && !config.is_unbreakable($value)
+                        && $line_pos + ($value.len() as u16) + 2 > config.max_width($keyword)
+                }
+                ValuePlacement::AlwaysNewline => true,
+                ValuePlacement::NeverNewline => false,
+            };
+
+            if wrap {
+                writeln!(out)?;
+
+                match config.wrapped_value_indent {
+                    WrappedValueIndent::Indent => {
+                        continuation_indent!(depth);
+                    }
+                    WrappedValueIndent::Aligned => {
+                        for _ in 0..$line_pos + 1 {
+                            write!(out, " ")?;
+                        }
+                    }
+                    WrappedValueIndent::Column(column) => {
+                        let indent_width = config.indent_width() as u16 * depth
+                            + config.continuation_width() as u16;
+
+                        for _ in 0..column.max(indent_width) {
+                            write!(out, " ")?;
+                        }
+                    }
+                }
+            } else {
+                write!(out, " ")?;
+            }
+
+            write!(out, "{}", $value)?;
+
+            wrap
+        }};
+    }
+
+    // Writes the statement value, evaluates to true if the value was moved to the next line
+    macro_rules! write_value {
+        ($node:expr) => {{
+            let kw_text = $node.keyword.text();
+            let line_pos: u16 = (config.indent_width() as u16) * depth + (kw_text.len() as u16);
+
+            let wrapped = match $node.value.as_ref().unwrap() {
+                NodeValue::Date(text) => write_simple_value!(kw_text, line_pos, text),
+                NodeValue::Number(text) => write_simple_value!(kw_text, line_pos, text),
+                NodeValue::Other(text) => write_simple_value!(kw_text, line_pos, text),
+                NodeValue::String(text) => {
+                    if (text.contains('\n')) {
+                        let same_line = matches!(
+                            config.value_placement(kw_text),
+                            ValuePlacement::NeverNewline
+                        );
+
+                        // Multi-lined strings need to be indented, unless they're explicitly kept
+                        // on the same line as the keyword
+                        if same_line {
+                            write!(out, " ")?;
+                        } else {
+                            writeln!(out)?;
+                            continuation_indent!(depth);
+                        }
+
+                        let own_line = config.closing_quote == ClosingQuotePlacement::OwnLine
+                            && config.reindents_strings();
+
+                        // With the closing quote on its own line, the rest of the string is
+                        // written without it, and without any line break or indentation in front
+                        // of it
+                        let (body, closing_quote) = if own_line {
+                            let (body, quote) = text.split_at(text.len() - 1);
+                            let body = body.trim_end_matches([' ', '\t']);
+
+                            (body.strip_suffix('\n').unwrap_or(body), quote)
+                        } else {
+                            (text.as_str(), "")
+                        };
+
+                        let mut lines = body.lines();
+
+                        // The first line is written normally
+                        write!(out, "{}", lines.next().unwrap())?;
+
+                        // Each subsequent non-empty line are indented to match the starting column
+                        // of the first line, i.e. right after the quote. Strings with preserved
+                        // indentation are written exactly as they are.
+                        while let Some(line) = lines.next() {
+                            writeln!(out)?;
+
+                            if !line.is_empty() && config.reindents_strings() {
+                                if same_line {
+                                    indent!(depth);
+
+                                    for _ in 0..kw_text.len() + 2 {
+                                        write!(out, " ")?;
+                                    }
+                                } else {
+                                    continuation_indent!(depth);
+                                    write!(out, " ")?;
+                                }
+                            }
+
+                            write!(out, "{}", line)?;
+                        }
+
+                        if own_line {
+                            writeln!(out)?;
+
+                            if same_line {
+                                indent!(depth);
+
+                                for _ in 0..kw_text.len() + 1 {
+                                    write!(out, " ")?;
+                                }
+                            } else {
+                                continuation_indent!(depth);
+                            }
+
+                            write!(out, "{closing_quote}")?;
+                        }
+
+                        !same_line
+                    } else {
+                        write_simple_value!(kw_text, line_pos, text)
+                    }
+                }
+                NodeValue::StringConcatenation(concat) => {
+                    let last = concat.len() - 1;
+
+                    for (i, (ref string, ref comments)) in concat.iter().enumerate() {
+                        if i == 0 {
+                            // The first string gets written on the same line as the keywords
+                            write!(out, " {}", string)?;
+                        } else {
+                            // The rest get displayed on new lines, by default padded to align
+                            // with the first string
+                            writeln!(out)?;
+                            indent!(depth);
+
+                            let leading = config.concat_operator == ConcatOperatorStyle::Leading;
+
+                            for _ in 0..config.concat_offset(kw_text) {
+                                write!(out, " ")?
+                            }
+
+                            if leading {
+                                write!(out, "+ {}", string)?;
+                            } else {
+                                write!(out, "{}", string)?;
+                            }
+                        }
+
+                        // With trailing operators, the plus has to come before any comments since
+                        // a line comment would swallow it
+                        if i < last && config.concat_operator == ConcatOperatorStyle::Trailing {
+                            write!(out, " +")?;
+                        }
+
+                        for comment in comments {
+                            write_comment!(comment);
+                        }
+                    }
+
+                    false
+                }
+            };
+
+            for comment in $node.value_comments.as_slice() {
+                write_comment!(comment);
+            }
+
+            wrapped
+        }};
+    }
+
+    match node {
+        Node::Statement(node) => {
+            indent!(depth);
+            write_keyword!(node);
+
+            if let Some(rest) = single_line_block(node, config, depth) {
+                writeln!(out, "{rest}")?;
+                return Ok(());
+            }
+
+            let value_wrapped = node.value.is_some() && write_value!(node);
+
+            if node.children.as_ref().is_some_and(|c| c.is_empty()) && config.collapse_empty_blocks
+            {
+                write!(out, " {{}}")?;
+
+                for comment in &node.post_comments {
+                    write_comment!(comment);
+                }
+            } else if let Some(ref children) = node.children {
+                write!(out, " {{")?;
+
+                for comment in &node.post_comments {
+                    write_comment!(comment);
+                }
+
+                writeln!(out)?;
+
+                write_nodes(out, children, config, depth + 1)?;
+
+                indent!(depth);
+                write!(out, "}}")?;
+            } else {
+                if value_wrapped {
+                    match config.semicolon_placement {
+                        SemicolonPlacement::Attached => (),
+                        SemicolonPlacement::NextLine => {
+                            writeln!(out)?;
+                            continuation_indent!(depth);
+                        }
+                        SemicolonPlacement::UnderKeyword => {
+                            writeln!(out)?;
+                            indent!(depth);
+                        }
+                    }
+                }
+
+                write!(out, ";")?;
+
+                for comment in &node.post_comments {
+                    write_comment!(comment);
+                }
+            }
+
+            writeln!(out)?; // All statements implicitly end with a line break
+        }
+
+        Node::Comment(text) => {
+            let text = comment_text(text, config);
+            let mut lines = text.split('\n');
+
+            indent!(depth);
+            write!(out, "{}", lines.next().unwrap_or_default())?;
+
+            // Lines of block comments with a gutter of asterisks are lined up under the first
+            // asterisk of the opening "/*", unless the comment is a box that must be left alone
+            let gutter =
+                !is_banner(&text) && lines.clone().all(|line| line.trim_start().starts_with('*'));
+
+            for line in lines {
+                writeln!(out)?;
+
+                if line.trim().is_empty() {
+                    continue;
+                }
+
+                indent!(depth);
+
+                if gutter {
+                    write!(out, " {}", line.trim_start())?;
+                } else {
+                    write!(out, "{line}")?;
+                }
+            }
+
+            writeln!(out)?;
+        }
+
+        Node::EmptyLine(_) => {
+            writeln!(out)?;
+        }
+
+        Node::Raw(text) => {
+            write!(out, "{text}")?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Writes a list of sibling nodes
+///
+/// If any of the alignment options are enabled, all the nodes are written to buffers
+/// first so each run of consecutive single-line statements can be padded to line up.
+///
+fn write_nodes<T: std::io::Write>(
+    out: &mut T,
+    nodes: &[Node],
+    config: &FormatConfig,
+    depth: u16,
+) -> Result<(), Error> {
+    if !config.align_values && !config.align_enum_values && !config.align_trailing_comments {
+        for node in nodes {
+            write_node(out, node, config, depth)?;
+        }
+
+        return Ok(());
+    }
+
+    let mut rendered = vec![];
+
+    for node in nodes {
+        let mut buffer: Vec<u8> = vec![];
+        write_node(&mut buffer, node, config, depth)?;
+        rendered.push((node, buffer));
+    }
+
+    if config.align_enum_values {
+        align_column(&mut rendered, enum_block_start);
+    }
+
+    if config.align_values {
+        let indent_width = depth as usize * config.indent_width() as usize;
+
+        align_column(&mut rendered, |node, buffer| {
+            value_start(node, buffer, indent_width)
+        });
+    }
+
+    if config.align_trailing_comments {
+        align_column(&mut rendered, |node, buffer| {
+            trailing_comment_start(node, buffer, config)
+        });
+    }
+
+    for (_, buffer) in rendered {
+        out.write_all(&buffer)?;
+    }
+
+    Ok(())
+}
+
+/// Pads the rendered nodes so the given position lines up in a column for each run of consecutive
+/// nodes where "find_start" returns a byte offset
+fn align_column(
+    rendered: &mut [(&Node, Vec<u8>)],
+    find_start: impl Fn(&Node, &[u8]) -> Option<usize>,
+) {
+    let width =
+        |buffer: &[u8], start: usize| String::from_utf8_lossy(&buffer[..start]).chars().count();
+
+    let mut items: Vec<_> = rendered
+        .iter_mut()
+        .map(|(node, buffer)| {
+            let start = find_start(node, buffer);
+            (buffer, start)
+        })
+        .collect();
+
+    for group in items.chunk_by_mut(|a, b| a.1.is_some() && b.1.is_some()) {
+        let column = group
+            .iter()
+            .filter_map(|(buffer, start)| start.map(|start| width(buffer, start)))
+            .max();
+
+        for (buffer, start) in group.iter_mut() {
+            if let (Some(start), Some(column)) = (*start, column) {
+                let padding = column - width(buffer, start);
+                buffer.splice(start..start, std::iter::repeat_n(b' ', padding));
+            }
+        }
+    }
+}
+
+/// Returns the byte offset of the space between the keyword and the value in the rendered output
+/// of a single-line statement
+fn value_start(node: &Node, rendered: &[u8], indent_width: usize) -> Option<usize> {
+    let Node::Statement(stmt) = node else {
+        return None;
+    };
+
+    if stmt.children.is_some() || stmt.value.is_none() || !is_single_line(rendered) {
+        return None;
+    }
+
+    Some(indent_width + stmt.keyword.text().len())
+}
+
+/// Returns the byte offset of the space before the block of an enum written on one line by
+/// "single_line_block"
+fn enum_block_start(node: &Node, rendered: &[u8]) -> Option<usize> {
+    let Node::Statement(stmt) = node else {
+        return None;
+    };
+
+    if stmt.keyword.text() != "enum" || stmt.children.is_none() || !is_single_line(rendered) {
+        return None;
+    }
+
+    Some(rendered.iter().rposition(|c| *c == b'{')? - 1)
+}
+
+/// Returns the value and block of a statement if it can be written on one line, e.g.
+/// " up { value 1; }"
+///
+/// This applies to enums that only have a "value" substatement when "align_enum_values" is set,
+/// and to the keywords listed in "single_line_blocks" if all their children are simple statements.
+///
+fn single_line_block(stmt: &Statement, config: &FormatConfig, depth: u16) -> Option<String> {
+    let keyword = stmt.keyword.text();
+    let listed = config.single_line_blocks.iter().any(|k| k == keyword)
+        || config
+            .extension_rule(&stmt.keyword)
+            .is_some_and(|rule| rule.single_line);
+    let enum_table = config.align_enum_values && keyword == "enum";
+
+    if !(listed || enum_table) || !stmt.post_comments.is_empty() || !stmt.value_comments.is_empty()
+    {
+        return None;
+    }
+
+    let children = stmt.children.as_deref()?;
+    let max_children = if listed {
+        config.single_line_max_children
+    } else {
+        1
+    };
+
+    if children.is_empty() || children.len() > max_children {
+        return None;
+    }
+
+    let mut rest = String::new();
+
+    if let Some(ref value) = stmt.value {
+        rest.push(' ');
+        rest.push_str(simple_value_text(value)?);
+    }
+
+    rest.push_str(" {");
+
+    for child in children {
+        let Node::Statement(child) = child else {
+            return None;
+        };
+
+        if (!listed && child.keyword.text() != "value")
+            || child.children.is_some()
+            || !child.keyword_comments.is_empty()
+            || !child.value_comments.is_empty()
+            || !child.post_comments.is_empty()
+        {
+            return None;
+        }
+
+        rest.push(' ');
+        rest.push_str(child.keyword.text());
+
+        if let Some(ref value) = child.value {
+            rest.push(' ');
+            rest.push_str(simple_value_text(value)?);
+        }
+
+        rest.push(';');
+    }
+
+    rest.push_str(" }");
+
+    let width = keyword.len() + rest.len();
+    let line_length = depth as usize * config.indent_width() as usize + width;
+
+    if line_length > config.max_width(keyword) as usize
+        || config
+            .single_line_max_width
+            .is_some_and(|max_width| width > max_width as usize)
+    {
+        return None;
+    }
+
+    Some(rest)
+}
+
+/// Returns the text of a value that is written on a single line as is
+fn simple_value_text(value: &NodeValue) -> Option<&str> {
+    match value {
+        NodeValue::String(text) if !text.contains('\n') => Some(text),
+        NodeValue::Number(text) | NodeValue::Date(text) | NodeValue::Other(text) => Some(text),
+        _ => None,
+    }
+}
+
+/// Returns the byte offset of the trailing comments in the rendered output of a single-line
+/// statement, if it has any
+fn trailing_comment_start(node: &Node, rendered: &[u8], config: &FormatConfig) -> Option<usize> {
+    let Node::Statement(stmt) = node else {
+        return None;
+    };
+
+    if stmt.children.is_some() || stmt.post_comments.is_empty() || !is_single_line(rendered) {
+        return None;
+    }
+
+    // Each comment is written with a leading space, followed by the final line break
+    let comments_len: usize = stmt
+        .post_comments
+        .iter()
+        .map(|comment| comment_text(comment, config).len() + 1)
+        .sum();
+
+    Some(rendered.len() - 1 - comments_len)
+}
+
+/// Returns the text of a comment as it should be written
+fn comment_text<'a>(comment: &'a str, config: &FormatConfig) -> Cow<'a, str> {
+    let comment = if config.space_comment_markers {
+        space_comment_markers(comment)
+    } else {
+        Cow::Borrowed(comment)
+    };
+
+    if config.strip_trailing_whitespace && comment.lines().any(|line| line != line.trim_end()) {
+        let lines: Vec<&str> = comment.split('\n').map(str::trim_end).collect();
+        return Cow::Owned(lines.join("\n"));
+    }
+
+    comment
+}
+
+fn is_single_line(rendered: &[u8]) -> bool {
+    rendered
+        .strip_suffix(b"\n")
+        .is_some_and(|line| !line.contains(&b'\n'))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn dedent(text: &str) -> String {
+        let mut text = textwrap::dedent(text).trim().to_string();
+        text.push('\n');
+        text
+    }
+
+    /// Formats the input file into a String
+    fn format_yang_str(buffer: &[u8], config: &FormatConfig) -> Result<String, Error> {
+        let mut output: Vec<u8> = vec![];
+
+        format_yang(&mut output, buffer, config)?;
+
+        Ok(String::from_utf8(output).expect("Invalid UTF-8 in input file"))
+    }
+
+    #[test]
+    fn test_write_node() {
+        let input_string = dedent(
+            r#"
+                module foo {
+                bar "testing" ;
+                foo 123.45    ;
+
+
+                        revision 2022-02-02 {description "qwerty";} oh "dear";
+
+                }
+                "#,
+        );
+
+        let tree = parse(input_string.as_bytes()).expect("Failed to parse input");
+        let module_node = tree.children.first().expect("Failed to get module node");
+
+        let mut out: Vec<u8> = vec![];
+
+        let config = FormatConfig {
+            indent: Indent::Spaces(4),
+            line_length: 80,
+            fix_canonical_order: false,
+            ..Default::default()
+        };
+
+        write_node(&mut out, module_node, &config, 0).expect("Formatting failed");
+
+        let result = String::from_utf8(out).unwrap();
+
+        assert_eq!(
+            dedent(
+                r#"
+                module foo {
+                    bar "testing";
+                    foo 123.45;
+
+
+                    revision 2022-02-02 {
+                        description "qwerty";
+                    }
+                    oh "dear";
+
+                }
+                "#
+            ),
+            result,
+        );
+    }
+
+    #[test]
     fn test_format() {
         let result = format_yang_str(
             dedent(
                 r#"
-                //
-                // Comments outside the module block should be fine
-                //
+                //
+                // Comments outside the module block should be fine
+                //
+                module foo {
+
+                bar      testing  ;
+                foo      123.45   ;
+
+                revision 2022-02-03 {
+                }
+                    revision 2022-02-02
+                    { description "qwerty"; }
+
+                //
+                // Some string formatting tests
+                //
+
+                test "I am not affected";
+                test 'I am converted';
+                test 'These "quotes" should remain single';
+
+                description "I am short and sweet";
+                description "I should stay on this line line <----------------->";
+                description "I should be wrapped to the next line <------------->";
+                description "  I should be stripped   ";
+                description
+                    "
+                    I should be stripped and changed to 1 line
+                    ";
+                description "I am multi-lined,
+                    so I automatically get wrapped
+                    to the next line even though each
+                    individual line is short.";
+
+                description "
+                The first line break here should be removed
+
+                     Then the rest of the string should be properly indented.
+                     The trailing line breaks should also be removed.
+
+                ";
+
+                pattern '((:|[0-9a-fA-F]{0,4}):)([0-9a-fA-F]{0,4}:){0,5}'+'((([0-9a-fA-F]{0,4}:)?(:|[0-9a-fA-F]{0,4}))|'
+                + '(((25[0-5]|2[0-4][0-9]|[01]?[0-9]?[0-9])\.){3}'
+                 + '(25[0-5]|2[0-4][0-9]|[01]?[0-9]?[0-9])))'
+                + '(%[\p{N}\p{L}]+)?';
+
+                pattern
+                "foo" + 'bar'
+                + 'baz';
+
+                augment "/foo"+"/bar"
+                +"/baz"
+                {
+
+                }
+
+                //
+                // Empty blocks
+                //
+
+                test{}
+
+                test{
+                }
+
+                test{
+
+                }
+
+                //
+                // Comments
+                //
+
+                test // This sometimes happens and must be supported
+                {
+                    foo bar;
+                }
+
+                test "something" // This sometimes happens and must be supported
+                {
+                    foo bar;
+                }
+
+                test "foo" /* This would be weird */ /* But let's support it anyway */
+                {
+                    foo bar;
+                }
+
+                test /* foo */ /* bar */ /* baz */ "foo" /* pow */
+                {
+                    // Nobody's ever going to do this (hopefully) so let's not even bother trying
+                    // to make it prettier. Just don't crash.
+                }
+
+                test "foo"; // A comment here is fine
+                test "foo" /* This however, is not fine*/ ;
+                test /* Nobody would ever do this, let's just not crash */ "foo" /* yuck */ ;
+
+                //
+                // Canonical order
+                //
+
+                leaf moo {
+                    description "I should not be sorted because sorting is not enabled";
+                    type string;
+                }
+                }"#,
+            )
+            .as_bytes(),
+            &(FormatConfig {
+                indent: Indent::Spaces(4),
+                line_length: 70,
+                fix_canonical_order: false,
+                ..Default::default()
+            }),
+        )
+        .unwrap();
+
+        assert_eq!(
+            dedent(
+                r#"
+                //
+                // Comments outside the module block should be fine
+                //
+                module foo {
+                    bar testing;
+                    foo 123.45;
+
+                    revision 2022-02-03 {
+                    }
+                    revision 2022-02-02 {
+                        description "qwerty";
+                    }
+
+                    //
+                    // Some string formatting tests
+                    //
+
+                    test "I am not affected";
+                    test "I am converted";
+                    test 'These "quotes" should remain single';
+
+                    description "I am short and sweet";
+                    description "I should stay on this line line <----------------->";
+                    description
+                        "I should be wrapped to the next line <------------->";
+                    description "I should be stripped";
+                    description "I should be stripped and changed to 1 line";
+                    description
+                        "I am multi-lined,
+                         so I automatically get wrapped
+                         to the next line even though each
+                         individual line is short.";
+
+                    description
+                        "The first line break here should be removed
+
+                         Then the rest of the string should be properly indented.
+                         The trailing line breaks should also be removed.";
+
+                    pattern '((:|[0-9a-fA-F]{0,4}):)([0-9a-fA-F]{0,4}:){0,5}'
+                          + '((([0-9a-fA-F]{0,4}:)?(:|[0-9a-fA-F]{0,4}))|'
+                          + '(((25[0-5]|2[0-4][0-9]|[01]?[0-9]?[0-9])\.){3}'
+                          + '(25[0-5]|2[0-4][0-9]|[01]?[0-9]?[0-9])))'
+                          + '(%[\p{N}\p{L}]+)?';
+
+                    pattern "foo"
+                          + "bar"
+                          + "baz";
+
+                    augment "/foo"
+                          + "/bar"
+                          + "/baz" {
+                    }
+
+                    //
+                    // Empty blocks
+                    //
+
+                    test {
+                    }
+
+                    test {
+                    }
+
+                    test {
+                    }
+
+                    //
+                    // Comments
+                    //
+
+                    test { // This sometimes happens and must be supported
+                        foo bar;
+                    }
+
+                    test "something" { // This sometimes happens and must be supported
+                        foo bar;
+                    }
+
+                    test "foo" { /* This would be weird */ /* But let's support it anyway */
+                        foo bar;
+                    }
+
+                    test "foo" { /* foo */ /* bar */ /* baz */ /* pow */
+                        // Nobody's ever going to do this (hopefully) so let's not even bother trying
+                        // to make it prettier. Just don't crash.
+                    }
+
+                    test "foo"; // A comment here is fine
+                    test "foo"; /* This however, is not fine*/
+                    test "foo"; /* Nobody would ever do this, let's just not crash */ /* yuck */
+
+                    //
+                    // Canonical order
+                    //
+
+                    leaf moo {
+                        description
+                            "I should not be sorted because sorting is not enabled";
+                        type string;
+                    }
+                }
+                "#
+            ),
+            result,
+        );
+    }
+
+    #[test]
+    fn test_format_with_fix_canonical_order() {
+        let result = format_yang_str(
+            dedent(
+                r#"
+                leaf {
+                    type string;
+
+
+                    description "I should be moved to the bottom";
+
+                    must "foo" {
+                        // ...
+                    }
+                }
+                "#,
+            )
+            .as_bytes(),
+            &(FormatConfig {
+                indent: Indent::Spaces(4),
+                line_length: 70,
+                fix_canonical_order: true,
+                force_sort: true,
+                ..Default::default()
+            }),
+        )
+        .unwrap();
+
+        assert_eq!(
+            dedent(
+                r#"
+                leaf {
+                    type string;
+
+                    must "foo" {
+                        // ...
+                    }
+
+                    description "I should be moved to the bottom";
+                }
+                "#
+            ),
+            result,
+        );
+    }
+
+    #[test]
+    fn test_format_with_continuation_indent() {
+        let result = format_yang_str(
+            dedent(
+                r#"
+                container foo {
+                    description "I should be wrapped to the next line <--------->";
+                    description "I am multi-lined,
+                                 and my lines follow the continuation indent";
+                }
+                "#,
+            )
+            .as_bytes(),
+            &(FormatConfig {
+                indent: Indent::Spaces(2),
+                continuation_indent: Some(4),
+                line_length: 60,
+                ..Default::default()
+            }),
+        )
+        .unwrap();
+
+        assert_eq!(
+            dedent(
+                r#"
+                container foo {
+                  description
+                      "I should be wrapped to the next line <--------->";
+                  description
+                      "I am multi-lined,
+                       and my lines follow the continuation indent";
+                }
+                "#
+            ),
+            result,
+        );
+    }
+
+    #[test]
+    fn test_format_with_collapse_empty_blocks() {
+        let result = format_yang_str(
+            dedent(
+                r#"
+                container foo {
+                    container bar {
+
+                    }
+                    container baz { // I am still here
+                    }
+                    container qux {
+                        // Not empty
+                    }
+                }
+                "#,
+            )
+            .as_bytes(),
+            &(FormatConfig {
+                collapse_empty_blocks: true,
+                ..Default::default()
+            }),
+        )
+        .unwrap();
+
+        assert_eq!(
+            dedent(
+                r#"
+                container foo {
+                  container bar {}
+                  container baz {} // I am still here
+                  container qux {
+                    // Not empty
+                  }
+                }
+                "#
+            ),
+            result,
+        );
+    }
+
+    #[test]
+    fn test_format_with_separate_definitions() {
+        let result = format_yang_str(
+            dedent(
+                r#"
+                module foo {
+                    typedef foo {
+                        type string;
+                    }
+                    typedef bar {
+                        type string;
+                    }
+                    // Comments stay with the statement below
+                    grouping baz {
+                        leaf a {
+                            type foo;
+                        }
+                        leaf b {
+                            type bar;
+                        }
+                    }
+
+
+                    container qux {
+                        uses baz;
+                    }
+                    leaf not-a-definition {
+                        type string;
+                    }
+                }
+                "#,
+            )
+            .as_bytes(),
+            &(FormatConfig {
+                separate_definitions: true,
+                ..Default::default()
+            }),
+        )
+        .unwrap();
+
+        assert_eq!(
+            dedent(
+                r#"
+                module foo {
+                  typedef foo {
+                    type string;
+                  }
+
+                  typedef bar {
+                    type string;
+                  }
+
+                  // Comments stay with the statement below
+                  grouping baz {
+                    leaf a {
+                      type foo;
+                    }
+                    leaf b {
+                      type bar;
+                    }
+                  }
+
+                  container qux {
+                    uses baz;
+                  }
+                  leaf not-a-definition {
+                    type string;
+                  }
+                }
+                "#
+            ),
+            result,
+        );
+    }
+
+    #[test]
+    fn test_format_with_blank_line_before() {
+        let result = format_yang_str(
+            dedent(
+                r#"
+                module foo {
+                    container foo {
+                        leaf a {
+                            type string;
+                        }
+                        list b {
+                            key "name";
+                            leaf name {
+                                type string;
+                            }
+                        }
+                    }
+                    // Comments stay with the statement below
+                    container bar {
+                    }
+
+                    list baz {
+                    }
+                }
+                "#,
+            )
+            .as_bytes(),
+            &(FormatConfig {
+                blank_line_before: DEFAULT_BLANK_LINE_BEFORE
+                    .iter()
+                    .map(|kw| kw.to_string())
+                    .collect(),
+                ..Default::default()
+            }),
+        )
+        .unwrap();
+
+        assert_eq!(
+            dedent(
+                r#"
+                module foo {
+                  container foo {
+                    leaf a {
+                      type string;
+                    }
+
+                    list b {
+                      key "name";
+                      leaf name {
+                        type string;
+                      }
+                    }
+                  }
+
+                  // Comments stay with the statement below
+                  container bar {
+                  }
+
+                  list baz {
+                  }
+                }
+                "#
+            ),
+            result,
+        );
+    }
+
+    #[test]
+    fn test_final_newline() {
+        let config = FormatConfig::default();
+
+        assert_eq!("foo;\n", format_yang_str(b"foo;", &config).unwrap());
+        assert_eq!("foo;\n", format_yang_str(b"foo;\n\n\n", &config).unwrap());
+        assert_eq!(
+            "// bar\n",
+            format_yang_str(b"// bar  \n\n", &config).unwrap()
+        );
+        assert_eq!("", format_yang_str(b"\n\n", &config).unwrap());
+
+        let config = FormatConfig {
+            final_newline: false,
+            ..Default::default()
+        };
+
+        assert_eq!("foo;", format_yang_str(b"foo;\n\n", &config).unwrap());
+        assert_eq!("foo {\n}", format_yang_str(b"foo {}", &config).unwrap());
+    }
+
+    #[test]
+    fn test_format_with_value_placement() {
+        let mut keyword_overrides = HashMap::new();
+
+        keyword_overrides.insert(
+            "description".to_string(),
+            KeywordOverrides {
+                value_placement: Some(ValuePlacement::AlwaysNewline),
+                ..Default::default()
+            },
+        );
+        keyword_overrides.insert(
+            "reference".to_string(),
+            KeywordOverrides {
+                value_placement: Some(ValuePlacement::NeverNewline),
+                ..Default::default()
+            },
+        );
+
+        let result = format_yang_str(
+            dedent(
+                r#"
+                leaf foo {
+                    type string;
+                    description "Short";
+                    reference "I would normally be wrapped to the next line";
+                    reference "I am multi-lined,
+                               but I stay on the keyword line";
+                }
+                "#,
+            )
+            .as_bytes(),
+            &(FormatConfig {
+                line_length: 40,
+                keyword_overrides,
+                ..Default::default()
+            }),
+        )
+        .unwrap();
+
+        assert_eq!(
+            dedent(
+                r#"
+                leaf foo {
+                  type string;
+                  description
+                    "Short";
+                  reference "I would normally be wrapped to the next line";
+                  reference "I am multi-lined,
+                             but I stay on the keyword line";
+                }
+                "#
+            ),
+            result,
+        );
+    }
+
+    #[test]
+    fn test_format_with_semicolon_placement() {
+        let input = dedent(
+            r#"
+            leaf foo {
+                type string;
+                description "I will be wrapped to the next line";
+            }
+            "#,
+        );
+
+        let format = |semicolon_placement| {
+            format_yang_str(
+                input.as_bytes(),
+                &(FormatConfig {
+                    line_length: 40,
+                    semicolon_placement,
+                    ..Default::default()
+                }),
+            )
+            .unwrap()
+        };
+
+        assert_eq!(
+            dedent(
+                r#"
+                leaf foo {
+                  type string;
+                  description
+                    "I will be wrapped to the next line";
+                }
+                "#
+            ),
+            format(SemicolonPlacement::Attached),
+        );
+
+        assert_eq!(
+            dedent(
+                r#"
+                leaf foo {
+                  type string;
+                  description
+                    "I will be wrapped to the next line"
+                    ;
+                }
+                "#
+            ),
+            format(SemicolonPlacement::NextLine),
+        );
+
+        assert_eq!(
+            dedent(
+                r#"
+                leaf foo {
+                  type string;
+                  description
+                    "I will be wrapped to the next line"
+                  ;
+                }
+                "#
+            ),
+            format(SemicolonPlacement::UnderKeyword),
+        );
+    }
+
+    #[test]
+    fn test_format_with_trailing_concat_operator() {
+        let result = format_yang_str(
+            dedent(
+                r#"
+                type string {
+                    pattern "foo"  // Comment here
+                          + "bar" /* Another comment */
+                          + "baz";
+                }
+                "#,
+            )
+            .as_bytes(),
+            &(FormatConfig {
+                concat_operator: ConcatOperatorStyle::Trailing,
+                ..Default::default()
+            }),
+        )
+        .unwrap();
+
+        assert_eq!(
+            dedent(
+                r#"
+                type string {
+                  pattern "foo" + // Comment here
+                          "bar" + /* Another comment */
+                          "baz";
+                }
+                "#
+            ),
+            result,
+        );
+    }
+
+    #[test]
+    fn test_format_with_concat_alignment() {
+        let input = dedent(
+            r#"
+            type string {
+                pattern "foo"
+                      + "bar";
+            }
+            "#,
+        );
+
+        let format = |concat_operator, concat_alignment| {
+            format_yang_str(
+                input.as_bytes(),
+                &(FormatConfig {
+                    continuation_indent: Some(4),
+                    concat_operator,
+                    concat_alignment,
+                    ..Default::default()
+                }),
+            )
+            .unwrap()
+        };
+
+        assert_eq!(
+            dedent(
+                r#"
+                type string {
+                  pattern "foo"
+                      + "bar";
+                }
+                "#
+            ),
+            format(ConcatOperatorStyle::Leading, ConcatAlignment::Continuation),
+        );
+
+        assert_eq!(
+            dedent(
+                r#"
+                type string {
+                  pattern "foo"
+                    + "bar";
+                }
+                "#
+            ),
+            format(ConcatOperatorStyle::Leading, ConcatAlignment::Keyword),
+        );
+
+        assert_eq!(
+            dedent(
+                r#"
+                type string {
+                  pattern "foo" +
+                      "bar";
+                }
+                "#
+            ),
+            format(ConcatOperatorStyle::Trailing, ConcatAlignment::Continuation),
+        );
+    }
+
+    #[test]
+    fn test_format_with_reflow_text() {
+        let result = format_yang_str(
+            dedent(
+                r#"
+                leaf foo {
+                    type string;
+                    description
+                        "This description is far too long to fit within the configured
+                         width, so it has to be re-wrapped. These
+                         short lines get joined.
+
+                         Paragraphs are preserved, as are lists:
+
+                         - first item, which is also a little bit too long to fit
+                         - second item";
+                    reference "Short and sweet";
+                }
+                "#,
+            )
+            .as_bytes(),
+            &(FormatConfig {
+                line_length: 50,
+                reflow_text: true,
+                ..Default::default()
+            }),
+        )
+        .unwrap();
+
+        assert_eq!(
+            dedent(
+                r#"
+                leaf foo {
+                  type string;
+                  description
+                    "This description is far too long to fit
+                     within the configured width, so it has to
+                     be re-wrapped. These short lines get
+                     joined.
+
+                     Paragraphs are preserved, as are lists:
+
+                     - first item, which is also a little bit
+                       too long to fit
+                     - second item";
+                  reference "Short and sweet";
+                }
+                "#
+            ),
+            result,
+        );
+    }
+
+    #[test]
+    fn test_format_with_preserve_strings() {
+        let result = format_yang_str(
+            dedent(
+                "
+                leaf foo {
+                    description \"  I keep my whitespace  \";
+                    description
+                      \"I keep my
+                         original     indentation
+                    \";
+                }
+                ",
+            )
+            .as_bytes(),
+            &(FormatConfig {
+                preserve_strings: true,
+                reflow_text: true,
+                ..Default::default()
+            }),
+        )
+        .unwrap();
+
+        assert_eq!(
+            dedent(
+                "
+                leaf foo {
+                  description \"  I keep my whitespace  \";
+                  description
+                    \"I keep my
+                         original     indentation
+                    \";
+                }
+                "
+            ),
+            result,
+        );
+    }
+
+    #[test]
+    fn test_format_with_preserve_string_indent() {
+        let result = format_yang_str(
+            dedent(
+                "
+                leaf foo {
+                    description '  I get stripped and converted  ';
+                    description
+                      \"
+                       I get stripped, but keep my
+                         original     indentation
+                    \";
+                }
+                ",
+            )
+            .as_bytes(),
+            &(FormatConfig {
+                preserve_string_indent: true,
+                ..Default::default()
+            }),
+        )
+        .unwrap();
+
+        assert_eq!(
+            dedent(
+                "
+                leaf foo {
+                  description \"I get stripped and converted\";
+                  description
+                    \"I get stripped, but keep my
+                         original     indentation\";
+                }
+                "
+            ),
+            result,
+        );
+    }
+
+    #[test]
+    fn test_format_with_split_long_strings() {
+        let result = format_yang_str(
+            dedent(
+                r#"
+                leaf foo {
+                    description "I fit on the next line <---------------->";
+                    description "I am far too long to fit on any line, no matter how I'm indented";
+                    pattern '[0-9a-fA-F]{0,4}:[0-9a-fA-F]{0,4}:[0-9a-fA-F]{0,4}:[0-9a-fA-F]{0,4}';
+                }
+                "#,
+            )
+            .as_bytes(),
+            &(FormatConfig {
+                line_length: 50,
+                split_long_strings: true,
+                never_wrap: vec![],
+                ..Default::default()
+            }),
+        )
+        .unwrap();
+
+        assert_eq!(
+            dedent(
+                r#"
+                leaf foo {
+                  description
+                    "I fit on the next line <---------------->";
+                  description "I am far too long to fit on any "
+                            + "line, no matter how I'm indented";
+                  pattern "[0-9a-fA-F]{0,4}:[0-9a-fA-F]{0,4}:[0"
+                        + "-9a-fA-F]{0,4}:[0-9a-fA-F]{0,4}";
+                }
+                "#
+            ),
+            result,
+        );
+    }
+
+    #[test]
+    fn test_format_with_split_long_strings_at_max_width() {
+        let config = FormatConfig {
+            line_length: 50,
+            split_long_strings: true,
+            never_wrap: vec![],
+            ..Default::default()
+        };
+        let result = format_yang_str(
+            dedent(
+                r#"
+                leaf foo {
+                    description "I fit on the next line, exactly at the max.";
+                    description "I fit on the next line, one past the max width";
+                }
+                "#,
+            )
+            .as_bytes(),
+            &config,
+        )
+        .unwrap();
+
+        assert_eq!(
+            dedent(
+                r#"
+                leaf foo {
+                  description
+                    "I fit on the next line, exactly at the max.";
+                  description "I fit on the next line, one "
+                            + "past the max width";
+                }
+                "#
+            ),
+            result,
+        );
+        assert_eq!(
+            Vec::<Diagnostic>::new(),
+            find_long_lines(result.as_bytes(), &config)
+        );
+    }
+
+    #[test]
+    fn test_format_with_split_long_strings_and_continuation_indent() {
+        let result = format_yang_str(
+            "leaf foo {\n  units \"far too many units to fit on one line of the output\";\n}\n"
+                .as_bytes(),
+            &(FormatConfig {
+                line_length: 40,
+                continuation_indent: Some(8),
+                concat_alignment: ConcatAlignment::Continuation,
+                split_long_strings: true,
+                ..Default::default()
+            }),
+        )
+        .unwrap();
+
+        // The continuation lines are indented further than the first string, so they decide how
+        // long the chunks can be
+        assert_eq!(
+            dedent(
+                r#"
+                leaf foo {
+                  units "far too many units to "
+                          + "fit on one line of the "
+                          + "output";
+                }
+                "#
+            ),
+            result,
+        );
+    }
+
+    #[test]
+    fn test_format_with_merge_short_concatenations() {
+        let result = format_yang_str(
+            dedent(
+                r#"
+                type string {
+                    pattern "[a-z]"
+                          + '[0-9]';
+                    pattern "[a-z]"
+                          + "[0-9]"; // keep
+                    pattern "[a-f]"
+                          + "[0-9]"; // keeping this for compat
+                    pattern "[a-z]" // Explains the first part
+                          + "[0-9]";
+                    pattern "I am too long to be merged back into one line"
+                          + " <------------------------>";
+                }
+                "#,
+            )
+            .as_bytes(),
+            &(FormatConfig {
+                line_length: 60,
+                merge_short_concatenations: true,
+                ..Default::default()
+            }),
+        )
+        .unwrap();
+
+        assert_eq!(
+            dedent(
+                r#"
+                type string {
+                  pattern "[a-z][0-9]";
+                  pattern "[a-z]"
+                        + "[0-9]"; // keep
+                  pattern "[a-f][0-9]"; // keeping this for compat
+                  pattern "[a-z]" // Explains the first part
+                        + "[0-9]";
+                  pattern "I am too long to be merged back into one line"
+                        + " <------------------------>";
+                }
+                "#
+            ),
+            result,
+        );
+    }
+
+    #[test]
+    fn test_format_with_wrap_xpath() {
+        let input = dedent(
+            r#"
+            container foo {
+                must "../type = 'ethernet' or ../type = 'loopback' or ../type = 'tunnel'";
+                when "../a   =   'b'
+                          and ../c = 'd'";
+            }
+            "#,
+        );
+
+        let config = FormatConfig {
+            line_length: 50,
+            wrap_xpath: true,
+            ..Default::default()
+        };
+
+        let expected = dedent(
+            r#"
+            container foo {
+              must
+                "../type = 'ethernet'
+                   or ../type = 'loopback'
+                   or ../type = 'tunnel'";
+              when "../a = 'b' and ../c = 'd'";
+            }
+            "#,
+        );
+
+        let result = format_yang_str(input.as_bytes(), &config).unwrap();
+        assert_eq!(expected, result);
+
+        // Formatting the output again should not change anything
+        let result = format_yang_str(result.as_bytes(), &config).unwrap();
+        assert_eq!(expected, result);
+    }
+
+    #[test]
+    fn test_format_with_split_long_paths() {
+        let result = format_yang_str(
+            dedent(
+                r#"
+                module foo {
+                    augment "/if:interfaces/if:interface/ip:ipv4/ip:address/ip:subnet" {
+                        leaf bar {
+                            type string;
+                        }
+                    }
+                    deviation "/if:interfaces/if:interface" {
+                        deviate not-supported;
+                    }
+                }
+                "#,
+            )
+            .as_bytes(),
+            &(FormatConfig {
+                line_length: 50,
+                split_long_paths: true,
+                ..Default::default()
+            }),
+        )
+        .unwrap();
+
+        assert_eq!(
+            dedent(
+                r#"
+                module foo {
+                  augment "/if:interfaces/if:interface/ip:ipv4"
+                        + "/ip:address/ip:subnet" {
+                    leaf bar {
+                      type string;
+                    }
+                  }
+                  deviation "/if:interfaces/if:interface" {
+                    deviate not-supported;
+                  }
+                }
+                "#
+            ),
+            result,
+        );
+    }
+
+    #[test]
+    fn test_format_with_never_wrap() {
+        let input = dedent(
+            r#"
+            type string {
+                pattern '[0-9a-fA-F]{0,4}:[0-9a-fA-F]{0,4}:[0-9a-fA-F]{0,4}';
+                length "1..1000 | 2000..3000 | 4000..5000 | 6000..7000";
+            }
+            "#,
+        );
+
+        let result = format_yang_str(
+            input.as_bytes(),
+            &(FormatConfig {
+                line_length: 50,
+                split_long_strings: true,
+                ..Default::default()
+            }),
+        )
+        .unwrap();
+
+        assert_eq!(
+            dedent(
+                r#"
+                type string {
+                  pattern "[0-9a-fA-F]{0,4}:[0-9a-fA-F]{0,4}:[0-9a-fA-F]{0,4}";
+                  length "1..1000 | 2000..3000 | 4000..5000 | "
+                       + "6000..7000";
+                }
+                "#
+            ),
+            result,
+        );
+
+        let result = format_yang_str(
+            input.as_bytes(),
+            &(FormatConfig {
+                line_length: 50,
+                never_wrap: vec!["length".to_string()],
+                ..Default::default()
+            }),
+        )
+        .unwrap();
+
+        assert_eq!(
+            dedent(
+                r#"
+                type string {
+                  pattern
+                    "[0-9a-fA-F]{0,4}:[0-9a-fA-F]{0,4}:[0-9a-fA-F]{0,4}";
+                  length "1..1000 | 2000..3000 | 4000..5000 | 6000..7000";
+                }
+                "#
+            ),
+            result,
+        );
+    }
+
+    #[test]
+    fn test_format_with_keyword_max_width() {
+        let mut keyword_overrides = HashMap::new();
+
+        keyword_overrides.insert(
+            "description".to_string(),
+            KeywordOverrides {
+                max_width: Some(80),
+                ..Default::default()
+            },
+        );
+
+        let result = format_yang_str(
+            dedent(
+                r#"
+                leaf foo {
+                    type string;
+                    description "I would normally be wrapped to the next line";
+                    reference "I would normally be wrapped to the next line";
+                }
+                "#,
+            )
+            .as_bytes(),
+            &(FormatConfig {
+                line_length: 40,
+                keyword_overrides,
+                ..Default::default()
+            }),
+        )
+        .unwrap();
+
+        assert_eq!(
+            dedent(
+                r#"
+                leaf foo {
+                  type string;
+                  description "I would normally be wrapped to the next line";
+                  reference
+                    "I would normally be wrapped to the next line";
+                }
+                "#
+            ),
+            result,
+        );
+    }
+
+    #[test]
+    fn test_format_with_never_wrap_patterns() {
+        let result = format_yang_str(
+            dedent(
+                r#"
+                module foo {
+                    reference "https://www.example.com/some/very/long/path/to/a/document.html";
+                    reference "This string is quite long and will be split up into pieces";
+                }
+                "#,
+            )
+            .as_bytes(),
+            &(FormatConfig {
+                line_length: 50,
+                split_long_strings: true,
+                never_wrap_patterns: vec![Regex::new(r"^https?://\S+$").unwrap()],
+                ..Default::default()
+            }),
+        )
+        .unwrap();
+
+        assert_eq!(
+            dedent(
+                r#"
+                module foo {
+                  reference "https://www.example.com/some/very/long/path/to/a/document.html";
+                  reference "This string is quite long and "
+                          + "will be split up into pieces";
+                }
+                "#
+            ),
+            result,
+        );
+    }
+
+    #[test]
+    fn test_format_regex_quotes() {
+        let result = format_yang_str(
+            dedent(
+                r#"
+                type string {
+                    pattern '\d+(\.\d+)?';
+                    pattern "\\d+(\\.\\d+)?";
+                    pattern "[a-z]+\\n" + '\s*';
+                    pattern "[0-9]+" + "\\.[0-9]+";
+                    pattern "\\d+" + "'[a-z]+'";
+                    pattern "\\d+'";
+                    pattern "\\d+\n";
+                    pattern '[a-z]+';
+                }
+                "#,
+            )
+            .as_bytes(),
+            &FormatConfig::default(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            dedent(
+                r#"
+                type string {
+                  pattern '\d+(\.\d+)?';
+                  pattern '\d+(\.\d+)?';
+                  pattern '[a-z]+\n'
+                        + '\s*';
+                  pattern '[0-9]+'
+                        + '\.[0-9]+';
+                  pattern "\\d+"
+                        + "'[a-z]+'";
+                  pattern "\\d+'";
+                  pattern "\\d+\n";
+                  pattern "[a-z]+";
+                }
+                "#
+            ),
+            result,
+        );
+    }
+
+    #[test]
+    fn test_format_single_quote_conversion() {
+        let result = format_yang_str(
+            dedent(
+                r#"
+                leaf foo {
+                    description 'Stored in C:\Program Files\Foo';
+                    reference 'See "Foo"';
+                    must 're-match(../name, "[a-z]+\d*")';
+                    when '../a = 1' + 'or ../b = 2';
+                    description 'Has trailing whitespace  
+                                 on the first line';
+                }
+                "#,
+            )
+            .as_bytes(),
+            &FormatConfig::default(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            dedent(
+                r#"
+                leaf foo {
+                  description "Stored in C:\\Program Files\\Foo";
+                  reference 'See "Foo"';
+                  must 're-match(../name, "[a-z]+\d*")';
+                  when "../a = 1"
+                     + "or ../b = 2";
+                  description
+                    'Has trailing whitespace  
+                     on the first line';
+                }
+                "#
+            ),
+            result,
+        );
+    }
+
+    #[test]
+    fn test_format_with_quote_style() {
+        let input = dedent(
+            r#"
+            leaf foo {
+                description "Plain text";
+                reference "Can't be single-quoted";
+                default "\t";
+                must '../a = "b"';
+                units '  ';
+                pattern "\\d+";
+            }
+            "#,
+        );
+
+        let format = |quote_style| {
+            format_yang_str(
+                input.as_bytes(),
+                &FormatConfig {
+                    quote_style,
+                    ..Default::default()
+                },
+            )
+            .unwrap()
+        };
+
+        assert_eq!(
+            dedent(
+                r#"
+                leaf foo {
+                  description 'Plain text';
+                  reference "Can't be single-quoted";
+                  default "\t";
+                  must '../a = "b"';
+                  units '';
+                  pattern '\d+';
+                }
+                "#
+            ),
+            format(QuoteStyle::Single),
+        );
+
+        assert_eq!(
+            dedent(
+                r#"
+                leaf foo {
+                  description "Plain text";
+                  reference "Can't be single-quoted";
+                  default "\t";
+                  must '../a = "b"';
+                  units '';
+                  pattern "\\d+";
+                }
+                "#
+            ),
+            format(QuoteStyle::Preserve),
+        );
+    }
+
+    #[test]
+    fn test_format_with_quote_string_arguments() {
+        let result = format_yang_str(
+            dedent(
+                r#"
+                module foo {
+                    prefix foo;
+                    list bar {
+                        key name;
+                        leaf name {
+                            type enumeration {
+                                enum up;
+                                enum down {
+                                    value 2;
+                                }
+                            }
+                            default up;
+                            config true;
+                        }
+                        leaf-list baz {
+                            type boolean;
+                            default false;
+                            max-elements unbounded;
+                            ordered-by user;
+                        }
+                    }
+                }
+                "#,
+            )
+            .as_bytes(),
+            &(FormatConfig {
+                quote_string_arguments: true,
+                ..Default::default()
+            }),
+        )
+        .unwrap();
+
+        assert_eq!(
+            dedent(
+                r#"
+                module foo {
+                  prefix "foo";
+                  list bar {
+                    key "name";
+                    leaf name {
+                      type enumeration {
+                        enum "up";
+                        enum "down" {
+                          value 2;
+                        }
+                      }
+                      default "up";
+                      config true;
+                    }
+                    leaf-list baz {
+                      type boolean;
+                      default false;
+                      max-elements unbounded;
+                      ordered-by user;
+                    }
+                  }
+                }
+                "#
+            ),
+            result,
+        );
+    }
+
+    #[test]
+    fn test_format_with_unquote_simple_arguments() {
+        let result = format_yang_str(
+            dedent(
+                r#"
+                module foo {
+                    prefix "foo";
+                    import ietf-inet-types {
+                        prefix 'inet';
+                        revision-date "2013-07-15";
+                    }
+                    leaf bar {
+                        type "inet:port-number";
+                        default "-5";
+                        units "packets per second";
+                        description "bar";
+                        must "count(../baz)";
+                        config "true";
+                        mandatory "";
+                    }
+                }
+                "#,
+            )
+            .as_bytes(),
+            &(FormatConfig {
+                unquote_simple_arguments: true,
+                ..Default::default()
+            }),
+        )
+        .unwrap();
+
+        assert_eq!(
+            dedent(
+                r#"
+                module foo {
+                  prefix foo;
+                  import ietf-inet-types {
+                    prefix inet;
+                    revision-date 2013-07-15;
+                  }
+                  leaf bar {
+                    type inet:port-number;
+                    default -5;
+                    units "packets per second";
+                    description "bar";
+                    must "count(../baz)";
+                    config true;
+                    mandatory "";
+                  }
+                }
+                "#
+            ),
+            result,
+        );
+    }
+
+    #[test]
+    fn test_non_printable_characters() {
+        let input = "leaf foo {\n  description \"Non\u{a0}breaking\u{7}\";\n  units \"zero\u{200b}width\";\n}\n";
+
+        let format = |non_printable| {
+            let mut output: Vec<u8> = vec![];
+            let config = FormatConfig {
+                non_printable,
+                ..Default::default()
+            };
+
+            let diagnostics = format_yang(&mut output, input.as_bytes(), &config).unwrap();
+
+            (String::from_utf8(output).unwrap(), diagnostics)
+        };
+
+        let (output, diagnostics) = format(NonPrintableCharacters::Warn);
+
+        assert_eq!(input, output);
+        assert_eq!(
+            vec![
+                Diagnostic::new("Non-printable character U+00A0 in string", 29)
+                    .with_code("non-printable-character"),
+                Diagnostic::new("Non-printable character U+0007 in string", 39)
+                    .with_code("non-printable-character"),
+                Diagnostic::new("Non-printable character U+200B in string", 56)
+                    .with_code("non-printable-character"),
+            ],
+            diagnostics,
+        );
+
+        let (output, diagnostics) = format(NonPrintableCharacters::Replace);
+
+        assert_eq!(
+            "leaf foo {\n  description \"Non breaking\u{7}\";\n  units \"zerowidth\";\n}\n",
+            output
+        );
+        assert_eq!(
+            vec![
+                Diagnostic::new("Non-printable character U+0007 in string", 39)
+                    .with_code("non-printable-character")
+            ],
+            diagnostics,
+        );
+
+        let (_, diagnostics) = format(NonPrintableCharacters::Ignore);
+
+        assert_eq!(Vec::<Diagnostic>::new(), diagnostics);
+    }
+
+    #[test]
+    fn test_normalize_boolean_case() {
+        let result = format_yang_str(
+            dedent(
+                r#"
+                leaf foo {
+                    config False;
+                    mandatory "TRUE";
+                    type leafref {
+                        require-instance True;
+                    }
+                    default True;
+                }
+                "#,
+            )
+            .as_bytes(),
+            &FormatConfig::default(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            dedent(
+                r#"
+                leaf foo {
+                  config false;
+                  mandatory "true";
+                  type leafref {
+                    require-instance true;
+                  }
+                  default True;
+                }
+                "#
+            ),
+            result,
+        );
+    }
+
+    #[test]
+    fn test_normalize_dates() {
+        let input = dedent(
+            r#"
+            module foo {
+                import bar {
+                    revision-date "2024-1-05";
+                }
+                revision 2023-02-29;
+                revision '2020-06-15' {
+                    description "Initial revision";
+                }
+            }
+            "#,
+        );
+
+        let mut output: Vec<u8> = vec![];
+        let diagnostics = format_yang(&mut output, input.as_bytes(), &FormatConfig::default());
+
+        assert_eq!(
+            dedent(
+                r#"
+                module foo {
+                  import bar {
+                    revision-date 2024-01-05;
+                  }
+                  revision 2023-02-29;
+                  revision 2020-06-15 {
+                    description "Initial revision";
+                  }
+                }
+                "#
+            ),
+            String::from_utf8(output).unwrap(),
+        );
+        assert_eq!(
+            vec![Diagnostic::new(
+                "Invalid revision: \"2023-02-29\" is not a valid calendar date",
+                84
+            )
+            .with_code("invalid-date")],
+            diagnostics.unwrap(),
+        );
+    }
+
+    #[test]
+    fn test_format_with_normalize_numbers() {
+        let result = format_yang_str(
+            dedent(
+                r#"
+                leaf-list foo {
+                    min-elements 01;
+                    max-elements unbounded;
+                    type enumeration {
+                        enum bar {
+                            value -0;
+                        }
+                        enum baz {
+                            value "+007";
+                        }
+                    }
+                    type decimal64 {
+                        fraction-digits 02;
+                        range "0.50 .. 100.00";
+                    }
+                }
+                "#,
+            )
+            .as_bytes(),
+            &(FormatConfig {
+                normalize_numbers: true,
+                ..Default::default()
+            }),
+        )
+        .unwrap();
+
+        assert_eq!(
+            dedent(
+                r#"
+                leaf-list foo {
+                  min-elements 1;
+                  max-elements unbounded;
+                  type enumeration {
+                    enum bar {
+                      value 0;
+                    }
+                    enum baz {
+                      value "7";
+                    }
+                  }
+                  type decimal64 {
+                    fraction-digits 2;
+                    range "0.50 .. 100.00";
+                  }
+                }
+                "#
+            ),
+            result,
+        );
+    }
+
+    #[test]
+    fn test_normalize_list_argument_whitespace() {
+        let input = dedent(
+            r#"
+            list foo {
+                key "name   id";
+                unique "a/b
+                        c";
+                if-feature "  bar  and (baz   or qux)";
+                leaf name {
+                    type string;
+                }
+            }
+            "#,
+        );
+
+        let expected = dedent(
+            r#"
+            list foo {
+              key "name id";
+              unique "a/b c";
+              if-feature "bar and (baz or qux)";
+              leaf name {
+                type string;
+              }
+            }
+            "#,
+        );
+
+        assert_eq!(
+            expected,
+            format_yang_str(input.as_bytes(), &FormatConfig::default()).unwrap()
+        );
+
+        // Single keys can be unquoted
+        let result = format_yang_str(
+            b"list foo {\n  key \"  name \";\n}\n",
+            &FormatConfig {
+                unquote_simple_arguments: true,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        assert_eq!("list foo {\n  key name;\n}\n", result);
+    }
+
+    #[test]
+    fn test_format_with_path_wrapping() {
+        let input = dedent(
+            r#"
+            leaf foo {
+                type leafref {
+                    path "/if:interfaces/if:interface[if:name = current()/../name]/if:type";
+                }
+            }
+            "#,
+        );
+
+        let format = |path_wrapping| {
+            format_yang_str(
+                input.as_bytes(),
+                &FormatConfig {
+                    line_length: 50,
+                    path_wrapping,
+                    ..Default::default()
+                },
+            )
+            .unwrap()
+        };
+
+        assert_eq!(
+            dedent(
+                r#"
+                leaf foo {
+                  type leafref {
+                    path "/if:interfaces"
+                       + "/if:interface[if:name = current()/../name]"
+                       + "/if:type";
+                  }
+                }
+                "#
+            ),
+            format(PathWrapping::Slash),
+        );
+
+        assert_eq!(
+            dedent(
+                r#"
+                leaf foo {
+                  type leafref {
+                    path "/if:interfaces/if:interface[if:name = current()/../name]/if:type";
+                  }
+                }
+                "#
+            ),
+            format(PathWrapping::Never),
+        );
+    }
+
+    #[test]
+    fn test_format_with_identifier_quoting() {
+        let input = dedent(
+            r#"
+            module foo {
+                prefix "foo";
+                belongs-to bar { prefix 'bar'; }
+                identity baz { base "inet:qux"; }
+                leaf qux {
+                    type string;
+                    default "text";
+                }
+                uses "grouping-with-dash";
+            }
+            "#,
+        );
+
+        let format = |identifier_quoting| {
+            format_yang_str(
+                input.as_bytes(),
+                &FormatConfig {
+                    identifier_quoting,
+                    ..Default::default()
+                },
+            )
+            .unwrap()
+        };
+
+        assert_eq!(
+            dedent(
+                r#"
+                module foo {
+                  prefix foo;
+                  belongs-to bar {
+                    prefix bar;
+                  }
+                  identity baz {
+                    base inet:qux;
+                  }
+                  leaf qux {
+                    type string;
+                    default "text";
+                  }
+                  uses grouping-with-dash;
+                }
+                "#
+            ),
+            format(IdentifierQuoting::Bare),
+        );
+
+        assert_eq!(
+            dedent(
+                r#"
+                module foo {
+                  prefix "foo";
+                  belongs-to "bar" {
+                    prefix "bar";
+                  }
+                  identity baz {
+                    base "inet:qux";
+                  }
+                  leaf qux {
+                    type "string";
+                    default "text";
+                  }
+                  uses "grouping-with-dash";
+                }
+                "#
+            ),
+            format(IdentifierQuoting::Quoted),
+        );
+    }
+
+    #[test]
+    fn test_format_with_align_trailing_comments() {
+        let result = format_yang_str(
+            dedent(
+                r#"
+                typedef foo {
+                    type enumeration {
+                        enum up; // The interface is up
+                        enum down; // The interface is down
+                        enum testing;   // In some test mode
+                        enum lower-layer-down {  // Not aligned
+                            value 7; // Seven
+                        }
+                        enum unknown; /* Status can't be determined */ // Really
+                    }
+                    default up;  // Up by default
+                }
+                "#,
+            )
+            .as_bytes(),
+            &(FormatConfig {
+                align_trailing_comments: true,
+                ..Default::default()
+            }),
+        )
+        .unwrap();
+
+        assert_eq!(
+            dedent(
+                r#"
+                typedef foo {
+                  type enumeration {
+                    enum up;      // The interface is up
+                    enum down;    // The interface is down
+                    enum testing; // In some test mode
+                    enum lower-layer-down { // Not aligned
+                      value 7; // Seven
+                    }
+                    enum unknown; /* Status can't be determined */ // Really
+                  }
+                  default up; // Up by default
+                }
+                "#
+            ),
+            result,
+        );
+    }
+
+    #[test]
+    fn test_format_with_align_values() {
+        let result = format_yang_str(
+            dedent(
+                r#"
+                list interface {
+                    key name;
+                    min-elements 1;
+                    max-elements 10;   // Hardware limit
+
+                    ordered-by user;
+                    description "Interfaces";
+                    leaf name { type string; }
+                    config true;
+                    status current;
+                }
+                "#,
+            )
+            .as_bytes(),
+            &(FormatConfig {
+                align_values: true,
+                align_trailing_comments: true,
+                ..Default::default()
+            }),
+        )
+        .unwrap();
+
+        assert_eq!(
+            dedent(
+                r#"
+                list interface {
+                  key          name;
+                  min-elements 1;
+                  max-elements 10; // Hardware limit
+
+                  ordered-by  user;
+                  description "Interfaces";
+                  leaf name {
+                    type string;
+                  }
+                  config true;
+                  status current;
+                }
+                "#
+            ),
+            result,
+        );
+    }
+
+    #[test]
+    fn test_format_with_align_enum_values() {
+        let result = format_yang_str(
+            dedent(
+                r#"
+                type enumeration {
+                    enum up { value 1; }
+                    enum down {
+                        value 2;
+                    }
+                    enum lower-layer-down { value 7; }
+                    enum testing {
+                        value 3;
+                        description "In some test mode";
+                    }
+                    enum dormant { value 5; }
+                    enum not-present {
+                        value 6; // Missing hardware
+                    }
+                }
+                "#,
+            )
+            .as_bytes(),
+            &(FormatConfig {
+                align_enum_values: true,
+                ..Default::default()
+            }),
+        )
+        .unwrap();
+
+        assert_eq!(
+            dedent(
+                r#"
+                type enumeration {
+                  enum up               { value 1; }
+                  enum down             { value 2; }
+                  enum lower-layer-down { value 7; }
+                  enum testing {
+                    value 3;
+                    description "In some test mode";
+                  }
+                  enum dormant { value 5; }
+                  enum not-present {
+                    value 6; // Missing hardware
+                  }
+                }
+                "#
+            ),
+            result,
+        );
+    }
+
+    #[test]
+    fn test_format_with_reflow_comments_and_pragmas() {
+        let input = dedent(
+            r#"
+            module foo {
+              // Keep this table exactly as written
+              // yangfmt: off
+              leaf bar   { type string; }
+              // yangfmt: on
+            }
+            "#,
+        );
+        let config = FormatConfig {
+            reflow_comments: true,
+            ..Default::default()
+        };
+
+        let result = format_yang_str(input.as_bytes(), &config).unwrap();
+
+        assert_eq!(input, result);
+        assert_eq!(result, format_yang_str(result.as_bytes(), &config).unwrap());
+    }
+
+    #[test]
+    fn test_format_with_reflow_comments() {
+        let result = format_yang_str(
+            dedent(
+                r#"
+                module foo {
+                    ////////////////////////////////////////
+                    // This comment block was edited by hand a few times and is now far too long
+                    // for the
+                    // configured width.
+                    //
+                    // - Bullets are wrapped separately from the paragraph above them
+                    // - Short bullet
+                    // ---------------------------------
+                    leaf bar {
+                        type string; // Trailing comments are left alone, no matter how long
+                    }
+                    /* Block comments are left alone too, no matter how long they are */
+                }
+                "#,
+            )
+            .as_bytes(),
+            &(FormatConfig {
+                line_length: 50,
+                reflow_comments: true,
+                ..Default::default()
+            }),
+        )
+        .unwrap();
+
+        assert_eq!(
+            dedent(
+                r#"
+                module foo {
+                  ////////////////////////////////////////
+                  // This comment block was edited by hand a few
+                  // times and is now far too long for the
+                  // configured width.
+                  //
+                  // - Bullets are wrapped separately from the
+                  //   paragraph above them
+                  // - Short bullet
+                  // ---------------------------------
+                  leaf bar {
+                    type string; // Trailing comments are left alone, no matter how long
+                  }
+                  /* Block comments are left alone too, no matter how long they are */
+                }
+                "#
+            ),
+            result,
+        );
+    }
+
+    #[test]
+    fn test_format_block_comments() {
+        let result = format_yang_str(
+            dedent(
+                r#"
+                module foo {
+                        /*
+                           * Skewed asterisks
+                          *
+                             */
+                        container bar {
+                                    /* Text lined up
+                                       after the marker */
+                                    leaf baz { type string; }
+                                    leaf qux; /* Trailing comment
+                                                 over two lines */
+                        }
+                }
+                "#,
+            )
+            .as_bytes(),
+            &FormatConfig::default(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            dedent(
+                r#"
+                module foo {
+                  /*
+                   * Skewed asterisks
+                   *
+                   */
+                  container bar {
+                    /* Text lined up
+                       after the marker */
+                    leaf baz {
+                      type string;
+                    }
+                    leaf qux; /* Trailing comment
+                                 over two lines */
+                  }
+                }
+                "#
+            ),
+            result,
+        );
+    }
+
+    #[test]
+    fn test_format_with_prefer_line_comments() {
+        let result = format_yang_str(
+            dedent(
+                r#"
+                module foo {
+                    /* Short note */
+                    /*
+                     * Genuine block comment
+                     */
+                    leaf bar { /* After the brace */
+                        type string; /* Not last */ /* Last */
+                        default /* Before the value */ "x";
+                    }
+                    /**/
+                }
+                "#,
+            )
+            .as_bytes(),
+            &(FormatConfig {
+                prefer_line_comments: true,
+                ..Default::default()
+            }),
+        )
+        .unwrap();
+
+        assert_eq!(
+            dedent(
+                r#"
+                module foo {
+                  // Short note
+                  /*
+                   * Genuine block comment
+                   */
+                  leaf bar { // After the brace
+                    type string; /* Not last */ // Last
+                    default "x"; // Before the value
+                  }
+                  //
+                }
+                "#
+            ),
+            result,
+        );
+    }
+
+    #[test]
+    fn test_format_with_space_comment_markers() {
+        let result = format_yang_str(
+            dedent(
+                r#"
+                module foo {
+                    //////////////
+                    //Section
+                    //leaf old;
+                    leaf bar { /*After the brace*/
+                        type string; //Note
+                    }
+                }
+                "#,
+            )
+            .as_bytes(),
+            &(FormatConfig {
+                space_comment_markers: true,
+                ..Default::default()
+            }),
+        )
+        .unwrap();
+
+        assert_eq!(
+            dedent(
+                r#"
+                module foo {
+                  //////////////
+                  // Section
+                  //leaf old;
+                  leaf bar { /* After the brace */
+                    type string; // Note
+                  }
+                }
+                "#
+            ),
+            result,
+        );
+    }
+
+    #[test]
+    fn test_format_preserves_banners() {
+        let input = dedent(
+            r#"
+            module foo {
+              /********************
+              *  Section          *
+              ********************/
+              //==== Types =====
+              // Boxed section    //
+              /* ===== */
+              leaf bar {
+                type string;
+              }
+            }
+            "#,
+        );
+
+        let result = format_yang_str(
+            input.as_bytes(),
+            &(FormatConfig {
+                reflow_comments: true,
+                prefer_line_comments: true,
+                space_comment_markers: true,
+                ..Default::default()
+            }),
+        )
+        .unwrap();
+
+        assert_eq!(input, result);
+    }
+
+    #[test]
+    fn test_format_strips_trailing_whitespace() {
+        let input = "leaf foo {\n  description \"First   \n    second\";\n  reference 'Kept  \n    as is';\n}\n/* Block   \n   comment */\n";
+
+        let format = |strip_trailing_whitespace| {
+            format_yang_str(
+                input.as_bytes(),
+                &FormatConfig {
+                    strip_trailing_whitespace,
+                    ..Default::default()
+                },
+            )
+            .unwrap()
+        };
+
+        assert_eq!(
+            "leaf foo {\n  description\n    \"First\n     second\";\n  reference\n    'Kept  \n     as is';\n}\n/* Block\n   comment */\n",
+            format(true),
+        );
+        assert_eq!(
+            "leaf foo {\n  description\n    \"First   \n     second\";\n  reference\n    'Kept  \n     as is';\n}\n/* Block   \n   comment */\n",
+            format(false),
+        );
+    }
+
+    #[test]
+    fn test_format_verbatim_strings() {
+        let result = format_yang_str(
+            dedent(
+                r#"
+                container foo {
+                            description "
+                                module: foo
+                                  +--rw foo
+                                     +--rw bar?   string
+                            ";
+                    // yangfmt: verbatim
+                    reference
+                        "   Indented   first line
+                      and a short one";
+                    // Not marked yangfmt: verbatim, so this one is reflowed
+                    description
+                        "   Indented   first line
+                      and a short one";
+                }
+                "#,
+            )
+            .as_bytes(),
+            &(FormatConfig {
+                reflow_text: true,
+                ..Default::default()
+            }),
+        )
+        .unwrap();
+
+        assert_eq!(
+            dedent(
+                r#"
+                container foo {
+                  description
+                    "
+                     module: foo
+                       +--rw foo
+                          +--rw bar?   string
+                     ";
+                  // yangfmt: verbatim
+                  reference
+                    "   Indented   first line
+                     and a short one";
+                  // Not marked yangfmt: verbatim, so this one is reflowed
+                  description "Indented   first line and a short one";
+                }
+                "#
+            ),
+            result,
+        );
+    }
+
+    #[test]
+    fn test_format_with_verbatim_keywords() {
+        let input = dedent(
+            r#"
+            module foo {
+                organization "  Example Inc.  ";
+                contact
+                    "WG Web:   <https://example.com>
+
+                     Editor:   Jane Doe
+                               <mailto:jane@example.com>";
+            }
+            "#,
+        );
+
+        let format = |verbatim: &[&str]| {
+            format_yang_str(
+                input.as_bytes(),
+                &FormatConfig {
+                    reflow_text: true,
+                    verbatim: verbatim.iter().map(|kw| kw.to_string()).collect(),
+                    ..Default::default()
+                },
+            )
+            .unwrap()
+        };
+
+        assert_eq!(
+            dedent(
+                r#"
+                module foo {
+                  organization "  Example Inc.  ";
+                  contact
+                    "WG Web:   <https://example.com>
+
+                     Editor:   Jane Doe
+                               <mailto:jane@example.com>";
+                }
+                "#
+            ),
+            format(DEFAULT_VERBATIM),
+        );
+
+        assert_eq!(
+            dedent(
+                r#"
+                module foo {
+                  organization "Example Inc.";
+                  contact
+                    "WG Web:   <https://example.com>
+
+                     Editor:   Jane Doe
+                               <mailto:jane@example.com>";
+                }
+                "#
+            ),
+            format(&[]),
+        );
+    }
+
+    #[test]
+    fn test_format_with_closing_quote_own_line() {
+        let result = format_yang_str(
+            dedent(
+                r#"
+                leaf foo {
+                    description "First line
+                        second line";
+                    reference "Already on
+                        its own line
+                    ";
+                    contact "Single line";
+                }
+                "#,
+            )
+            .as_bytes(),
+            &(FormatConfig {
+                closing_quote: ClosingQuotePlacement::OwnLine,
+                verbatim: vec![],
+                ..Default::default()
+            }),
+        )
+        .unwrap();
+
+        assert_eq!(
+            dedent(
+                r#"
+                leaf foo {
+                  description
+                    "First line
+                     second line
+                    ";
+                  reference
+                    "Already on
+                     its own line
+                    ";
+                  contact "Single line";
+                }
+                "#
+            ),
+            result,
+        );
+    }
+
+    #[test]
+    fn test_format_with_compact_blocks() {
+        let result = format_yang_str(
+            dedent(
+                r#"
+                module foo {
+                    leaf bar {
+                        type string;
+
+                        description "Bar";
+                    }
+
+                    container baz {
+                        leaf a { type string; }
+
+                        leaf b { type string; }
+
+                        leaf c { type string; }
+                    }
+                }
+                "#,
+            )
+            .as_bytes(),
+            &(FormatConfig {
+                compact_blocks: Some(3),
+                ..Default::default()
+            }),
+        )
+        .unwrap();
+
+        assert_eq!(
+            dedent(
+                r#"
+                module foo {
+                  leaf bar {
+                    type string;
+                    description "Bar";
+                  }
+                  container baz {
+                    leaf a {
+                      type string;
+                    }
+
+                    leaf b {
+                      type string;
+                    }
+
+                    leaf c {
+                      type string;
+                    }
+                  }
+                }
+                "#
+            ),
+            result,
+        );
+    }
+
+    #[test]
+    fn test_format_off_regions() {
+        let result = format_yang_str(
+            dedent(
+                r#"
+                module foo {
+                    // yangfmt: off
+                    typedef  bar {  type   string; }
+                      leaf baz  { type bar; }
+                    // yangfmt: on
+                    leaf   qux { type bar; }
+                    container quux {
+                        // yangfmt: off
+                        leaf   a;
+                    }
+                }
+                "#,
+            )
+            .as_bytes(),
+            &FormatConfig::default(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            dedent(
+                r#"
+                module foo {
+                  // yangfmt: off
+                    typedef  bar {  type   string; }
+                      leaf baz  { type bar; }
+                  // yangfmt: on
+                  leaf qux {
+                    type bar;
+                  }
+                  container quux {
+                    // yangfmt: off
+                        leaf   a;
+                  }
+                }
+                "#
+            ),
+            result,
+        );
+    }
+
+    #[test]
+    fn test_format_skip_file() {
+        let input = "// Vendored, do not format\n// yangfmt: skip-file\nmodule  foo {leaf bar;}\n";
+
+        assert!(has_skip_file_pragma(input.as_bytes()));
+        assert_eq!(
+            input,
+            format_yang_str(input.as_bytes(), &FormatConfig::default()).unwrap()
+        );
+
+        // Only comments above the first statement count
+        let input = "module foo {\n  // yangfmt: skip-file\n}\n";
+
+        assert!(!has_skip_file_pragma(input.as_bytes()));
+    }
+
+    #[test]
+    fn test_format_skipped_statements() {
+        let result = format_yang_str(
+            dedent(
+                r#"
+                module foo {
+                    leaf   bar {
+                        // yangfmt: skip
+                        type string {
+                            pattern '[a-z]+'
+                                  + '[0-9]*';
+                        }
+                        description   "Bar";
+                    }
+                    leaf baz  { type string; }  // yangfmt: skip
+                    leaf   qux;
+                }
+                "#,
+            )
+            .as_bytes(),
+            &FormatConfig::default(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            dedent(
+                r#"
+                module foo {
+                  leaf bar {
+                    // yangfmt: skip
+                        type string {
+                            pattern '[a-z]+'
+                                  + '[0-9]*';
+                        }
+                    description "Bar";
+                  }
+                    leaf baz  { type string; }  // yangfmt: skip
+                  leaf qux;
+                }
+                "#
+            ),
+            result,
+        );
+    }
+
+    #[test]
+    fn test_format_with_single_line_blocks() {
+        let result = format_yang_str(
+            dedent(
+                r#"
+                module foo {
+                    import bar { prefix b; }
+                    leaf baz {
+                        type bits {
+                            bit up {
+                                position 0;
+                            }
+                            bit down {
+                                position 1;
+                                description "Down";
+                            }
+                            bit sideways-and-also-a-very-long-name-that-does-not-fit {
+                                position 2;
+                            }
+                        }
+                    }
+                }
+                "#,
+            )
+            .as_bytes(),
+            &FormatConfig {
+                single_line_blocks: vec!["bit".to_string(), "import".to_string()],
+                single_line_max_children: 2,
+                single_line_max_width: Some(50),
+                ..FormatConfig::default()
+            },
+        )
+        .unwrap();
+
+        assert_eq!(
+            dedent(
+                r#"
+                module foo {
+                  import bar { prefix b; }
+                  leaf baz {
+                    type bits {
+                      bit up { position 0; }
+                      bit down { position 1; description "Down"; }
+                      bit sideways-and-also-a-very-long-name-that-does-not-fit {
+                        position 2;
+                      }
+                    }
+                  }
+                }
+                "#
+            ),
+            result,
+        );
+    }
+
+    #[test]
+    fn test_format_mixed_line_endings() {
+        let input = "module foo {\r\n  description\r\n    \"Foo\r\n     bar\";\n  leaf bar;\r\n}\n";
+
+        assert!(has_mixed_line_endings(input.as_bytes()));
+        assert_eq!(
+            "module foo {\r\n  description\r\n    \"Foo\r\n     bar\";\r\n  leaf bar;\r\n}\r\n",
+            format_yang_str(input.as_bytes(), &FormatConfig::default()).unwrap(),
+        );
+        assert_eq!(
+            "module foo {\n  description\n    \"Foo\n     bar\";\n  leaf bar;\n}\n",
+            format_yang_str(
+                input.as_bytes(),
+                &FormatConfig {
+                    line_ending: LineEnding::Lf,
+                    ..FormatConfig::default()
+                }
+            )
+            .unwrap(),
+        );
+    }
+
+    #[test]
+    fn test_format_with_wrapped_value_indent() {
+        let input = dedent(
+            r#"
+            module foo {
+              leaf bar {
+                type leafref-with-an-annoyingly-long-name-that-really-does-not-fit-in-the-width;
+              }
+            }
+            "#,
+        );
+
+        let format = |wrapped_value_indent| {
+            format_yang_str(
+                input.as_bytes(),
+                &FormatConfig {
+                    wrapped_value_indent,
+                    ..FormatConfig::default()
+                },
+            )
+            .unwrap()
+        };
+
+        assert_eq!(
+            dedent(
+                r#"
+                module foo {
+                  leaf bar {
+                    type
+                         leafref-with-an-annoyingly-long-name-that-really-does-not-fit-in-the-width;
+                  }
+                }
+                "#
+            ),
+            format(WrappedValueIndent::Aligned),
+        );
+        assert_eq!(
+            dedent(
+                r#"
+                module foo {
+                  leaf bar {
+                    type
+                      leafref-with-an-annoyingly-long-name-that-really-does-not-fit-in-the-width;
+                  }
+                }
+                "#
+            ),
+            format(WrappedValueIndent::Column(2)),
+        );
+        assert_eq!(
+            dedent(
+                r#"
+                module foo {
+                  leaf bar {
+                    type
+                        leafref-with-an-annoyingly-long-name-that-really-does-not-fit-in-the-width;
+                  }
+                }
+                "#
+            ),
+            format(WrappedValueIndent::Column(8)),
+        );
+    }
+
+    #[test]
+    fn test_format_if_feature_expressions() {
+        let input = dedent(
+            r#"
+            module foo {
+                leaf bar {
+                    if-feature "not(foo)and ( bar   or baz )";
+                    if-feature "first-feature-name or second-feature-name or (third-feature and fourth)";
+                    type string;
+                }
+            }
+            "#,
+        );
+
+        let expected = dedent(
+            r#"
+            module foo {
+              leaf bar {
+                if-feature "not (foo) and (bar or baz)";
+                if-feature
+                  "first-feature-name or second-feature-name
+                     or (third-feature and fourth)";
+                type string;
+              }
+            }
+            "#,
+        );
+
+        assert_eq!(
+            expected,
+            format_yang_str(input.as_bytes(), &FormatConfig::default()).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_format_with_reflow_error_messages() {
+        let result = format_yang_str(
+            dedent(
+                r#"
+                leaf foo {
+                    type uint8 {
+                        range "1..10" {
+                            error-message "The value must be between one and ten, both inclusive";
+                        }
+                    }
+                    must ". != 5" {
+                        // yangfmt: verbatim
+                        error-message "Five is right out, as everybody knows by now";
+                    }
+                }
+                "#,
+            )
+            .as_bytes(),
+            &(FormatConfig {
+                line_length: 50,
+                reflow_text: true,
+                ..Default::default()
+            }),
+        )
+        .unwrap();
+
+        assert_eq!(
+            dedent(
+                r#"
+                leaf foo {
+                  type uint8 {
+                    range "1..10" {
+                      error-message
+                        "The value must be between one and ten,
+                         both inclusive";
+                    }
+                  }
+                  must ". != 5" {
+                    // yangfmt: verbatim
+                    error-message
+                      "Five is right out, as everybody knows by now";
+                  }
+                }
+                "#
+            ),
+            result,
+        );
+    }
+
+    #[test]
+    fn test_find_canonical_order_violations() {
+        let input = "module foo {\n  prefix f;\n  namespace urn:foo;\n  leaf bar {\n    description x;\n    type string;\n  }\n}\n";
+
+        assert_eq!(
+            vec![
+                Diagnostic::new(
+                    "\"namespace\" statement is out of canonical order, it should come before \"prefix\"",
+                    27,
+                ).with_code("canonical-order"),
+                Diagnostic::new(
+                    "\"type\" statement is out of canonical order, it should come before \"description\"",
+                    82,
+                ).with_code("canonical-order"),
+            ],
+            find_canonical_order_violations(input.as_bytes(), &ExtensionRules::new()).unwrap(),
+        );
+    }
+
+    #[test]
+    fn test_format_with_sort_revisions() {
+        let input = dedent(
+            r#"
+            module foo {
+              revision 2020-01-01 {
+                description "Initial revision";
+              }
+              revision 2024-06-01 {
+                description "Added bar";
+              }
+            }
+            "#,
+        );
+
+        let format = |sort_revisions| {
+            let config = FormatConfig {
+                sort_revisions,
+                ..FormatConfig::default()
+            };
+            let mut output: Vec<u8> = vec![];
+            let diagnostics = format_yang(&mut output, input.as_bytes(), &config).unwrap();
+
+            (String::from_utf8(output).unwrap(), diagnostics)
+        };
+
+        // Unsorted revisions are reported by the "revision-order" lint rule, not the formatter
+        let (output, diagnostics) = format(false);
+
+        assert_eq!(input, output);
+        assert_eq!(Vec::<Diagnostic>::new(), diagnostics);
+
+        let (output, diagnostics) = format(true);
+
+        assert_eq!(
+            dedent(
+                r#"
+                module foo {
+                  revision 2024-06-01 {
+                    description "Added bar";
+                  }
+                  revision 2020-01-01 {
+                    description "Initial revision";
+                  }
+                }
+                "#
+            ),
+            output
+        );
+        assert_eq!(Vec::<Diagnostic>::new(), diagnostics);
+    }
+
+    #[test]
+    fn test_format_with_sort_revisions_and_empty_lines() {
+        let input = dedent(
+            r#"
+            module foo {
+              revision 2020-01-01;
+
+              revision 2024-06-01;
+            }
+            "#,
+        );
+
+        let format = |force_sort| {
+            let config = FormatConfig {
+                sort_revisions: true,
+                force_sort,
+                ..FormatConfig::default()
+            };
+            let mut output: Vec<u8> = vec![];
+            let diagnostics = format_yang(&mut output, input.as_bytes(), &config).unwrap();
+
+            (String::from_utf8(output).unwrap(), diagnostics)
+        };
+
+        let (output, diagnostics) = format(false);
+
+        assert_eq!(input, output);
+        assert_eq!(
+            vec![Diagnostic::new(
+                "Revisions need manual ordering, they're not listed newest first but have \
+                 comments or empty lines between them",
+                input.find("revision 2024").unwrap(),
+            )
+            .with_code("manual-ordering")],
+            diagnostics
+        );
+
+        let (output, _) = format(true);
+
+        assert_eq!(
+            dedent(
+                r#"
                 module foo {
+                  revision 2024-06-01;
 
-                bar      testing  ;
-                foo      123.45   ;
+                  revision 2020-01-01;
+                }
+                "#
+            ),
+            output
+        );
+    }
 
-                revision 2022-02-03 {
+    #[test]
+    fn test_format_reports_lossy_changes() {
+        let input = dedent(
+            r#"
+            module foo {
+              import b { prefix b; }
+
+              import a { prefix a; }
+              leaf bar /* bytes */ {
+                description "   ";
+              }
+            }
+            "#,
+        );
+
+        let format = |report_lossy_changes| {
+            let config = FormatConfig {
+                report_lossy_changes,
+                fix_canonical_order: true,
+                force_sort: true,
+                ..FormatConfig::default()
+            };
+            let mut output: Vec<u8> = vec![];
+            let diagnostics = format_yang(&mut output, input.as_bytes(), &config).unwrap();
+
+            (String::from_utf8(output).unwrap(), diagnostics)
+        };
+
+        let (output, diagnostics) = format(true);
+
+        assert_eq!(
+            vec![
+                Diagnostic::new(
+                    "String with only whitespace replaced by an empty string",
+                    input.find(r#""   ""#).unwrap(),
+                )
+                .with_code("string-emptied"),
+                Diagnostic::new(
+                    "Comment inside the \"leaf\" statement moved after its opening brace or \
+                     semicolon",
+                    input.find("leaf").unwrap(),
+                )
+                .with_code("comment-moved"),
+                Diagnostic::new(
+                    "1 blank line(s) removed while sorting the statements of this block",
+                    input.find("import a").unwrap(),
+                )
+                .with_code("blank-lines-removed"),
+            ],
+            diagnostics
+        );
+        assert_eq!((output, vec![]), format(false));
+    }
+
+    #[test]
+    fn test_format_with_enum_sorting() {
+        let input = dedent(
+            r#"
+            module foo {
+              leaf bar {
+                type enumeration {
+                  enum up {
+                    value 1;
+                  }
+                  // Default state
+                  enum down {
+                    value 0;
+                  }
                 }
-                    revision 2022-02-02
-                    { description "qwerty"; }
+              }
+            }
+            "#,
+        );
 
-                //
-                // Some string formatting tests
-                //
+        let config = FormatConfig {
+            enum_sorting: EnumSorting::Value,
+            ..FormatConfig::default()
+        };
+        let mut output: Vec<u8> = vec![];
+        let diagnostics = format_yang(&mut output, input.as_bytes(), &config).unwrap();
 
-                test "I am not affected";
-                test 'I am converted';
-                test 'These "quotes" should remain single';
+        // The comment may mean the enums are ordered on purpose
+        assert_eq!(input, String::from_utf8(output).unwrap());
+        assert_eq!(
+            vec![Diagnostic::new(
+                "Enums need manual ordering, they're not sorted but have comments or empty lines \
+                 between them",
+                input.find("enum down").unwrap(),
+            )
+            .with_code("manual-ordering")],
+            diagnostics
+        );
 
-                description "I am short and sweet";
-                description "I should stay on this line line <----------------->";
-                description "I should be wrapped to the next line <------------->";
-                description "  I should be stripped   ";
-                description
-                    "
-                    I should be stripped and changed to 1 line
-                    ";
-                description "I am multi-lined,
-                    so I automatically get wrapped
-                    to the next line even though each
-                    individual line is short.";
+        let config = FormatConfig {
+            force_sort: true,
+            ..config
+        };
 
-                description "
-                The first line break here should be removed
+        assert_eq!(
+            dedent(
+                r#"
+                module foo {
+                  leaf bar {
+                    type enumeration {
+                      // Default state
+                      enum down {
+                        value 0;
+                      }
+                      enum up {
+                        value 1;
+                      }
+                    }
+                  }
+                }
+                "#
+            ),
+            format_yang_str(input.as_bytes(), &config).unwrap(),
+        );
+    }
 
-                     Then the rest of the string should be properly indented.
-                     The trailing line breaks should also be removed.
+    #[test]
+    fn test_format_with_enum_sorting_and_implicit_values() {
+        let input = dedent(
+            r#"
+            module foo {
+              leaf bar {
+                type enumeration {
+                  enum zeta;
+                  enum alpha;
+                }
+              }
+            }
+            "#,
+        );
 
-                ";
+        for enum_sorting in [EnumSorting::Name, EnumSorting::Value] {
+            let config = FormatConfig {
+                enum_sorting,
+                ..FormatConfig::default()
+            };
+            let mut output: Vec<u8> = vec![];
+            let diagnostics = format_yang(&mut output, input.as_bytes(), &config).unwrap();
+
+            // Implicit values are already in order, but swapping the names changes their values
+            assert_eq!(input, String::from_utf8(output).unwrap());
+            assert_eq!(
+                match enum_sorting {
+                    EnumSorting::Name => vec![Diagnostic::new(
+                        "Enums can't be sorted without changing the value of the ones without a \
+                         \"value\" statement",
+                        input.find("enum zeta").unwrap(),
+                    )
+                    .with_code("implicit-values")],
+                    _ => vec![],
+                },
+                diagnostics
+            );
+        }
+    }
 
-                pattern '((:|[0-9a-fA-F]{0,4}):)([0-9a-fA-F]{0,4}:){0,5}'+'((([0-9a-fA-F]{0,4}:)?(:|[0-9a-fA-F]{0,4}))|'
-                + '(((25[0-5]|2[0-4][0-9]|[01]?[0-9]?[0-9])\.){3}'
-                 + '(25[0-5]|2[0-4][0-9]|[01]?[0-9]?[0-9])))'
-                + '(%[\p{N}\p{L}]+)?';
+    #[test]
+    fn test_format_with_sort_by_name() {
+        let input = dedent(
+            r#"
+            module foo {
+              feature zeta;
+              identity beta;
+              feature alpha;
+              // Base identity
+              identity alpha;
+            }
+            "#,
+        );
 
-                pattern
-                "foo" + 'bar'
-                + 'baz';
+        let config = FormatConfig {
+            sort_by_name: vec!["feature".to_string(), "identity".to_string()],
+            ..FormatConfig::default()
+        };
 
-                augment "/foo"+"/bar"
-                +"/baz"
-                {
+        let mut output: Vec<u8> = vec![];
+        let diagnostics = format_yang(&mut output, input.as_bytes(), &config).unwrap();
+
+        assert_eq!(
+            dedent(
+                r#"
+                module foo {
+                  feature alpha;
+                  identity beta;
+                  feature zeta;
+                  // Base identity
+                  identity alpha;
+                }
+                "#
+            ),
+            String::from_utf8(output).unwrap(),
+        );
+        assert_eq!(
+            vec![Diagnostic::new(
+                "\"identity\" statements need manual ordering, they're not sorted by name but \
+                 have comments or empty lines between them",
+                input.find("identity alpha").unwrap(),
+            )
+            .with_code("manual-ordering")],
+            diagnostics
+        );
+    }
 
+    #[test]
+    fn test_format_with_sort_by_name_nested() {
+        let input = dedent(
+            r#"
+            module foo {
+              container system {
+                typedef name {
+                  type string;
                 }
-
-                //
-                // Empty blocks
-                //
-
-                test{}
-
-                test{
+                leaf hostname {
+                  type name;
                 }
-
-                test{
-
+                grouping timers;
+                typedef address {
+                  type string;
                 }
+                leaf domain {
+                  type string;
+                }
+                grouping counters;
+              }
+            }
+            "#,
+        );
 
-                //
-                // Comments
-                //
+        let config = FormatConfig {
+            sort_by_name: vec!["typedef".to_string(), "grouping".to_string()],
+            ..FormatConfig::default()
+        };
 
-                test // This sometimes happens and must be supported
-                {
-                    foo bar;
+        assert_eq!(
+            dedent(
+                r#"
+                module foo {
+                  container system {
+                    typedef address {
+                      type string;
+                    }
+                    leaf hostname {
+                      type name;
+                    }
+                    grouping counters;
+                    typedef name {
+                      type string;
+                    }
+                    leaf domain {
+                      type string;
+                    }
+                    grouping timers;
+                  }
                 }
+                "#
+            ),
+            format_yang_str(input.as_bytes(), &config).unwrap(),
+        );
+    }
 
-                test "something" // This sometimes happens and must be supported
-                {
-                    foo bar;
+    #[test]
+    fn test_format_with_documentation_last() {
+        let input = dedent(
+            r#"
+            module foo {
+              description "Not moved";
+              container bar {
+                description "Bar";
+                presence "Enables bar";
+                leaf baz {
+                  reference "RFC 7950";
+                  type string;
+                  status deprecated;
                 }
+              }
+            }
+            "#,
+        );
 
-                test "foo" /* This would be weird */ /* But let's support it anyway */
-                {
-                    foo bar;
-                }
+        let config = FormatConfig {
+            documentation_last: true,
+            ..FormatConfig::default()
+        };
 
-                test /* foo */ /* bar */ /* baz */ "foo" /* pow */
-                {
-                    // Nobody's ever going to do this (hopefully) so let's not even bother trying
-                    // to make it prettier. Just don't crash.
+        assert_eq!(
+            dedent(
+                r#"
+                module foo {
+                  description "Not moved";
+                  container bar {
+                    presence "Enables bar";
+                    description "Bar";
+                    leaf baz {
+                      type string;
+                      status deprecated;
+                      reference "RFC 7950";
+                    }
+                  }
                 }
+                "#
+            ),
+            format_yang_str(input.as_bytes(), &config).unwrap(),
+        );
+    }
 
-                test "foo"; // A comment here is fine
-                test "foo" /* This however, is not fine*/ ;
-                test /* Nobody would ever do this, let's just not crash */ "foo" /* yuck */ ;
+    #[test]
+    fn test_format_with_profiles() {
+        let input = dedent(
+            r#"
+            module foo {
+              revision 2020-01-01;
+              revision 2024-01-01;
+              container a {
+                description "An example container with a description past 70 columns";
+              }
+              container b {
+                presence "Enables b";
+              }
+            }
+            "#,
+        );
 
-                //
-                // Canonical order
-                //
+        assert_eq!(
+            dedent(
+                r#"
+                module foo {
+                  revision 2024-01-01;
+                  revision 2020-01-01;
+                  container a {
+                    description
+                      "An example container with a description past 70 columns";
+                  }
 
-                leaf moo {
-                    description "I should not be sorted because sorting is not enabled";
-                    type string;
+                  container b {
+                    presence "Enables b";
+                  }
                 }
-                }"#,
-            )
-            .as_bytes(),
-            &(FormatConfig {
-                indent: Indent::Spaces(4),
-                line_length: 70,
-                fix_canonical_order: false,
-            }),
-        )
-        .unwrap();
+                "#
+            ),
+            format_yang_str(input.as_bytes(), &Profile::Ietf.config()).unwrap(),
+        );
 
         assert_eq!(
             dedent(
                 r#"
-                //
-                // Comments outside the module block should be fine
-                //
                 module foo {
-                    bar testing;
-                    foo 123.45;
-
-                    revision 2022-02-03 {
-                    }
-                    revision 2022-02-02 {
-                        description "qwerty";
-                    }
-
-                    //
-                    // Some string formatting tests
-                    //
-
-                    test "I am not affected";
-                    test "I am converted";
-                    test 'These "quotes" should remain single';
-
-                    description "I am short and sweet";
-                    description "I should stay on this line line <----------------->";
-                    description
-                        "I should be wrapped to the next line <------------->";
-                    description "I should be stripped";
-                    description "I should be stripped and changed to 1 line";
-                    description
-                        "I am multi-lined,
-                         so I automatically get wrapped
-                         to the next line even though each
-                         individual line is short.";
+                  revision 2020-01-01;
+                  revision 2024-01-01;
 
-                    description
-                        "The first line break here should be removed
+                  container a {
+                    description "An example container with a description past 70 columns";
+                  }
 
-                         Then the rest of the string should be properly indented.
-                         The trailing line breaks should also be removed.";
+                  container b {
+                    presence "Enables b";
+                  }
+                }
+                "#
+            ),
+            format_yang_str(input.as_bytes(), &Profile::OpenConfig.config()).unwrap(),
+        );
+    }
 
-                    pattern "((:|[0-9a-fA-F]{0,4}):)([0-9a-fA-F]{0,4}:){0,5}"
-                          + "((([0-9a-fA-F]{0,4}:)?(:|[0-9a-fA-F]{0,4}))|"
-                          + "(((25[0-5]|2[0-4][0-9]|[01]?[0-9]?[0-9])\.){3}"
-                          + "(25[0-5]|2[0-4][0-9]|[01]?[0-9]?[0-9])))"
-                          + "(%[\p{N}\p{L}]+)?";
+    #[test]
+    fn test_format_with_fold_long_lines() {
+        let input = dedent(
+            r#"
+            module foo {
+              leaf bar {
+                type string {
+                  pattern "[a-zA-Z_][a-zA-Z0-9_.-]*(:[a-zA-Z_][a-zA-Z0-9_.-]*)?(/[a-z]+)*";
+                }
+              }
+            }
+            "#,
+        );
 
-                    pattern "foo"
-                          + "bar"
-                          + "baz";
+        assert_eq!(
+            dedent(
+                r#"
+                =============== NOTE: '\' line wrapping per RFC 8792 ================
 
-                    augment "/foo"
-                          + "/bar"
-                          + "/baz" {
+                module foo {
+                  leaf bar {
+                    type string {
+                      pattern "[a-zA-Z_][a-zA-Z0-9_.-]*(:[a-zA-Z_][a-zA-Z0-9_.-]*)?(\
+                /[a-z]+)*";
                     }
+                  }
+                }
+                "#
+            ),
+            format_yang_str(input.as_bytes(), &Profile::Rfc.config()).unwrap(),
+        );
 
-                    //
-                    // Empty blocks
-                    //
+        assert_eq!(
+            input,
+            format_yang_str(
+                input.as_bytes(),
+                &FormatConfig {
+                    fold_long_lines: false,
+                    ..Profile::Rfc.config()
+                }
+            )
+            .unwrap(),
+        );
+    }
 
-                    test {
-                    }
+    #[test]
+    fn test_format_with_pyang_style() {
+        let input = dedent(
+            r#"
+            module foo {
+              namespace "urn:foo";
+              prefix foo;
+              import bar { prefix b; }
+              description "Example module";
+
+              revision 2024-01-01 { description "Initial revision"; }
+              container baz {
+                leaf name {
+                  type string;
+                  default very-long-default-value-that-goes-past-the-max-width-of-the-line;
+                }
+              }
+              leaf qux { type b:counter; }
+            }
+            "#,
+        );
 
-                    test {
-                    }
+        let config = FormatConfig {
+            style: Style::Pyang,
+            ..FormatConfig::default()
+        };
 
-                    test {
-                    }
+        assert_eq!(
+            dedent(
+                r#"
+                module foo {
+                  namespace "urn:foo";
+                  prefix foo;
 
-                    //
-                    // Comments
-                    //
+                  import bar {
+                    prefix b;
+                  }
 
-                    test { // This sometimes happens and must be supported
-                        foo bar;
-                    }
+                  description
+                    "Example module";
 
-                    test "something" { // This sometimes happens and must be supported
-                        foo bar;
-                    }
+                  revision 2024-01-01 {
+                    description
+                      "Initial revision";
+                  }
 
-                    test "foo" { /* This would be weird */ /* But let's support it anyway */
-                        foo bar;
+                  container baz {
+                    leaf name {
+                      type string;
+                      default "very-long-default-value-that-goes-past-the-max-width-of-the-line";
                     }
+                  }
 
-                    test "foo" { /* foo */ /* bar */ /* baz */ /* pow */
-                        // Nobody's ever going to do this (hopefully) so let's not even bother trying
-                        // to make it prettier. Just don't crash.
-                    }
+                  leaf qux {
+                    type b:counter;
+                  }
+                }
+                "#
+            ),
+            format_yang_str(input.as_bytes(), &config).unwrap(),
+        );
+    }
 
-                    test "foo"; // A comment here is fine
-                    test "foo"; /* This however, is not fine*/
-                    test "foo"; /* Nobody would ever do this, let's just not crash */ /* yuck */
+    #[test]
+    fn test_format_vendor_extensions() {
+        let input = dedent(
+            r#"
+            module foo {
+              container bar {
+                tailf:cli-drop-node-name;
+                tailf:info 'Bar settings';
+                leaf baz {
+                  tailf:callpoint baz-hook { tailf:transform true; }
+                  type string;
+                  description "Baz";
+                  when "../qux = 'on'";
+                }
+                description "Bar";
+              }
+            }
+            "#,
+        );
 
-                    //
-                    // Canonical order
-                    //
+        let config = FormatConfig {
+            fix_canonical_order: true,
+            quote_style: QuoteStyle::Double,
+            ..FormatConfig::default()
+        };
 
-                    leaf moo {
-                        description
-                            "I should not be sorted because sorting is not enabled";
-                        type string;
+        assert_eq!(
+            dedent(
+                r#"
+                module foo {
+                  container bar {
+                    description "Bar";
+                    tailf:info "Bar settings";
+                    leaf baz {
+                      when "../qux = 'on'";
+                      type string;
+                      description "Baz";
+                      tailf:callpoint baz-hook { tailf:transform true; }
                     }
+                    tailf:cli-drop-node-name;
+                  }
                 }
                 "#
             ),
-            result,
+            format_yang_str(input.as_bytes(), &config).unwrap(),
         );
     }
 
     #[test]
-    #[ignore]
-    fn test_format_with_fix_canonical_order() {
-        let result = format_yang_str(
-            dedent(
-                r#"
-                leaf {
-                    type string;
-
-
-                    description "I should be moved to the bottom";
-
-                    must "foo" {
-                        // ...
-                    }
+    fn test_format_configured_extensions() {
+        let input = dedent(
+            r#"
+            module foo {
+              leaf bar {
+                md:annotation "last-modified" {
                 }
-                "#,
-            )
-            .as_bytes(),
-            &(FormatConfig {
-                indent: Indent::Spaces(4),
-                line_length: 70,
-                fix_canonical_order: true,
-            }),
-        )
-        .unwrap();
+                type string;
+                description "Bar";
+              }
+            }
+            "#,
+        );
+
+        let config = FormatConfig {
+            fix_canonical_order: true,
+            unquote_simple_arguments: true,
+            extensions: ExtensionRules::from([(
+                "md:annotation".to_string(),
+                ExtensionRule {
+                    argument: ArgumentKind::Identifier,
+                    placement: Placement::After("description".to_string()),
+                    single_line: false,
+                    children: false,
+                },
+            )]),
+            ..FormatConfig::default()
+        };
 
         assert_eq!(
             dedent(
                 r#"
-                leaf {
+                module foo {
+                  leaf bar {
                     type string;
-                    description "I should be moved to the bottom";
+                    description "Bar";
+                    md:annotation last-modified;
+                  }
                 }
                 "#
             ),
-            result,
+            format_yang_str(input.as_bytes(), &config).unwrap(),
         );
     }
 }
@@ -0,0 +1,65 @@
+//! Helpers for normalizing the boolean feature expressions used as "if-feature" arguments.
+//!
+//! The grammar is small: feature names (possibly prefixed), "and", "or", "not" and parentheses.
+//! Long expressions are wrapped with "xpath::wrap_at_operators".
+
+/// Normalizes the spacing of an if-feature expression
+///
+/// Tokens are separated by single spaces, except right inside parentheses. Parentheses are kept
+/// as written. Essentially converts:
+///
+///     not(foo)and ( bar   or baz )
+///
+/// Into:
+///
+///     not (foo) and (bar or baz)
+///
+/// Returns None if the expression contains anything that isn't valid in an if-feature
+/// expression, or if its parentheses aren't balanced.
+///
+pub fn normalize_if_feature(expr: &str) -> Option<String> {
+    let spaced = expr.replace('(', " ( ").replace(')', " ) ");
+    let mut output = String::with_capacity(expr.len());
+    let mut depth = 0;
+    let mut previous = "";
+
+    for token in spaced.split_whitespace() {
+        match token {
+            "(" => depth += 1,
+            ")" if depth == 0 => return None,
+            ")" => depth -= 1,
+            _ if !token.chars().all(is_feature_name_char) => return None,
+            _ => (),
+        }
+
+        if !output.is_empty() && previous != "(" && token != ")" {
+            output.push(' ');
+        }
+
+        output.push_str(token);
+        previous = token;
+    }
+
+    (depth == 0).then_some(output)
+}
+
+fn is_feature_name_char(c: char) -> bool {
+    c.is_alphanumeric() || matches!(c, '-' | '_' | '.' | ':')
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_normalize_if_feature() {
+        assert_eq!(
+            Some("not (foo) and (bar or x:baz)".to_string()),
+            normalize_if_feature(" not(foo)and ( bar\n   or x:baz ) "),
+        );
+        assert_eq!(Some("foo".to_string()), normalize_if_feature("foo"));
+        assert_eq!(None, normalize_if_feature("(foo or bar"));
+        assert_eq!(None, normalize_if_feature("foo) or (bar"));
+        assert_eq!(None, normalize_if_feature("foo = 'bar'"));
+    }
+}
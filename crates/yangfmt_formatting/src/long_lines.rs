@@ -0,0 +1,143 @@
+//! Measures the formatted output, to report the lines the formatter couldn't make fit.
+
+use std::collections::HashMap;
+
+use yangfmt_lexing::TokenType;
+use yangfmt_parsing::Diagnostic;
+
+use crate::FormatConfig;
+
+/// Reports the lines of the formatted output that are still longer than the max width
+///
+/// Lines can't always be wrapped to fit, e.g. when they hold a long pattern, a URL or another
+/// string without spaces. Each line is measured against the max width of the statement it belongs
+/// to, so lines of keywords with a wider "max_width" override aren't reported.
+///
+/// Unlike other diagnostics, the positions are byte offsets in the output rather than the input.
+///
+pub fn find_long_lines(output: &[u8], config: &FormatConfig) -> Vec<Diagnostic> {
+    let Ok(text) = std::str::from_utf8(output) else {
+        return vec![];
+    };
+
+    let keywords = line_keywords(output);
+    let mut diagnostics = vec![];
+    let mut position = 0;
+
+    for (index, line) in text.split('\n').enumerate() {
+        let line_length = line.trim_end_matches('\r').chars().count();
+        let max_width = match keywords.get(&index) {
+            Some(keyword) => config.max_width(keyword),
+            None => config.line_length,
+        } as usize;
+
+        if line_length > max_width {
+            diagnostics.push(
+                Diagnostic::new(
+                    format!(
+                        "Line is still {line_length} characters long after formatting, the max \
+                         width is {max_width}"
+                    ),
+                    position,
+                )
+                .with_code("long-line"),
+            );
+        }
+
+        position += line.len() + 1;
+    }
+
+    diagnostics
+}
+
+/// Returns the keyword of the statement each line starts in, by line index
+///
+/// Lines with nothing but whitespace and comments are left out.
+///
+fn line_keywords(output: &[u8]) -> HashMap<usize, &str> {
+    let mut keywords = HashMap::new();
+
+    let Ok(tokens) = yangfmt_lexing::scan(output) else {
+        return keywords;
+    };
+
+    let mut keyword = None;
+    let mut statement_start = true;
+    let mut line = 0;
+
+    for token in &tokens {
+        let line_count = token.text.matches('\n').count();
+
+        match token.token_type {
+            TokenType::WhiteSpace | TokenType::LineBreak | TokenType::Comment => (),
+            TokenType::SemiColon | TokenType::OpenCurlyBrace | TokenType::ClosingCurlyBrace => {
+                statement_start = true;
+            }
+            _ => {
+                if statement_start {
+                    keyword = Some(token.text);
+                    statement_start = false;
+                }
+
+                if let Some(keyword) = keyword {
+                    for index in line..=line + line_count {
+                        keywords.entry(index).or_insert(keyword);
+                    }
+                }
+            }
+        }
+
+        line += line_count;
+    }
+
+    keywords
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::KeywordOverrides;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_find_long_lines() {
+        let output = concat!(
+            "module foo {\n",
+            "  description\n",
+            "    \"Some text that is too\n",
+            "     long: https://example.com/a-very-long-url\";\n",
+            "  pattern '[a-z]+-[0-9]+-[a-z]+';\n",
+            "}\n",
+        );
+        let mut config = FormatConfig {
+            line_length: 30,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            vec![
+                Diagnostic::new(
+                    "Line is still 48 characters long after formatting, the max width is 30",
+                    output.find("     long").unwrap(),
+                )
+                .with_code("long-line"),
+                Diagnostic::new(
+                    "Line is still 33 characters long after formatting, the max width is 30",
+                    output.find("  pattern").unwrap(),
+                )
+                .with_code("long-line"),
+            ],
+            find_long_lines(output.as_bytes(), &config),
+        );
+
+        config.keyword_overrides.insert(
+            "pattern".to_string(),
+            KeywordOverrides {
+                max_width: Some(40),
+                ..Default::default()
+            },
+        );
+
+        assert_eq!(1, find_long_lines(output.as_bytes(), &config).len());
+    }
+}
@@ -0,0 +1,190 @@
+//! Parsing of "yangfmt:" comments at the top of a file, which change how that file is formatted.
+//!
+//! Only comments before the first statement of the file are considered, for example:
+//!
+//!     // yangfmt: max-width=100 no-reflow
+//!     module foo { ... }
+//!
+//! The "off", "on" and "skip" directives are handled by the parser and are ignored here.
+
+use yangfmt_lexing::TokenType;
+
+use crate::{FormatConfig, IdentifierQuoting, Indent, PathWrapping, QuoteStyle};
+
+/// Prefix of every comment addressed to the formatter
+const PRAGMA_PREFIX: &str = "yangfmt:";
+
+/// Directives that share the prefix but aren't options
+const DIRECTIVES: &[&str] = &["off", "on", "skip"];
+
+/// Comment that makes the formatter leave the whole file alone
+const SKIP_FILE: &str = "skip-file";
+
+/// Returns the comments before the first statement of the file
+fn header_comments(buffer: &[u8]) -> impl Iterator<Item = &str> {
+    yangfmt_lexing::scan_iter(buffer)
+        .map_while(Result::ok)
+        .take_while(|token| {
+            matches!(
+                token.token_type,
+                TokenType::Comment | TokenType::WhiteSpace | TokenType::LineBreak
+            )
+        })
+        .filter(|token| token.token_type == TokenType::Comment)
+        .map(|token| token.text)
+}
+
+/// Returns the words following "yangfmt:" in the comment, if the comment starts with it
+///
+/// Words are separated by whitespace or commas, e.g. "max-width=100, no-reflow". Comments that
+/// only mention yangfmt somewhere in the text aren't pragmas.
+///
+fn pragma_words(comment: &str) -> Vec<&str> {
+    let text = comment
+        .strip_prefix("//")
+        .or_else(|| comment.strip_prefix("/*"))
+        .unwrap_or(comment)
+        .trim_start();
+
+    let Some(rest) = text.strip_prefix(PRAGMA_PREFIX) else {
+        return vec![];
+    };
+
+    let rest = rest.lines().next().unwrap_or_default();
+    let rest = rest.trim_end().trim_end_matches("*/");
+
+    rest.split(|c: char| c.is_whitespace() || c == ',')
+        .filter(|word| !word.is_empty())
+        .collect()
+}
+
+/// Checks if the file has a "yangfmt: skip-file" comment before its first statement
+pub fn has_skip_file_pragma(buffer: &[u8]) -> bool {
+    header_comments(buffer).any(|comment| pragma_words(comment).first() == Some(&SKIP_FILE))
+}
+
+/// Applies the options given by "yangfmt:" comments at the top of the file to the config
+///
+/// Returns an error for unknown options and invalid values.
+///
+pub fn apply_pragmas(buffer: &[u8], config: &mut FormatConfig) -> Result<(), String> {
+    for comment in header_comments(buffer) {
+        let words = pragma_words(comment);
+
+        match words.first() {
+            None => continue,
+            Some(word) if DIRECTIVES.contains(word) || *word == SKIP_FILE => continue,
+            Some(_) => (),
+        }
+
+        for word in words {
+            apply_pragma(word, config)?;
+        }
+    }
+
+    Ok(())
+}
+
+fn apply_pragma(word: &str, config: &mut FormatConfig) -> Result<(), String> {
+    let (key, value) = match word.split_once('=') {
+        Some((key, value)) => (key, Some(value)),
+        None => (word, None),
+    };
+
+    fn parse<T: std::str::FromStr>(key: &str, value: Option<&str>) -> Result<T, String> {
+        let value = value.ok_or_else(|| format!("yangfmt pragma {key:?} requires a value"))?;
+
+        value
+            .parse()
+            .map_err(|_| format!("invalid value {value:?} for yangfmt pragma {key:?}"))
+    }
+
+    fn flag(key: &str, value: Option<&str>) -> Result<(), String> {
+        match value {
+            Some(_) => Err(format!("yangfmt pragma {key:?} doesn't take a value")),
+            None => Ok(()),
+        }
+    }
+
+    match key {
+        "max-width" => config.line_length = parse(key, value)?,
+        "tab-width" => config.indent = Indent::Spaces(parse(key, value)?),
+        "quote-style" => config.quote_style = parse::<QuoteStyle>(key, value)?,
+        "path-wrapping" => config.path_wrapping = parse::<PathWrapping>(key, value)?,
+        "identifier-quoting" => config.identifier_quoting = parse::<IdentifierQuoting>(key, value)?,
+        "reflow" | "no-reflow" => {
+            flag(key, value)?;
+            config.reflow_text = key == "reflow";
+        }
+        "reflow-comments" | "no-reflow-comments" => {
+            flag(key, value)?;
+            config.reflow_comments = key == "reflow-comments";
+        }
+        "canonical-order" | "no-canonical-order" => {
+            flag(key, value)?;
+            config.fix_canonical_order = key == "canonical-order";
+        }
+        _ => return Err(format!("unknown yangfmt pragma {key:?}")),
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_apply_pragmas() {
+        let mut config = FormatConfig {
+            reflow_text: true,
+            ..FormatConfig::default()
+        };
+
+        apply_pragmas(
+            b"// yangfmt: max-width=100, no-reflow\n/* yangfmt: quote-style=single */\n\
+              // yangfmt: off\nmodule foo {\n  // yangfmt: tab-width=8\n}\n",
+            &mut config,
+        )
+        .unwrap();
+
+        assert_eq!(100, config.line_length);
+        assert!(!config.reflow_text);
+        assert_eq!(QuoteStyle::Single, config.quote_style);
+        assert!(matches!(config.indent, Indent::Spaces(2)));
+
+        let mut config = FormatConfig::default();
+
+        assert!(apply_pragmas(b"// yangfmt: max-widht=100\n", &mut config).is_err());
+        assert!(apply_pragmas(b"// yangfmt: max-width=wide\n", &mut config).is_err());
+        assert!(apply_pragmas(b"// yangfmt: no-reflow=1\n", &mut config).is_err());
+    }
+
+    #[test]
+    fn test_apply_pragmas_ignores_prose() {
+        let mut config = FormatConfig::default();
+
+        apply_pragmas(
+            b"// This file is formatted with yangfmt: run make fmt\n\
+              /* Checked by yangfmt: see the Makefile */\nmodule foo {}\n",
+            &mut config,
+        )
+        .unwrap();
+
+        assert_eq!(FormatConfig::default().line_length, config.line_length);
+        assert!(!has_skip_file_pragma(
+            b"// Don't add yangfmt: skip-file here\nmodule foo {}\n"
+        ));
+    }
+
+    #[test]
+    fn test_has_skip_file_pragma() {
+        assert!(has_skip_file_pragma(
+            b"// yangfmt: skip-file\nmodule foo {}\n"
+        ));
+        assert!(!has_skip_file_pragma(b"// yangfmt: skip\nmodule foo {}\n"));
+        assert!(!has_skip_file_pragma(
+            b"module foo {\n  // yangfmt: skip-file\n}\n"
+        ));
+    }
+}
@@ -0,0 +1,215 @@
+/// This module handles re-wrapping prose text to fit within a given width.
+///
+/// The text is treated as a series of paragraphs separated by blank lines. Within a paragraph,
+/// lines are joined and re-wrapped at word boundaries. List items (lines starting with a bullet
+/// such as "-", "*", "o" or "1.") are wrapped separately, with continuation lines indented to
+/// line up with the text after the bullet.
+///
+use regex::Regex;
+use textwrap::{Options, WordSeparator, WrapAlgorithm};
+
+lazy_static! {
+    /// Matches the bullet at the start of a list item, including the whitespace after it
+    static ref BULLET_PATTERN: Regex = Regex::new(r"^([-*+o]|\d+[.)])\s+").unwrap();
+
+    /// Matches lines of ASCII diagrams and tables, such as "+--rw name" or "| a | b |"
+    static ref DIAGRAM_LINE_PATTERN: Regex = Regex::new(r"\+--|--\+|\|.*\|").unwrap();
+
+    /// Matches the spaces between two columns of a table
+    static ref COLUMN_GAP_PATTERN: Regex = Regex::new(r" {3,}").unwrap();
+}
+
+/// The narrowest width text will be wrapped to, regardless of indentation
+const MIN_WIDTH: usize = 20;
+
+/// A paragraph or list item, collected from one or more input lines
+struct Item {
+    initial_indent: String,
+    subsequent_indent: String,
+    text: String,
+    is_bullet: bool,
+}
+
+/// Re-wraps the given text to fit within the given width
+///
+/// Leading indentation is preserved for paragraphs and list items, and blank lines are kept as
+/// they are.
+///
+pub fn reflow_text(text: &str, width: usize) -> String {
+    let mut lines: Vec<String> = vec![];
+    let mut current: Option<Item> = None;
+
+    let flush = |current: &mut Option<Item>, lines: &mut Vec<String>| {
+        if let Some(item) = current.take() {
+            lines.extend(wrap_item(&item, width));
+        }
+    };
+
+    for line in text.lines() {
+        let trimmed = line.trim();
+
+        if trimmed.is_empty() {
+            flush(&mut current, &mut lines);
+            lines.push(String::new());
+            continue;
+        }
+
+        let indent = &line[..line.len() - line.trim_start().len()];
+
+        if let Some(bullet) = BULLET_PATTERN.find(trimmed) {
+            flush(&mut current, &mut lines);
+
+            current = Some(Item {
+                initial_indent: indent.to_string(),
+                subsequent_indent: format!("{}{}", indent, " ".repeat(bullet.end())),
+                text: trimmed.to_string(),
+                is_bullet: true,
+            });
+
+            continue;
+        }
+
+        // Lines continue the current item if they're indented the same way (or further, in
+        // the case of list items)
+        let continues_item = current.as_ref().is_some_and(|item| {
+            if item.is_bullet {
+                indent.len() > item.initial_indent.len()
+            } else {
+                indent == item.initial_indent
+            }
+        });
+
+        if continues_item {
+            let item = current.as_mut().unwrap();
+            item.text.push(' ');
+            item.text.push_str(trimmed);
+        } else {
+            flush(&mut current, &mut lines);
+
+            current = Some(Item {
+                initial_indent: indent.to_string(),
+                subsequent_indent: indent.to_string(),
+                text: trimmed.to_string(),
+                is_bullet: false,
+            });
+        }
+    }
+
+    flush(&mut current, &mut lines);
+
+    lines.join("\n")
+}
+
+/// Checks if at least two lines of the text look like part of an ASCII diagram or table, in which
+/// case the text shouldn't be re-wrapped or re-indented
+///
+/// Lines with several spaces between words only count as table rows if a column lines up with a
+/// column of another row, since prose sometimes has wide gaps too.
+///
+pub fn looks_preformatted(text: &str) -> bool {
+    let diagram_lines = text
+        .lines()
+        .filter(|line| DIAGRAM_LINE_PATTERN.is_match(line))
+        .count();
+
+    if diagram_lines >= 2 {
+        return true;
+    }
+
+    let rows: Vec<Vec<usize>> = text
+        .lines()
+        .map(column_starts)
+        .filter(|columns| !columns.is_empty())
+        .collect();
+
+    rows.iter().enumerate().any(|(index, columns)| {
+        rows[index + 1..]
+            .iter()
+            .any(|other| columns.iter().any(|column| other.contains(column)))
+    })
+}
+
+/// Returns where the columns after the first one start in a line of a table, counted in
+/// characters from the start of its text
+fn column_starts(line: &str) -> Vec<usize> {
+    let line = line.trim();
+
+    COLUMN_GAP_PATTERN
+        .find_iter(line)
+        .map(|gap| line[..gap.end()].chars().count())
+        .collect()
+}
+
+fn wrap_item(item: &Item, width: usize) -> Vec<String> {
+    let options = Options::new(width.max(MIN_WIDTH))
+        .initial_indent(&item.initial_indent)
+        .subsequent_indent(&item.subsequent_indent)
+        .word_separator(WordSeparator::AsciiSpace)
+        .wrap_algorithm(WrapAlgorithm::FirstFit)
+        .break_words(false);
+
+    textwrap::wrap(&item.text, options)
+        .into_iter()
+        .map(|line| line.into_owned())
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_reflow_paragraphs() {
+        let text = "This is a rather long line that will need to be wrapped somewhere\n\
+                    and this line\n\
+                    should be joined.\n\
+                    \n\
+                    Second paragraph.";
+
+        assert_eq!(
+            "This is a rather long line that will\n\
+             need to be wrapped somewhere and this\n\
+             line should be joined.\n\
+             \n\
+             Second paragraph.",
+            reflow_text(text, 40),
+        );
+    }
+
+    #[test]
+    fn test_looks_preformatted() {
+        assert!(looks_preformatted(
+            "Tree:\n  +--rw interfaces\n     +--rw interface* [name]"
+        ));
+        assert!(looks_preformatted(
+            "| Value | Meaning |\n|-------|---------|\n| 1     | Up      |"
+        ));
+        assert!(looks_preformatted("Name     Value\nfoo      1\nbar      2"));
+
+        assert!(!looks_preformatted(
+            "Plain prose that happens to be\nspread over a few lines.  Sentences\nmay use two spaces."
+        ));
+        assert!(!looks_preformatted(
+            "Note:    the value is only used\n    when the peer is down.   Otherwise\n    the default applies."
+        ));
+    }
+
+    #[test]
+    fn test_reflow_list_items() {
+        let text = "Supported values:\n\
+                    - foo: the first value, which has a long explanation attached\n\
+                    - bar: the second\n\
+                    \u{20}\u{20}value\n\
+                    1. numbered lists work too";
+
+        assert_eq!(
+            "Supported values:\n\
+             - foo: the first value, which has a long\n\
+             \u{20}\u{20}explanation attached\n\
+             - bar: the second value\n\
+             1. numbered lists work too",
+            reflow_text(text, 40),
+        );
+    }
+}
@@ -0,0 +1,289 @@
+//! This module contains helpers for working with the contents of quoted YANG strings.
+//!
+//! Unless otherwise noted, the functions in this module operate on the text between the quotes.
+
+/// Splits the string content into chunks no longer than the given width
+///
+/// Text containing spaces is split at word boundaries, keeping the space at the end of each chunk.
+/// If a single word is longer than the width, the chunk is allowed to overflow. Text without any
+/// spaces (such as regular expressions) is split at the width, but never in the middle of an
+/// escape sequence.
+///
+/// Concatenating the chunks always yields the original text.
+///
+pub fn split_string_content(text: &str, width: usize) -> Vec<&str> {
+    let has_spaces = text.contains(' ');
+    let mut chunks = vec![];
+    let mut rest = text;
+
+    while rest.chars().count() > width {
+        let window_end = rest
+            .char_indices()
+            .nth(width)
+            .map_or(rest.len(), |(index, _)| index);
+        let window = &rest[..window_end];
+
+        let split_at = if has_spaces {
+            match window.rfind(' ') {
+                Some(index) if index > 0 => index + 1,
+                _ => match rest[window_end..].find(' ') {
+                    Some(index) => window_end + index + 1,
+                    None => rest.len(),
+                },
+            }
+        } else {
+            let mut index = window_end;
+
+            while index > 1 && ends_with_escape(&rest[..index]) {
+                index -= 1;
+
+                while !rest.is_char_boundary(index) {
+                    index -= 1;
+                }
+            }
+
+            index
+        };
+
+        if split_at >= rest.len() {
+            break;
+        }
+
+        chunks.push(&rest[..split_at]);
+        rest = &rest[split_at..];
+    }
+
+    chunks.push(rest);
+    chunks
+}
+
+/// Splits a schema node identifier or leafref path into chunks no longer than the given width
+///
+/// The path is only split right before a "/", so every chunk except the first starts with a
+/// slash. Slashes inside predicates (e.g. "[name = current()/../name]") are ignored. Node
+/// identifiers longer than the width are allowed to overflow.
+///
+/// Concatenating the chunks always yields the original text.
+///
+pub fn split_path_segments(text: &str, width: usize) -> Vec<&str> {
+    let mut chunks = vec![];
+    let mut start = 0;
+    let mut end = 0;
+
+    let mut predicate_depth = 0;
+    let mut literal: Option<char> = None;
+
+    let boundaries = text
+        .char_indices()
+        .filter(|(index, c)| {
+            match (literal, c) {
+                (Some(quote), c) if *c == quote => literal = None,
+                (Some(_), _) => (),
+                (None, '\'' | '"') => literal = Some(*c),
+                (None, '[') => predicate_depth += 1,
+                (None, ']') => predicate_depth -= 1,
+                (None, '/') => return *index > 0 && predicate_depth == 0,
+                _ => (),
+            }
+
+            false
+        })
+        .map(|(index, _)| index)
+        .chain([text.len()]);
+
+    for boundary in boundaries {
+        if end > start && text[start..boundary].chars().count() > width {
+            chunks.push(&text[start..end]);
+            start = end;
+        }
+
+        end = boundary;
+    }
+
+    chunks.push(&text[start..]);
+    chunks
+}
+
+/// Escapes backslashes and double quotes, so the text can be put in a double-quoted string
+pub fn escape_double_quoted(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+/// Returns the literal value of double-quoted string content that only escapes backslashes and
+/// double quotes
+///
+/// Returns None if the content contains any other escape sequence, such as "\n", since the value
+/// can't be written the same way without escape processing.
+///
+pub fn unescape_quotes_and_backslashes(text: &str) -> Option<String> {
+    let mut output = String::with_capacity(text.len());
+    let mut chars = text.chars();
+
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            output.push(c);
+            continue;
+        }
+
+        match chars.next() {
+            Some(escaped @ ('\\' | '"')) => output.push(escaped),
+            _ => return None,
+        }
+    }
+
+    Some(output)
+}
+
+/// Checks if the character is invisible or a control character, other than regular whitespace
+pub fn is_non_printable(c: char) -> bool {
+    (c.is_control() && !matches!(c, '\t' | '\n' | '\r'))
+        || invisible_replacement(c).is_some()
+        || matches!(c, '\u{2028}' | '\u{2029}')
+}
+
+/// Replaces invisible characters that have an obvious replacement
+///
+/// Unusual spaces (such as non-breaking spaces) are replaced with regular spaces, and zero-width
+/// characters are removed. Control characters are left alone.
+///
+pub fn replace_invisible(text: &str) -> String {
+    let mut output = String::with_capacity(text.len());
+
+    for c in text.chars() {
+        match invisible_replacement(c) {
+            Some(replacement) => output.push_str(replacement),
+            None => output.push(c),
+        }
+    }
+
+    output
+}
+
+/// Returns what an invisible character should be replaced with, if it's safe to replace
+pub fn invisible_replacement(c: char) -> Option<&'static str> {
+    match c {
+        '\u{00a0}' | '\u{2000}'..='\u{200a}' | '\u{202f}' | '\u{205f}' | '\u{3000}' => Some(" "),
+        '\u{00ad}' | '\u{200b}'..='\u{200d}' | '\u{2060}' | '\u{feff}' => Some(""),
+        _ => None,
+    }
+}
+
+/// Removes the whitespace before every line break in the text
+///
+/// Whitespace at the very end of the text is kept, since it's not followed by a line break.
+///
+pub fn trim_line_ends(text: &str) -> String {
+    match text.rsplit_once('\n') {
+        Some((lines, last)) => {
+            let mut output: String = lines
+                .split('\n')
+                .map(|line| format!("{}\n", line.trim_end()))
+                .collect();
+
+            output.push_str(last);
+            output
+        }
+        None => text.to_string(),
+    }
+}
+
+/// Checks if the text ends with an unfinished escape sequence, i.e. an odd number of backslashes
+fn ends_with_escape(text: &str) -> bool {
+    text.chars().rev().take_while(|c| *c == '\\').count() % 2 == 1
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_trim_line_ends() {
+        assert_eq!(
+            "\"foo\n  bar\n\nbaz  \"",
+            trim_line_ends("\"foo  \n  bar\t\n \nbaz  \"")
+        );
+        assert_eq!("foo  ", trim_line_ends("foo  "));
+    }
+
+    #[test]
+    fn test_split_string_content() {
+        assert_eq!(
+            vec!["The quick brown ", "fox jumps over ", "the lazy dog"],
+            split_string_content("The quick brown fox jumps over the lazy dog", 16),
+        );
+        assert_eq!(
+            vec!["Incomprehensibilities ", "abound"],
+            split_string_content("Incomprehensibilities abound", 10),
+        );
+        assert_eq!(
+            vec!["[0-9a-f", "A-F]{0,", "4}"],
+            split_string_content("[0-9a-fA-F]{0,4}", 7),
+        );
+        assert_eq!(
+            vec!["abc", "\\.de", "fg"],
+            split_string_content("abc\\.defg", 4),
+        );
+        assert_eq!(vec!["short"], split_string_content("short", 10));
+    }
+
+    #[test]
+    fn test_escape_double_quoted() {
+        assert_eq!(
+            r#"C:\\foo \"bar\""#,
+            escape_double_quoted(r#"C:\foo "bar""#)
+        );
+    }
+
+    #[test]
+    fn test_unescape_quotes_and_backslashes() {
+        assert_eq!(
+            Some(r#"\d+"\."#.to_string()),
+            unescape_quotes_and_backslashes(r#"\\d+\"\\."#),
+        );
+        assert_eq!(None, unescape_quotes_and_backslashes(r"foo\nbar"));
+        assert_eq!(None, unescape_quotes_and_backslashes(r"\d+"));
+        assert_eq!(None, unescape_quotes_and_backslashes(r"trailing\"));
+    }
+
+    #[test]
+    fn test_non_printable() {
+        assert!(is_non_printable('\u{00a0}'));
+        assert!(is_non_printable('\u{200b}'));
+        assert!(is_non_printable('\u{0007}'));
+        assert!(!is_non_printable('\t'));
+        assert!(!is_non_printable('é'));
+
+        assert_eq!(
+            "no break\u{0007}",
+            replace_invisible("no\u{00a0}bre\u{200b}ak\u{0007}")
+        );
+    }
+
+    #[test]
+    fn test_split_path_segments() {
+        assert_eq!(
+            vec!["/if:interfaces/if:interface", "/ip:ipv4/ip:address"],
+            split_path_segments("/if:interfaces/if:interface/ip:ipv4/ip:address", 30),
+        );
+        assert_eq!(
+            vec!["/a", "/very-long-node-name", "/b"],
+            split_path_segments("/a/very-long-node-name/b", 5),
+        );
+        assert_eq!(
+            vec!["ip:ipv4/ip:address"],
+            split_path_segments("ip:ipv4/ip:address", 30)
+        );
+        assert_eq!(
+            vec![
+                "/if:interfaces",
+                "/if:interface[if:name = current()/../name]",
+                "/if:type"
+            ],
+            split_path_segments(
+                "/if:interfaces/if:interface[if:name = current()/../name]/if:type",
+                20
+            ),
+        );
+    }
+}
@@ -0,0 +1,196 @@
+//! Helpers for laying out the XPath expressions used as "when" and "must" arguments.
+//!
+//! The functions in this module operate on the text between the quotes of the YANG string. XPath
+//! string literals inside the expression are never modified.
+
+/// Number of extra spaces continuation lines of a wrapped expression are indented by
+const HANGING_INDENT: usize = 2;
+
+/// Collapses all whitespace outside of XPath string literals into single spaces
+///
+/// Returns None if the expression contains an unterminated string literal, in which case it's
+/// not safe to touch.
+///
+pub fn normalize_whitespace(expr: &str) -> Option<String> {
+    let mut output = String::with_capacity(expr.len());
+    let mut literal: Option<char> = None;
+    let mut pending_space = false;
+
+    for c in expr.trim().chars() {
+        match literal {
+            Some(quote) => {
+                output.push(c);
+
+                if c == quote {
+                    literal = None;
+                }
+            }
+            None if c.is_whitespace() => pending_space = true,
+            None => {
+                if pending_space {
+                    output.push(' ');
+                    pending_space = false;
+                }
+
+                if c == '\'' || c == '"' {
+                    literal = Some(c);
+                }
+
+                output.push(c);
+            }
+        }
+    }
+
+    match literal {
+        Some(_) => None,
+        None => Some(output),
+    }
+}
+
+/// Returns the positions a normalized expression can be broken at
+///
+/// Lines may start right before a top-level logical operator ("and", "or" and "|"), or right
+/// after an opening parenthesis if "after_parens" is set.
+///
+fn break_positions(expr: &str, after_parens: bool) -> Vec<usize> {
+    let mut positions = vec![];
+    let mut literal: Option<char> = None;
+    let mut depth = 0;
+
+    for (i, c) in expr.char_indices() {
+        if let Some(quote) = literal {
+            if c == quote {
+                literal = None;
+            }
+
+            continue;
+        }
+
+        match c {
+            '\'' | '"' => literal = Some(c),
+            '(' => {
+                depth += 1;
+
+                if after_parens && !expr[i + 1..].starts_with(')') {
+                    positions.push(i + 1);
+                }
+            }
+            ')' => depth -= 1,
+            ' ' if depth == 0 => {
+                let rest = &expr[i + 1..];
+
+                if rest.starts_with("and ") || rest.starts_with("or ") || rest.starts_with("| ") {
+                    positions.push(i + 1);
+                }
+            }
+            _ => (),
+        }
+    }
+
+    positions
+}
+
+/// Wraps a normalized expression into lines that fit within the given width
+///
+/// Lines after the first are indented with a hanging indent. Lines that can't be broken up are
+/// allowed to overflow.
+///
+pub fn wrap_xpath(expr: &str, width: usize) -> Vec<String> {
+    wrap_at(expr, width, &break_positions(expr, true))
+}
+
+/// Like "wrap_xpath", but only breaks lines before top-level logical operators
+///
+/// This suits the simpler if-feature expressions, which read better with their parentheses
+/// kept on one line.
+///
+pub fn wrap_at_operators(expr: &str, width: usize) -> Vec<String> {
+    wrap_at(expr, width, &break_positions(expr, false))
+}
+
+fn wrap_at(expr: &str, width: usize, positions: &[usize]) -> Vec<String> {
+    let mut lines = vec![];
+    let mut start = 0;
+
+    loop {
+        let indent = if lines.is_empty() { 0 } else { HANGING_INDENT };
+        let fits = |end: usize| indent + expr[start..end].trim_end().chars().count() <= width;
+
+        if fits(expr.len()) {
+            lines.push(format!("{}{}", " ".repeat(indent), &expr[start..]));
+            break;
+        }
+
+        let candidates = positions.iter().copied().filter(|pos| *pos > start);
+
+        let end = candidates
+            .clone()
+            .take_while(|pos| fits(*pos))
+            .last()
+            .or_else(|| candidates.clone().next());
+
+        match end {
+            Some(end) => {
+                lines.push(format!(
+                    "{}{}",
+                    " ".repeat(indent),
+                    expr[start..end].trim_end()
+                ));
+                start = end;
+            }
+            None => {
+                lines.push(format!("{}{}", " ".repeat(indent), &expr[start..]));
+                break;
+            }
+        }
+    }
+
+    lines
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_normalize_whitespace() {
+        assert_eq!(
+            Some("../a = 'x  y' and\t\"b\" = ../c".replace('\t', " ")),
+            normalize_whitespace("  ../a   =  'x  y'\n    and\t\"b\"   = ../c "),
+        );
+        assert_eq!(None, normalize_whitespace("../a = 'oops"));
+    }
+
+    #[test]
+    fn test_wrap_xpath() {
+        assert_eq!(
+            vec![
+                "../type = 'ethernet' or ../type = 'loopback'",
+                "  or (../mtu > 1500 and ../enabled = 'true')",
+            ],
+            wrap_xpath(
+                "../type = 'ethernet' or ../type = 'loopback' or (../mtu > 1500 and ../enabled = 'true')",
+                45,
+            ),
+        );
+        assert_eq!(
+            vec![
+                "derived-from-or-self(",
+                "  ../if:type, 'ianaift:ethernetCsmacd')",
+            ],
+            wrap_xpath(
+                "derived-from-or-self(../if:type, 'ianaift:ethernetCsmacd')",
+                40
+            ),
+        );
+    }
+
+    #[test]
+    fn test_wrap_at_operators() {
+        assert_eq!(
+            vec!["foo-feature or bar-feature", "  or (baz-feature and qux)"],
+            wrap_at_operators("foo-feature or bar-feature or (baz-feature and qux)", 40),
+        );
+    }
+}
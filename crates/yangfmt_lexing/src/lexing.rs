@@ -134,11 +134,11 @@ impl<'a> Iterator for ScanIterator<'a> {
     }
 }
 
-pub fn scan_iter(buffer: &[u8]) -> ScanIterator {
+pub fn scan_iter(buffer: &[u8]) -> ScanIterator<'_> {
     ScanIterator { buffer, cursor: 0 }
 }
 
-pub fn scan(buffer: &[u8]) -> Result<Vec<Token>> {
+pub fn scan(buffer: &[u8]) -> Result<Vec<Token<'_>>> {
     let mut tokens = vec![];
 
     for token in scan_iter(buffer) {
@@ -158,7 +158,7 @@ pub fn scan(buffer: &[u8]) -> Result<Vec<Token>> {
 ///
 /// Returns an error on lexer errors such as unterminated strings or comments.
 ///
-fn next_token(buffer: &[u8], cursor: usize) -> Result<Option<(usize, Token)>> {
+fn next_token(buffer: &[u8], cursor: usize) -> Result<Option<(usize, Token<'_>)>> {
     let char = match buffer.get(cursor) {
         Some(char) => char,
         None => return Ok(None),
@@ -263,8 +263,8 @@ fn scan_string(buffer: &[u8], cursor: usize) -> Result<Option<usize>> {
 fn scan_comment(buffer: &[u8], cursor: usize) -> Option<usize> {
     let is_forward_slash = |c: &u8| *c == SLASH;
 
-    if !(buffer.get(cursor).map_or(false, is_forward_slash)
-        && buffer.get(cursor + 1).map_or(false, is_forward_slash))
+    if !(buffer.get(cursor).is_some_and(is_forward_slash)
+        && buffer.get(cursor + 1).is_some_and(is_forward_slash))
     {
         return None;
     }
@@ -285,8 +285,8 @@ fn scan_comment(buffer: &[u8], cursor: usize) -> Option<usize> {
 
 /// Checks if there is a block comment at the current position
 fn scan_block_comment(buffer: &[u8], cursor: usize) -> Result<Option<usize>> {
-    if !(buffer.get(cursor).map_or(false, |c| *c == SLASH)
-        && buffer.get(cursor + 1).map_or(false, |c| *c == ASTERISK))
+    if !(buffer.get(cursor).is_some_and(|c| *c == SLASH)
+        && buffer.get(cursor + 1).is_some_and(|c| *c == ASTERISK))
     {
         return Ok(None);
     }
@@ -301,8 +301,8 @@ fn scan_block_comment(buffer: &[u8], cursor: usize) -> Result<Option<usize>> {
             ));
         }
 
-        if buffer.get(i).map_or(false, |c| *c == ASTERISK)
-            && buffer.get(i + 1).map_or(false, |c| *c == SLASH)
+        if buffer.get(i).is_some_and(|c| *c == ASTERISK)
+            && buffer.get(i + 1).is_some_and(|c| *c == SLASH)
         {
             break;
         }
@@ -318,7 +318,7 @@ fn scan_whitespace(buffer: &[u8], cursor: usize) -> Option<usize> {
     for i in cursor.. {
         if buffer
             .get(i)
-            .map_or(false, |char| [SPACE, TAB].contains(char))
+            .is_some_and(|char| [SPACE, TAB].contains(char))
         {
             continue;
         } else {
@@ -333,10 +333,10 @@ fn scan_whitespace(buffer: &[u8], cursor: usize) -> Option<usize> {
 
 /// Checks if there is a line break at this position
 fn scan_line_break(buffer: &[u8], cursor: usize) -> Option<usize> {
-    if buffer.get(cursor).map_or(false, |c| *c == b'\n') {
+    if buffer.get(cursor).is_some_and(|c| *c == b'\n') {
         Some(1)
-    } else if buffer.get(cursor).map_or(false, |c| *c == b'\r') {
-        if buffer.get(cursor + 1).map_or(false, |c| *c == b'\n') {
+    } else if buffer.get(cursor).is_some_and(|c| *c == b'\r') {
+        if buffer.get(cursor + 1).is_some_and(|c| *c == b'\n') {
             Some(2)
         } else {
             Some(1)
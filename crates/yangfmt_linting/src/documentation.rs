@@ -0,0 +1,162 @@
+//! Lint rules about the description and reference statements documenting a model.
+
+use yangfmt_parsing::Diagnostic;
+
+use crate::{argument, describe, find_child, walk_statements, Document};
+
+/// Words in a description that point to what replaces a deprecated or obsolete definition
+const REPLACEMENT_WORDS: &[&str] = &[
+    "in favor of",
+    "in favour of",
+    "instead",
+    "replace",
+    "superseded",
+    "use ",
+];
+
+/// Reports definitions without a description or reference, for the statements listed in
+/// "LintConfig::description_required" and "LintConfig::reference_required"
+pub fn check_missing_documentation(document: &Document, diagnostics: &mut Vec<Diagnostic>) {
+    let config = document.config;
+
+    walk_statements(&document.tree.children, None, &mut |statement, _| {
+        let keyword = statement.keyword.text();
+
+        for (documentation, required) in [
+            ("description", &config.description_required),
+            ("reference", &config.reference_required),
+        ] {
+            if required.iter().any(|required| required == keyword)
+                && find_child(statement, documentation).is_none()
+            {
+                diagnostics.push(Diagnostic::new(
+                    format!("{} has no {documentation}", describe(statement)),
+                    statement.position,
+                ));
+            }
+        }
+    });
+}
+
+/// Reports deprecated and obsolete definitions that have no reference, and no description saying
+/// what replaces them
+pub fn check_status_explained(document: &Document, diagnostics: &mut Vec<Diagnostic>) {
+    walk_statements(&document.tree.children, None, &mut |statement, parent| {
+        let Some(parent) = parent else {
+            return;
+        };
+
+        let status = argument(statement);
+
+        if statement.keyword.text() != "status" || !matches!(status, "deprecated" | "obsolete") {
+            return;
+        }
+
+        if find_child(parent, "reference").is_some() {
+            return;
+        }
+
+        let explained = find_child(parent, "description").is_some_and(|description| {
+            let text = argument(description).to_lowercase();
+
+            REPLACEMENT_WORDS.iter().any(|word| text.contains(word))
+        });
+
+        if !explained {
+            diagnostics.push(Diagnostic::new(
+                format!(
+                    "{} is {status}, but has no reference and its description doesn't say what \
+                     replaces it",
+                    describe(parent)
+                ),
+                statement.position,
+            ));
+        }
+    });
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{run_check, LintConfig};
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_check_missing_documentation() {
+        let input = textwrap::dedent(
+            "
+            module foo {
+              description \"Foo\";
+              typedef percent {
+                type uint8;
+                description \"Percentage\";
+              }
+              container bar {
+                leaf baz {
+                  type percent;
+                }
+              }
+            }
+            ",
+        );
+        let config = LintConfig {
+            description_required: vec!["container".to_string(), "leaf".to_string()],
+            reference_required: vec!["typedef".to_string()],
+            ..LintConfig::default()
+        };
+        let diagnostics = run_check(check_missing_documentation, &input, Some(config));
+
+        assert_eq!(
+            vec![
+                Diagnostic::new(
+                    "typedef \"percent\" has no reference",
+                    input.find("typedef").unwrap(),
+                ),
+                Diagnostic::new(
+                    "container \"bar\" has no description",
+                    input.find("container").unwrap(),
+                ),
+                Diagnostic::new(
+                    "leaf \"baz\" has no description",
+                    input.find("leaf").unwrap(),
+                ),
+            ],
+            diagnostics,
+        );
+    }
+
+    #[test]
+    fn test_check_status_explained() {
+        let input = textwrap::dedent(
+            "
+            module foo {
+              leaf a {
+                status deprecated;
+                description \"Old MTU.\";
+              }
+              leaf b {
+                status obsolete;
+                description \"Use 'mtu' instead.\";
+              }
+              leaf c {
+                status deprecated;
+                reference \"RFC 9999\";
+              }
+              leaf d {
+                status current;
+              }
+            }
+            ",
+        );
+        let diagnostics = run_check(check_status_explained, &input, None);
+
+        assert_eq!(
+            vec![Diagnostic::new(
+                "leaf \"a\" is deprecated, but has no reference and its description doesn't say \
+                 what replaces it",
+                input.find("status").unwrap(),
+            )],
+            diagnostics,
+        );
+    }
+}
@@ -0,0 +1,240 @@
+//! Lint rules about statements defined or repeated more than once in the same block.
+
+use yangfmt_parsing::{Diagnostic, Node, Statement, StatementKeyword};
+
+use crate::{argument, describe, walk_statements, Document};
+
+/// Groups of statements whose names have to be unique among their siblings
+///
+/// Data nodes, RPCs and notifications share a namespace, so a leaf and a container can't have the
+/// same name either.
+///
+const NAMESPACES: &[&[&str]] = &[
+    &[
+        "action",
+        "anydata",
+        "anyxml",
+        "case",
+        "choice",
+        "container",
+        "leaf",
+        "leaf-list",
+        "list",
+        "notification",
+        "rpc",
+    ],
+    &["bit"],
+    &["enum"],
+    &["extension"],
+    &["feature"],
+    &["grouping"],
+    &["identity"],
+    &["import"],
+    &["include"],
+    &["typedef"],
+];
+
+/// Statements that can appear at most once in a block
+///
+/// "type" and "default" are handled separately, since they can be repeated in some blocks.
+///
+const SINGLE_KEYWORDS: &[&str] = &[
+    "argument",
+    "belongs-to",
+    "config",
+    "contact",
+    "description",
+    "error-app-tag",
+    "error-message",
+    "fraction-digits",
+    "input",
+    "key",
+    "length",
+    "mandatory",
+    "max-elements",
+    "min-elements",
+    "modifier",
+    "namespace",
+    "ordered-by",
+    "organization",
+    "output",
+    "path",
+    "position",
+    "prefix",
+    "presence",
+    "range",
+    "reference",
+    "require-instance",
+    "revision-date",
+    "status",
+    "units",
+    "value",
+    "when",
+    "yang-version",
+    "yin-element",
+];
+
+/// Reports statements that repeat a sibling: definitions with a name that's already taken,
+/// statements that can only appear once, and exact copies of another statement
+///
+/// Only statements with a built-in keyword are checked, in blocks of built-in statements, since
+/// the rules of extension statements aren't known.
+///
+pub fn check_duplicates(document: &Document, diagnostics: &mut Vec<Diagnostic>) {
+    check_block(None, &document.tree.children, diagnostics);
+
+    walk_statements(&document.tree.children, None, &mut |statement, _| {
+        if let (StatementKeyword::Keyword(_), Some(children)) =
+            (&statement.keyword, &statement.children)
+        {
+            check_block(Some(statement), children, diagnostics);
+        }
+    });
+}
+
+fn check_block(parent: Option<&Statement>, nodes: &[Node], diagnostics: &mut Vec<Diagnostic>) {
+    let statements: Vec<&Statement> = nodes
+        .iter()
+        .filter_map(|node| match node {
+            Node::Statement(
+                statement @ Statement {
+                    keyword: StatementKeyword::Keyword(_),
+                    ..
+                },
+            ) => Some(statement),
+            _ => None,
+        })
+        .collect();
+
+    for (index, statement) in statements.iter().enumerate() {
+        let keyword = statement.keyword.text();
+        let previous = &statements[..index];
+
+        let message = if is_single(keyword, parent)
+            && previous.iter().any(|other| other.keyword.text() == keyword)
+        {
+            let parent = parent.map_or("the top level".to_string(), describe);
+
+            format!("\"{keyword}\" statement appears more than once in {parent}")
+        } else if let Some(other) = previous.iter().find(|other| same_name(statement, other)) {
+            format!(
+                "{} has the same name as the {} above",
+                describe(statement),
+                describe(other)
+            )
+        } else if previous.iter().any(|other| other == statement) {
+            format!("{} is repeated", describe(statement))
+        } else {
+            continue;
+        };
+
+        diagnostics.push(Diagnostic::new(message, statement.position));
+    }
+}
+
+/// Returns true if the statement can only appear once in the parent's block
+fn is_single(keyword: &str, parent: Option<&Statement>) -> bool {
+    let parent_keyword = parent.map(|parent| parent.keyword.text()).unwrap_or("");
+
+    match keyword {
+        // A union has several member types
+        "type" => parent_keyword != "type",
+        // YANG 1.1 leaf-lists can have several defaults, and refine and deviate can set them
+        "default" => !matches!(parent_keyword, "leaf-list" | "refine" | "deviate"),
+        _ => SINGLE_KEYWORDS.contains(&keyword),
+    }
+}
+
+/// Returns true if the statements define something with the same name in the same namespace
+fn same_name(a: &Statement, b: &Statement) -> bool {
+    let name = argument(a);
+
+    !name.is_empty()
+        && name == argument(b)
+        && NAMESPACES.iter().any(|namespace| {
+            namespace.contains(&a.keyword.text()) && namespace.contains(&b.keyword.text())
+        })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::run_check;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_check_duplicates() {
+        let input = textwrap::dedent(
+            "
+            module foo {
+              import ietf-inet-types { prefix inet; }
+              import ietf-inet-types { prefix inet2; }
+              container mtu;
+              leaf mtu {
+                type enumeration {
+                  enum up;
+                  enum down;
+                  enum up;
+                }
+                description \"One\";
+                description \"Two\";
+              }
+              leaf-list tags {
+                type string;
+                default a;
+                default b;
+                if-feature tags;
+                if-feature tags;
+                ex:note x;
+                ex:note x;
+              }
+              leaf address {
+                type union {
+                  type string;
+                  type uint32;
+                }
+              }
+            }
+            ",
+        );
+        let diagnostics = run_check(check_duplicates, &input, None);
+
+        assert_eq!(
+            vec![
+                Diagnostic::new(
+                    "import \"ietf-inet-types\" has the same name as the import \
+                     \"ietf-inet-types\" above",
+                    input.find("import ietf-inet-types { prefix inet2").unwrap(),
+                ),
+                Diagnostic::new(
+                    "leaf \"mtu\" has the same name as the container \"mtu\" above",
+                    input.find("leaf mtu").unwrap(),
+                ),
+                Diagnostic::new(
+                    "\"description\" statement appears more than once in leaf \"mtu\"",
+                    input.find("description \"Two\"").unwrap(),
+                ),
+                Diagnostic::new(
+                    "enum \"up\" has the same name as the enum \"up\" above",
+                    input.rfind("enum up").unwrap(),
+                ),
+                Diagnostic::new(
+                    "if-feature \"tags\" is repeated",
+                    input.rfind("if-feature").unwrap(),
+                ),
+            ],
+            diagnostics,
+        );
+    }
+
+    #[test]
+    fn test_is_single() {
+        let leaf = Statement::new("leaf");
+        let union = Statement::new("type");
+
+        assert!(is_single("type", Some(&leaf)));
+        assert!(!is_single("type", Some(&union)));
+        assert!(is_single("description", None));
+        assert!(!is_single("must", Some(&leaf)));
+    }
+}
@@ -0,0 +1,178 @@
+//! Lint rules comparing the module with the name of its file.
+//!
+//! YANG files are expected to be named after the module they contain, optionally followed by the
+//! revision of the module, e.g. "ietf-interfaces@2018-02-20.yang". Tools loading modules by name
+//! rely on this, see RFC 7950 section 5.2.
+
+use yangfmt_parsing::{Diagnostic, Node};
+
+use crate::{argument, module_statement, Document};
+
+/// Returns the module name and revision in the name of a ".yang" file
+///
+/// The file name may include directories, which are ignored.
+///
+pub fn parse_file_name(file_name: &str) -> Option<(&str, Option<&str>)> {
+    let file_name = file_name.rsplit(['/', '\\']).next()?;
+    let stem = file_name.strip_suffix(".yang")?;
+
+    match stem.split_once('@') {
+        Some((name, revision)) => Some((name, Some(revision))),
+        None => Some((stem, None)),
+    }
+}
+
+/// Reports modules whose name is different from the name of their file
+pub fn check_module_file_name(document: &Document, diagnostics: &mut Vec<Diagnostic>) {
+    let Some((file_module, _)) = document.file_name.and_then(parse_file_name) else {
+        return;
+    };
+
+    let Some(module) = module_statement(&document.tree) else {
+        return;
+    };
+
+    let name = argument(module);
+
+    if name != file_module {
+        diagnostics.push(Diagnostic::new(
+            format!(
+                "The {} name \"{name}\" doesn't match the file name, which should be \
+                 \"{name}.yang\" or \"{name}@<revision>.yang\"",
+                module.keyword.text()
+            ),
+            module.value_position.unwrap_or(module.position),
+        ));
+    }
+}
+
+/// Reports files named with a revision that isn't the latest revision of the module
+pub fn check_revision_file_name(document: &Document, diagnostics: &mut Vec<Diagnostic>) {
+    let Some((_, Some(file_revision))) = document.file_name.and_then(parse_file_name) else {
+        return;
+    };
+
+    let Some(module) = module_statement(&document.tree) else {
+        return;
+    };
+
+    // Dates in the YYYY-MM-DD format sort chronologically as text
+    let latest = module
+        .children
+        .iter()
+        .flatten()
+        .filter_map(|node| match node {
+            Node::Statement(stmt) if stmt.keyword.text() == "revision" => Some(stmt),
+            _ => None,
+        })
+        .max_by_key(|revision| argument(revision));
+
+    match latest {
+        Some(revision) if argument(revision) != file_revision => {
+            diagnostics.push(Diagnostic::new(
+                format!(
+                    "The file name has revision {file_revision}, but the latest revision of the \
+                     module is {}",
+                    argument(revision)
+                ),
+                revision.value_position.unwrap_or(revision.position),
+            ));
+        }
+        None => diagnostics.push(Diagnostic::new(
+            format!(
+                "The file name has revision {file_revision}, but the module has no revision \
+                 statements"
+            ),
+            module.position,
+        )),
+        _ => (),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::LintConfig;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_parse_file_name() {
+        assert_eq!(Some(("foo", None)), parse_file_name("models/foo.yang"));
+        assert_eq!(
+            Some(("foo", Some("2024-01-01"))),
+            parse_file_name("foo@2024-01-01.yang")
+        );
+        assert_eq!(None, parse_file_name("foo.yin"));
+    }
+
+    #[test]
+    fn test_check_module_file_name() {
+        let input = "module foo-types {\n  prefix ft;\n}\n";
+        let config = LintConfig::default();
+        let mut document = Document::new(input.as_bytes(), &config).unwrap();
+
+        let mut check = |file_name| {
+            let mut diagnostics = vec![];
+
+            document.file_name = Some(file_name);
+            check_module_file_name(&document, &mut diagnostics);
+            diagnostics
+        };
+
+        assert_eq!(
+            vec![Diagnostic::new(
+                "The module name \"foo-types\" doesn't match the file name, which should be \
+                 \"foo-types.yang\" or \"foo-types@<revision>.yang\"",
+                7,
+            )],
+            check("models/foo.yang"),
+        );
+        assert_eq!(1, check("foo-types2.yang").len());
+        assert!(check("models/foo-types.yang").is_empty());
+        assert!(check("foo-types@2024-01-01.yang").is_empty());
+        assert!(check("foo.txt").is_empty());
+    }
+
+    #[test]
+    fn test_check_revision_file_name() {
+        let input = "module foo {\n  revision 2023-05-01;\n  revision 2024-01-01;\n}\n";
+        let config = LintConfig::default();
+        let mut document = Document::new(input.as_bytes(), &config).unwrap();
+
+        let mut check = |file_name| {
+            let mut diagnostics = vec![];
+
+            document.file_name = Some(file_name);
+            check_revision_file_name(&document, &mut diagnostics);
+            diagnostics
+        };
+
+        assert_eq!(
+            vec![Diagnostic::new(
+                "The file name has revision 2023-05-01, but the latest revision of the module is \
+                 2024-01-01",
+                input.find("2024-01-01").unwrap(),
+            )],
+            check("foo@2023-05-01.yang"),
+        );
+        assert!(check("foo@2024-01-01.yang").is_empty());
+        assert!(check("foo.yang").is_empty());
+
+        let input = "module foo {\n  prefix f;\n}\n";
+        let document = Document {
+            file_name: Some("foo@2024-01-01.yang"),
+            ..Document::new(input.as_bytes(), &config).unwrap()
+        };
+        let mut diagnostics = vec![];
+
+        check_revision_file_name(&document, &mut diagnostics);
+
+        assert_eq!(
+            vec![Diagnostic::new(
+                "The file name has revision 2024-01-01, but the module has no revision statements",
+                0,
+            )],
+            diagnostics,
+        );
+    }
+}
@@ -0,0 +1,526 @@
+//! Lint rules checking the document against the YANG grammar, which the parser doesn't enforce.
+
+use yangfmt_parsing::{Diagnostic, Statement, StatementKeyword, STATEMENT_KEYWORDS};
+
+use crate::{walk_statements, Document};
+
+/// Statements allowed at the top level of a document
+const TOP_LEVEL_KEYWORDS: &[&str] = &["module", "submodule"];
+
+/// Returns the statements allowed as sub-statements of the given statement, per RFC 7950
+///
+/// Extension statements are allowed everywhere and aren't listed. Statements that only take
+/// extension statements, like "description", have an empty list.
+///
+pub fn allowed_substatements(keyword: &str) -> &'static [&'static str] {
+    match keyword {
+        "module" => &[
+            "anydata",
+            "anyxml",
+            "augment",
+            "choice",
+            "contact",
+            "container",
+            "description",
+            "deviation",
+            "extension",
+            "feature",
+            "grouping",
+            "identity",
+            "import",
+            "include",
+            "leaf",
+            "leaf-list",
+            "list",
+            "namespace",
+            "notification",
+            "organization",
+            "prefix",
+            "reference",
+            "revision",
+            "rpc",
+            "typedef",
+            "uses",
+            "yang-version",
+        ],
+        "submodule" => &[
+            "anydata",
+            "anyxml",
+            "augment",
+            "belongs-to",
+            "choice",
+            "contact",
+            "container",
+            "description",
+            "deviation",
+            "extension",
+            "feature",
+            "grouping",
+            "identity",
+            "import",
+            "include",
+            "leaf",
+            "leaf-list",
+            "list",
+            "notification",
+            "organization",
+            "reference",
+            "revision",
+            "rpc",
+            "typedef",
+            "uses",
+            "yang-version",
+        ],
+        "import" => &["description", "prefix", "reference", "revision-date"],
+        "include" => &["description", "reference", "revision-date"],
+        "belongs-to" => &["prefix"],
+        "revision" | "when" => &["description", "reference"],
+        "typedef" => &[
+            "default",
+            "description",
+            "reference",
+            "status",
+            "type",
+            "units",
+        ],
+        "type" => &[
+            "base",
+            "bit",
+            "enum",
+            "fraction-digits",
+            "length",
+            "path",
+            "pattern",
+            "range",
+            "require-instance",
+            "type",
+        ],
+        "pattern" => &[
+            "description",
+            "error-app-tag",
+            "error-message",
+            "modifier",
+            "reference",
+        ],
+        "range" | "length" | "must" => {
+            &["description", "error-app-tag", "error-message", "reference"]
+        }
+        "enum" => &["description", "if-feature", "reference", "status", "value"],
+        "bit" => &[
+            "description",
+            "if-feature",
+            "position",
+            "reference",
+            "status",
+        ],
+        "container" => &[
+            "action",
+            "anydata",
+            "anyxml",
+            "choice",
+            "config",
+            "container",
+            "description",
+            "grouping",
+            "if-feature",
+            "leaf",
+            "leaf-list",
+            "list",
+            "must",
+            "notification",
+            "presence",
+            "reference",
+            "status",
+            "typedef",
+            "uses",
+            "when",
+        ],
+        "leaf" => &[
+            "config",
+            "default",
+            "description",
+            "if-feature",
+            "mandatory",
+            "must",
+            "reference",
+            "status",
+            "type",
+            "units",
+            "when",
+        ],
+        "leaf-list" => &[
+            "config",
+            "default",
+            "description",
+            "if-feature",
+            "max-elements",
+            "min-elements",
+            "must",
+            "ordered-by",
+            "reference",
+            "status",
+            "type",
+            "units",
+            "when",
+        ],
+        "list" => &[
+            "action",
+            "anydata",
+            "anyxml",
+            "choice",
+            "config",
+            "container",
+            "description",
+            "grouping",
+            "if-feature",
+            "key",
+            "leaf",
+            "leaf-list",
+            "list",
+            "max-elements",
+            "min-elements",
+            "must",
+            "notification",
+            "ordered-by",
+            "reference",
+            "status",
+            "typedef",
+            "unique",
+            "uses",
+            "when",
+        ],
+        "choice" => &[
+            "anydata",
+            "anyxml",
+            "case",
+            "choice",
+            "config",
+            "container",
+            "default",
+            "description",
+            "if-feature",
+            "leaf",
+            "leaf-list",
+            "list",
+            "mandatory",
+            "reference",
+            "status",
+            "when",
+        ],
+        "case" => &[
+            "anydata",
+            "anyxml",
+            "choice",
+            "container",
+            "description",
+            "if-feature",
+            "leaf",
+            "leaf-list",
+            "list",
+            "reference",
+            "status",
+            "uses",
+            "when",
+        ],
+        "anydata" | "anyxml" => &[
+            "config",
+            "description",
+            "if-feature",
+            "mandatory",
+            "must",
+            "reference",
+            "status",
+            "when",
+        ],
+        "grouping" => &[
+            "action",
+            "anydata",
+            "anyxml",
+            "choice",
+            "container",
+            "description",
+            "grouping",
+            "leaf",
+            "leaf-list",
+            "list",
+            "notification",
+            "reference",
+            "status",
+            "typedef",
+            "uses",
+        ],
+        "uses" => &[
+            "augment",
+            "description",
+            "if-feature",
+            "reference",
+            "refine",
+            "status",
+            "when",
+        ],
+        "refine" => &[
+            "config",
+            "default",
+            "description",
+            "if-feature",
+            "mandatory",
+            "max-elements",
+            "min-elements",
+            "must",
+            "presence",
+            "reference",
+        ],
+        "augment" => &[
+            "action",
+            "anydata",
+            "anyxml",
+            "case",
+            "choice",
+            "container",
+            "description",
+            "if-feature",
+            "leaf",
+            "leaf-list",
+            "list",
+            "notification",
+            "reference",
+            "status",
+            "uses",
+            "when",
+        ],
+        "rpc" | "action" => &[
+            "description",
+            "grouping",
+            "if-feature",
+            "input",
+            "output",
+            "reference",
+            "status",
+            "typedef",
+        ],
+        "input" | "output" => &[
+            "anydata",
+            "anyxml",
+            "choice",
+            "container",
+            "grouping",
+            "leaf",
+            "leaf-list",
+            "list",
+            "must",
+            "typedef",
+            "uses",
+        ],
+        "notification" => &[
+            "anydata",
+            "anyxml",
+            "choice",
+            "container",
+            "description",
+            "grouping",
+            "if-feature",
+            "leaf",
+            "leaf-list",
+            "list",
+            "must",
+            "reference",
+            "status",
+            "typedef",
+            "uses",
+        ],
+        "deviation" => &["description", "deviate", "reference"],
+        "deviate" => &[
+            "config",
+            "default",
+            "mandatory",
+            "max-elements",
+            "min-elements",
+            "must",
+            "type",
+            "unique",
+            "units",
+        ],
+        "extension" => &["argument", "description", "reference", "status"],
+        "argument" => &["yin-element"],
+        "identity" => &["base", "description", "if-feature", "reference", "status"],
+        "feature" => &["description", "if-feature", "reference", "status"],
+        _ => &[],
+    }
+}
+
+/// Reports built-in statements that aren't allowed where they are
+///
+/// Statements of unknown keywords, and the sub-statements of extension statements, are left alone
+/// since their grammar isn't known.
+///
+pub fn check_substatements(document: &Document, diagnostics: &mut Vec<Diagnostic>) {
+    walk_statements(&document.tree.children, None, &mut |statement, parent| {
+        let StatementKeyword::Keyword(ref keyword) = statement.keyword else {
+            return;
+        };
+
+        match parent {
+            None if !TOP_LEVEL_KEYWORDS.contains(&keyword.as_str()) => {
+                diagnostics.push(Diagnostic::new(
+                    format!(
+                        "\"{keyword}\" statement isn't allowed at the top level, expected module \
+                         or submodule"
+                    ),
+                    statement.position,
+                ));
+            }
+            Some(Statement {
+                keyword: StatementKeyword::Keyword(ref parent_keyword),
+                ..
+            }) if !allowed_substatements(parent_keyword).contains(&keyword.as_str()) => {
+                diagnostics.push(Diagnostic::new(
+                    format!("\"{keyword}\" statement isn't allowed in \"{parent_keyword}\""),
+                    statement.position,
+                ));
+            }
+            _ => (),
+        }
+    });
+}
+
+/// Reports statements whose keyword is neither a YANG keyword nor a prefixed extension keyword
+///
+/// The closest YANG keyword is suggested if the keyword looks like a typo of one.
+///
+pub fn check_keywords(document: &Document, diagnostics: &mut Vec<Diagnostic>) {
+    walk_statements(&document.tree.children, None, &mut |statement, _| {
+        let StatementKeyword::Invalid(ref keyword) = statement.keyword else {
+            return;
+        };
+
+        let message = match closest_keyword(keyword) {
+            Some(suggestion) => {
+                format!("Invalid statement keyword \"{keyword}\", did you mean \"{suggestion}\"?")
+            }
+            None => format!("Invalid statement keyword \"{keyword}\""),
+        };
+
+        diagnostics.push(Diagnostic::new(message, statement.position));
+    });
+}
+
+/// Returns the YANG keyword closest to the given word, if it's close enough to be a typo
+fn closest_keyword(word: &str) -> Option<&'static str> {
+    let max_distance = (word.chars().count() / 3).clamp(1, 3);
+
+    STATEMENT_KEYWORDS
+        .iter()
+        .map(|keyword| (edit_distance(word, keyword), *keyword))
+        .filter(|(distance, _)| *distance <= max_distance)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, keyword)| keyword)
+}
+
+/// Returns the Levenshtein distance between the words, where swapping two adjacent characters
+/// counts as one edit
+pub(crate) fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+
+    // Distances between prefixes of "a" and "b", one row per prefix length of "a"
+    let mut rows = vec![(0..=b.len()).collect::<Vec<_>>()];
+
+    for i in 1..=a.len() {
+        let mut row = vec![i; b.len() + 1];
+
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+
+            row[j] = (rows[i - 1][j] + 1)
+                .min(row[j - 1] + 1)
+                .min(rows[i - 1][j - 1] + cost);
+
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                row[j] = row[j].min(rows[i - 2][j - 2] + 1);
+            }
+        }
+
+        rows.push(row);
+    }
+
+    rows[a.len()][b.len()]
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::run_check;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_check_substatements() {
+        let input = textwrap::dedent(
+            "
+            module foo {
+              container bar {
+                type string;
+                ex:config {
+                  type string;
+                }
+                leaf baz {
+                  type string {
+                    length 1..10;
+                  }
+                  key name;
+                }
+              }
+            }
+            leaf qux;
+            ",
+        );
+        let diagnostics = run_check(check_substatements, &input, None);
+
+        assert_eq!(
+            vec![
+                Diagnostic::new(
+                    "\"type\" statement isn't allowed in \"container\"",
+                    input.find("type").unwrap(),
+                ),
+                Diagnostic::new(
+                    "\"key\" statement isn't allowed in \"leaf\"",
+                    input.find("key").unwrap(),
+                ),
+                Diagnostic::new(
+                    "\"leaf\" statement isn't allowed at the top level, expected module or \
+                     submodule",
+                    input.find("leaf qux").unwrap(),
+                ),
+            ],
+            diagnostics,
+        );
+    }
+
+    #[test]
+    fn test_check_keywords() {
+        let input = "module foo {\n  contianer bar;\n  lef baz;\n  frobnicate;\n  ex:qux;\n}\n";
+        let diagnostics = run_check(check_keywords, input, None);
+
+        assert_eq!(
+            vec![
+                Diagnostic::new(
+                    "Invalid statement keyword \"contianer\", did you mean \"container\"?",
+                    15,
+                ),
+                Diagnostic::new(
+                    "Invalid statement keyword \"lef\", did you mean \"leaf\"?",
+                    32,
+                ),
+                Diagnostic::new("Invalid statement keyword \"frobnicate\"", 43),
+            ],
+            diagnostics,
+        );
+    }
+
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(0, edit_distance("leaf", "leaf"));
+        assert_eq!(1, edit_distance("lef", "leaf"));
+        assert_eq!(1, edit_distance("contianer", "container"));
+        assert_eq!(3, edit_distance("kitten", "sitting"));
+    }
+}
@@ -0,0 +1,241 @@
+//! Lint rules for the YANG authoring guidelines of RFC 8407, in the "guidelines" rule group.
+
+use yangfmt_parsing::{Diagnostic, Node, Statement, StatementKeyword};
+
+use crate::{argument, describe, find_child, module_statement, Document};
+
+/// Statements defining data nodes
+const DATA_NODE_KEYWORDS: &[&str] = &[
+    "anydata",
+    "anyxml",
+    "choice",
+    "container",
+    "leaf",
+    "leaf-list",
+    "list",
+];
+
+/// Statements every module needs, per section 4.8 of RFC 8407
+const HEADER_KEYWORDS: &[&str] = &["organization", "contact", "revision"];
+
+/// Statements whose sub-statements aren't configuration, so "config" doesn't apply in them
+const NON_CONFIG_KEYWORDS: &[&str] = &["action", "notification", "rpc"];
+
+/// Phrases of the copyright notice RFC 8407 asks for in the module description
+const COPYRIGHT_PHRASES: &[&str] = &["Copyright (c)", "All rights reserved"];
+
+/// Reports top-level data nodes after the first one in a module
+pub fn check_top_level_nodes(document: &Document, diagnostics: &mut Vec<Diagnostic>) {
+    let Some(module) = module_statement(&document.tree) else {
+        return;
+    };
+
+    let data_nodes = module
+        .children
+        .iter()
+        .flatten()
+        .filter_map(|node| match node {
+            Node::Statement(stmt) if DATA_NODE_KEYWORDS.contains(&stmt.keyword.text()) => {
+                Some(stmt)
+            }
+            _ => None,
+        });
+
+    for stmt in data_nodes.skip(1) {
+        diagnostics.push(Diagnostic::new(
+            format!(
+                "{} is another top-level data node, modules should only have one",
+                describe(stmt)
+            ),
+            stmt.position,
+        ));
+    }
+}
+
+/// Reports "config" statements that repeat what's inherited from the parent, or contradict it
+pub fn check_config_placement(document: &Document, diagnostics: &mut Vec<Diagnostic>) {
+    check_config_in(&document.tree.children, false, diagnostics);
+}
+
+fn check_config_in(nodes: &[Node], parent_config_false: bool, diagnostics: &mut Vec<Diagnostic>) {
+    for node in nodes {
+        let Node::Statement(
+            stmt @ Statement {
+                keyword: StatementKeyword::Keyword(keyword),
+                children: Some(children),
+                ..
+            },
+        ) = node
+        else {
+            continue;
+        };
+
+        if NON_CONFIG_KEYWORDS.contains(&keyword.as_str()) {
+            continue;
+        }
+
+        let config = find_child(stmt, "config");
+
+        let message = match (config.map(argument), parent_config_false) {
+            (Some("false"), true) => {
+                Some("\"config false\" is redundant, the parent is already config false")
+            }
+            (Some("true"), true) => Some("\"config true\" isn't allowed under config false"),
+            (Some("true"), false) => Some("\"config true\" is redundant, it's the default"),
+            _ => None,
+        };
+
+        if let (Some(message), Some(config)) = (message, config) {
+            diagnostics.push(Diagnostic::new(message, config.position));
+        }
+
+        // Groupings can be used anywhere, so what they inherit isn't known
+        let config_false = keyword != "grouping"
+            && (parent_config_false || config.is_some_and(|config| argument(config) == "false"));
+
+        check_config_in(children, config_false, diagnostics);
+    }
+}
+
+/// Reports modules without an organization, a contact or a revision
+pub fn check_module_header(document: &Document, diagnostics: &mut Vec<Diagnostic>) {
+    let Some(module) = module_statement(&document.tree) else {
+        return;
+    };
+
+    for keyword in HEADER_KEYWORDS {
+        if find_child(module, keyword).is_none() {
+            diagnostics.push(Diagnostic::new(
+                format!("{} has no \"{keyword}\" statement", describe(module)),
+                module.position,
+            ));
+        }
+    }
+}
+
+/// Reports module descriptions without a copyright notice
+pub fn check_copyright_notice(document: &Document, diagnostics: &mut Vec<Diagnostic>) {
+    let Some(module) = module_statement(&document.tree) else {
+        return;
+    };
+
+    let Some(description) = find_child(module, "description") else {
+        return;
+    };
+
+    if !COPYRIGHT_PHRASES
+        .iter()
+        .all(|phrase| argument(description).contains(phrase))
+    {
+        diagnostics.push(Diagnostic::new(
+            format!(
+                "The description of {} doesn't include a copyright notice",
+                describe(module)
+            ),
+            description.position,
+        ));
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::run_check;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_check_top_level_nodes() {
+        let input = "module foo {\n  container a;\n  grouping g;\n  leaf b;\n  list c;\n}\n";
+
+        assert_eq!(
+            vec![
+                Diagnostic::new(
+                    "leaf \"b\" is another top-level data node, modules should only have one",
+                    input.find("leaf").unwrap(),
+                ),
+                Diagnostic::new(
+                    "list \"c\" is another top-level data node, modules should only have one",
+                    input.find("list").unwrap(),
+                ),
+            ],
+            run_check(check_top_level_nodes, input, None),
+        );
+    }
+
+    #[test]
+    fn test_check_config_placement() {
+        let input = textwrap::dedent(
+            "
+            module foo {
+              container state {
+                config false;
+                leaf a {
+                  config false;
+                }
+                leaf b {
+                  config true;
+                }
+              }
+              container settings {
+                config true;
+              }
+              grouping g {
+                leaf c {
+                  config false;
+                }
+              }
+            }
+            ",
+        );
+
+        assert_eq!(
+            vec![
+                Diagnostic::new(
+                    "\"config false\" is redundant, the parent is already config false",
+                    input.find("config false;\n    }").unwrap(),
+                ),
+                Diagnostic::new(
+                    "\"config true\" isn't allowed under config false",
+                    input.find("config true").unwrap(),
+                ),
+                Diagnostic::new(
+                    "\"config true\" is redundant, it's the default",
+                    input.rfind("config true").unwrap(),
+                ),
+            ],
+            run_check(check_config_placement, &input, None),
+        );
+    }
+
+    #[test]
+    fn test_check_module_header() {
+        let input = "module foo {\n  organization \"Example\";\n}\n";
+
+        assert_eq!(
+            vec![
+                Diagnostic::new("module \"foo\" has no \"contact\" statement", 0),
+                Diagnostic::new("module \"foo\" has no \"revision\" statement", 0),
+            ],
+            run_check(check_module_header, input, None),
+        );
+    }
+
+    #[test]
+    fn test_check_copyright_notice() {
+        let input = "module foo {\n  description \"Foo\";\n}\n";
+
+        assert_eq!(
+            vec![Diagnostic::new(
+                "The description of module \"foo\" doesn't include a copyright notice",
+                15,
+            )],
+            run_check(check_copyright_notice, input, None),
+        );
+
+        let input =
+            "module foo {\n  description\n    \"Copyright (c) 2024 IETF Trust and the persons \
+                     identified as authors of the code. All rights reserved.\";\n}\n";
+
+        assert!(run_check(check_copyright_notice, input, None).is_empty());
+    }
+}
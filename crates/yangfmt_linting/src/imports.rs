@@ -0,0 +1,236 @@
+//! Lint rules that look into the modules a module imports, found in the search path.
+//!
+//! Without a search path the registry is empty, and these rules don't report anything.
+
+use yangfmt_modules::{Module, Registry};
+use yangfmt_parsing::{Diagnostic, Node, Statement};
+
+use crate::{argument, find_child, module_statement, walk_statements, Document};
+
+/// Reports imports and includes of modules that aren't found in the search path
+pub fn check_unresolved_imports(document: &Document, diagnostics: &mut Vec<Diagnostic>) {
+    let registry = &document.config.modules;
+
+    if registry.is_empty() {
+        return;
+    }
+
+    for dependency in dependencies(&document.tree) {
+        let name = argument(dependency);
+        let revision = find_child(dependency, "revision-date").map(argument);
+        let kind = match dependency.keyword.text() {
+            "include" => "submodule",
+            _ => "module",
+        };
+        let capitalized_kind = match kind {
+            "submodule" => "Submodule",
+            _ => "Module",
+        };
+
+        let message = match (registry.find(name, revision), revision) {
+            (Some(path), _) if registry.load(name, revision).is_none() => format!(
+                "{capitalized_kind} \"{name}\" can't be parsed, it was found in {}",
+                path.display()
+            ),
+            (Some(_), _) => continue,
+            (None, Some(revision)) => {
+                format!("Revision {revision} of {kind} \"{name}\" isn't found in the search path")
+            }
+            (None, None) => {
+                format!("{capitalized_kind} \"{name}\" isn't found in the search path")
+            }
+        };
+
+        diagnostics.push(Diagnostic::new(message, dependency.position));
+    }
+}
+
+/// Reports "uses" of groupings that the imported module doesn't define
+pub fn check_imported_groupings(document: &Document, diagnostics: &mut Vec<Diagnostic>) {
+    let registry = &document.config.modules;
+
+    if registry.is_empty() {
+        return;
+    }
+
+    let imports: Vec<&Statement> = dependencies(&document.tree)
+        .into_iter()
+        .filter(|dependency| dependency.keyword.text() == "import")
+        .collect();
+
+    walk_statements(&document.tree.children, None, &mut |statement, _| {
+        if statement.keyword.text() != "uses" {
+            return;
+        }
+
+        let Some((prefix, grouping)) = argument(statement).split_once(':') else {
+            return;
+        };
+
+        let Some(import) = imports.iter().find(|import| {
+            find_child(import, "prefix").is_some_and(|child| argument(child) == prefix)
+        }) else {
+            return;
+        };
+
+        let revision = find_child(import, "revision-date").map(argument);
+
+        let Some(groupings) = registry
+            .load(argument(import), revision)
+            .and_then(|module| definitions(registry, module, "grouping"))
+        else {
+            return;
+        };
+
+        if !groupings.contains(&grouping) {
+            diagnostics.push(Diagnostic::new(
+                format!(
+                    "Grouping \"{grouping}\" isn't defined in module \"{}\"",
+                    argument(import)
+                ),
+                statement.value_position.unwrap_or(statement.position),
+            ));
+        }
+    });
+}
+
+/// Returns the import and include statements of the document
+fn dependencies(tree: &yangfmt_parsing::RootNode) -> Vec<&Statement> {
+    module_statement(tree)
+        .and_then(|module| module.children.as_ref())
+        .into_iter()
+        .flatten()
+        .filter_map(|node| match node {
+            Node::Statement(stmt) if matches!(stmt.keyword.text(), "import" | "include") => {
+                Some(stmt)
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// Returns the names of the top-level definitions with the keyword in the module and the
+/// submodules it includes, or None if a submodule can't be loaded
+fn definitions<'a>(
+    registry: &'a Registry,
+    module: &'a Module,
+    keyword: &str,
+) -> Option<Vec<&'a str>> {
+    let mut names = vec![];
+
+    for node in module.statement()?.children.iter().flatten() {
+        let Node::Statement(stmt) = node else {
+            continue;
+        };
+
+        match stmt.keyword.text() {
+            text if text == keyword => names.push(argument(stmt)),
+            "include" => {
+                let revision = find_child(stmt, "revision-date").map(argument);
+                let submodule = registry.load(argument(stmt), revision)?;
+
+                names.extend(definitions(registry, submodule, keyword)?);
+            }
+            _ => (),
+        }
+    }
+
+    Some(names)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{run_check, LintConfig};
+    use pretty_assertions::assert_eq;
+    use std::path::PathBuf;
+
+    /// Creates a directory in the temporary directory with the given files
+    fn directory(name: &str, files: &[(&str, &str)]) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("yangfmt-{name}-{}", std::process::id()));
+
+        let _ = std::fs::remove_dir_all(&path);
+        std::fs::create_dir_all(&path).unwrap();
+
+        for (file_name, contents) in files {
+            std::fs::write(path.join(file_name), contents).unwrap();
+        }
+
+        path
+    }
+
+    #[test]
+    fn test_imports() {
+        let path = directory(
+            "imports",
+            &[
+                (
+                    "types.yang",
+                    "module types { include types-sub; grouping address; }",
+                ),
+                ("types-sub.yang", "submodule types-sub { grouping port; }"),
+                ("broken.yang", "module broken {"),
+            ],
+        );
+        let input = textwrap::dedent(
+            "
+            module foo {
+              import types { prefix t; }
+              import broken { prefix b; }
+              import missing { prefix m; }
+              import types-old { prefix o; revision-date 2020-01-01; }
+              include foo-sub;
+              container a {
+                uses t:address;
+                uses t:port;
+                uses t:mtu;
+                uses m:anything;
+              }
+            }
+            ",
+        );
+        let config = || {
+            Some(LintConfig {
+                modules: Registry::new(std::slice::from_ref(&path)).unwrap(),
+                ..LintConfig::default()
+            })
+        };
+
+        assert_eq!(
+            vec![
+                Diagnostic::new(
+                    format!(
+                        "Module \"broken\" can't be parsed, it was found in {}",
+                        path.join("broken.yang").display()
+                    ),
+                    input.find("import broken").unwrap(),
+                ),
+                Diagnostic::new(
+                    "Module \"missing\" isn't found in the search path",
+                    input.find("import missing").unwrap(),
+                ),
+                Diagnostic::new(
+                    "Revision 2020-01-01 of module \"types-old\" isn't found in the search path",
+                    input.find("import types-old").unwrap(),
+                ),
+                Diagnostic::new(
+                    "Submodule \"foo-sub\" isn't found in the search path",
+                    input.find("include").unwrap(),
+                ),
+            ],
+            run_check(check_unresolved_imports, &input, config()),
+        );
+
+        assert_eq!(
+            vec![Diagnostic::new(
+                "Grouping \"mtu\" isn't defined in module \"types\"",
+                input.find("t:mtu").unwrap(),
+            )],
+            run_check(check_imported_groupings, &input, config()),
+        );
+
+        assert!(run_check(check_unresolved_imports, &input, None).is_empty());
+
+        std::fs::remove_dir_all(path).unwrap();
+    }
+}
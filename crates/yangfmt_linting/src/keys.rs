@@ -0,0 +1,441 @@
+//! Lint rules about the keys of lists.
+//!
+//! Every name in the "key" argument of a list has to be a leaf defined directly in the list,
+//! either in its block or in a grouping it uses. Groupings from other modules can't be looked
+//! into, so keys of lists using those are only checked if the key is found anyway.
+
+use std::collections::HashMap;
+
+use regex::Regex;
+use yangfmt_parsing::{Diagnostic, Node, NodeValue, Statement};
+
+use crate::grammar::edit_distance;
+use crate::prefixes::declared_prefixes;
+use crate::{argument, describe, find_child, walk_statements, Document};
+
+lazy_static! {
+    /// A name in the argument of a "key" statement
+    static ref KEY_NAME: Regex = Regex::new(r#"[^\s"']+"#).unwrap();
+}
+
+/// Statements whose sub-statements aren't configuration, so "config" doesn't apply in them
+const NON_CONFIG_KEYWORDS: &[&str] = &["action", "input", "notification", "output", "rpc"];
+
+/// Statements whose sub-statements are placed elsewhere, where they're used or in the node they
+/// target, so whether they're config false isn't known
+const UNKNOWN_CONFIG_KEYWORDS: &[&str] = &["augment", "deviate", "grouping", "refine"];
+
+/// What the checks of the lists need to know about the module
+struct Context<'a> {
+    /// The groupings defined anywhere in the module, by name
+    groupings: HashMap<&'a str, &'a Statement>,
+    own_prefix: Option<&'a str>,
+}
+
+/// The direct children of a list, by name
+struct Children<'a> {
+    nodes: HashMap<&'a str, &'a Statement>,
+    /// True if the list uses a grouping that isn't defined in the module
+    incomplete: bool,
+}
+
+/// Reports keys that aren't leaves of their list, are listed twice, or have another "config"
+/// than the list
+pub fn check_list_keys(document: &Document, diagnostics: &mut Vec<Diagnostic>) {
+    let mut groupings: HashMap<&str, &Statement> = HashMap::new();
+
+    walk_statements(&document.tree.children, None, &mut |statement, _| {
+        if statement.keyword.text() == "grouping" {
+            groupings.entry(argument(statement)).or_insert(statement);
+        }
+    });
+
+    let own_prefix = declared_prefixes(&document.tree)
+        .into_iter()
+        .find(|declared| declared.module.is_none())
+        .map(|declared| declared.prefix);
+
+    let context = Context {
+        groupings,
+        own_prefix,
+    };
+
+    check_keys_in(&context, &document.tree.children, Some(false), diagnostics);
+}
+
+/// Checks the lists among the nodes and their descendants
+///
+/// "parent_config_false" is whether the parent is config false, None where it isn't known, like in
+/// groupings and augments, or doesn't apply, like in RPCs.
+///
+fn check_keys_in(
+    context: &Context,
+    nodes: &[Node],
+    parent_config_false: Option<bool>,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    for node in nodes {
+        let Node::Statement(stmt) = node else {
+            continue;
+        };
+
+        let keyword = stmt.keyword.text();
+
+        let config_false = if UNKNOWN_CONFIG_KEYWORDS.contains(&keyword)
+            || NON_CONFIG_KEYWORDS.contains(&keyword)
+        {
+            None
+        } else {
+            match find_child(stmt, "config").map(argument) {
+                Some(value) => Some(value == "false"),
+                None => parent_config_false,
+            }
+        };
+
+        if keyword == "list" {
+            check_list(context, stmt, config_false, diagnostics);
+        }
+
+        if let Some(ref children) = stmt.children {
+            check_keys_in(context, children, config_false, diagnostics);
+        }
+    }
+}
+
+fn check_list(
+    context: &Context,
+    list: &Statement,
+    config_false: Option<bool>,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    let Some(key) = find_child(list, "key") else {
+        return;
+    };
+
+    let children = list_children(context, list);
+    let mut seen: Vec<&str> = vec![];
+
+    for (key_name, position) in key_names(key) {
+        // The names may have the module's own prefix, but keys can't be in another module
+        let name = match key_name.split_once(':') {
+            Some((prefix, name)) if Some(prefix) == context.own_prefix => name,
+            Some((prefix, _)) => {
+                diagnostics.push(Diagnostic::new(
+                    format!(
+                        "Key \"{key_name}\" has the prefix \"{prefix}\" of another module, keys \
+                         have to be leaves of the list"
+                    ),
+                    position,
+                ));
+                continue;
+            }
+            None => key_name,
+        };
+
+        if seen.contains(&name) {
+            diagnostics.push(Diagnostic::new(
+                format!("Key \"{name}\" is listed more than once"),
+                position,
+            ));
+            continue;
+        }
+
+        seen.push(name);
+
+        let Some(child) = children.nodes.get(name) else {
+            if children.incomplete {
+                continue;
+            }
+
+            let message = match closest_leaf(name, &children) {
+                Some(suggestion) => format!(
+                    "Key \"{name}\" isn't a leaf of {}, did you mean \"{suggestion}\"?",
+                    describe(list)
+                ),
+                None => format!("Key \"{name}\" isn't a leaf of {}", describe(list)),
+            };
+
+            diagnostics.push(Diagnostic::new(message, position));
+            continue;
+        };
+
+        if child.keyword.text() != "leaf" {
+            diagnostics.push(Diagnostic::new(
+                format!(
+                    "Key \"{name}\" is a {} of {}, keys have to be leaves",
+                    child.keyword.text(),
+                    describe(list)
+                ),
+                position,
+            ));
+            continue;
+        }
+
+        let Some(config) = find_child(child, "config") else {
+            continue;
+        };
+
+        let leaf_config_false = argument(config) == "false";
+
+        if config_false.is_some_and(|config_false| config_false != leaf_config_false) {
+            let (leaf_config, list_config) = match leaf_config_false {
+                true => ("config false", "config true"),
+                false => ("config true", "config false"),
+            };
+
+            diagnostics.push(Diagnostic::new(
+                format!(
+                    "Key leaf \"{name}\" is {leaf_config}, but {} is {list_config}",
+                    describe(list)
+                ),
+                config.position,
+            ));
+        }
+    }
+}
+
+/// Returns the names in the argument of a "key" statement, with their positions
+fn key_names(key: &Statement) -> Vec<(&str, usize)> {
+    let value_position = key.value_position.unwrap_or(key.position);
+
+    match key.value {
+        Some(NodeValue::String(ref text) | NodeValue::Other(ref text)) => KEY_NAME
+            .find_iter(text)
+            .map(|name| (name.as_str(), value_position + name.start()))
+            .collect(),
+        // Positions inside concatenated strings aren't worth the trouble
+        Some(NodeValue::StringConcatenation(ref parts)) => parts
+            .iter()
+            .flat_map(|(text, _)| KEY_NAME.find_iter(text))
+            .map(|name| (name.as_str(), value_position))
+            .collect(),
+        _ => vec![],
+    }
+}
+
+/// Indexes the data nodes defined directly in the list, including the ones of used groupings
+fn list_children<'a>(context: &Context<'a>, list: &'a Statement) -> Children<'a> {
+    let mut children = Children {
+        nodes: HashMap::new(),
+        incomplete: false,
+    };
+
+    add_children(context, list, &mut children, &mut vec![]);
+    children
+}
+
+fn add_children<'a>(
+    context: &Context<'a>,
+    statement: &'a Statement,
+    children: &mut Children<'a>,
+    used: &mut Vec<&'a str>,
+) {
+    for node in statement.children.iter().flatten() {
+        let Node::Statement(stmt) = node else {
+            continue;
+        };
+
+        match stmt.keyword.text() {
+            "anydata" | "anyxml" | "container" | "leaf" | "leaf-list" | "list" => {
+                children.nodes.entry(argument(stmt)).or_insert(stmt);
+            }
+            "uses" => {
+                let name = argument(stmt);
+                let local_name = match name.split_once(':') {
+                    Some((prefix, name)) if Some(prefix) == context.own_prefix => Some(name),
+                    Some(_) => None,
+                    None => Some(name),
+                };
+
+                match local_name.and_then(|name| context.groupings.get(name)) {
+                    // Groupings using themselves are invalid, but shouldn't hang the linter
+                    Some(grouping) if !used.contains(&argument(grouping)) => {
+                        used.push(argument(grouping));
+                        add_children(context, grouping, children, used);
+                        used.pop();
+                    }
+                    Some(_) => (),
+                    None => children.incomplete = true,
+                }
+            }
+            _ => (),
+        }
+    }
+}
+
+/// Returns the leaf of the list closest to the given name, if it's close enough to be a typo
+fn closest_leaf<'a>(name: &str, children: &Children<'a>) -> Option<&'a str> {
+    let max_distance = (name.chars().count() / 3).clamp(1, 3);
+
+    children
+        .nodes
+        .iter()
+        .filter(|(_, stmt)| stmt.keyword.text() == "leaf")
+        .map(|(leaf, _)| (edit_distance(name, leaf), *leaf))
+        .filter(|(distance, _)| *distance <= max_distance)
+        .min_by_key(|&(distance, leaf)| (distance, leaf))
+        .map(|(_, leaf)| leaf)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::run_check;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_check_list_keys() {
+        let input = textwrap::dedent(
+            r#"
+            module foo {
+              prefix f;
+              grouping endpoint {
+                leaf address {
+                  type string;
+                }
+                uses port;
+              }
+              grouping port {
+                leaf port {
+                  type uint16;
+                }
+              }
+              list server {
+                key "adress f:port name name";
+                uses f:endpoint;
+                leaf name {
+                  config false;
+                  type string;
+                }
+              }
+              container state {
+                config false;
+                list session {
+                  key "id peer";
+                  leaf id {
+                    config true;
+                    type uint32;
+                  }
+                  container peer;
+                }
+              }
+              list remote {
+                key "id";
+                uses other:identified;
+              }
+            }
+            "#,
+        );
+        let key = input.find("adress").unwrap();
+
+        assert_eq!(
+            vec![
+                Diagnostic::new(
+                    "Key \"adress\" isn't a leaf of list \"server\", did you mean \"address\"?",
+                    key,
+                ),
+                Diagnostic::new(
+                    "Key leaf \"name\" is config false, but list \"server\" is config true",
+                    input.find("config false").unwrap(),
+                ),
+                Diagnostic::new(
+                    "Key \"name\" is listed more than once",
+                    input.find("name\"").unwrap(),
+                ),
+                Diagnostic::new(
+                    "Key leaf \"id\" is config true, but list \"session\" is config false",
+                    input.find("config true").unwrap(),
+                ),
+                Diagnostic::new(
+                    "Key \"peer\" is a container of list \"session\", keys have to be leaves",
+                    input.find("peer\"").unwrap(),
+                ),
+            ],
+            run_check(check_list_keys, &input, None),
+        );
+    }
+
+    #[test]
+    fn test_check_list_keys_in_groupings() {
+        // Where the grouping is used, and so whether the list is config false, isn't known
+        let input = "grouping g {\n  list l {\n    key k;\n    leaf k {\n      config false;\n    }\n  }\n}\n";
+
+        assert_eq!(
+            Vec::<Diagnostic>::new(),
+            run_check(check_list_keys, input, None)
+        );
+        assert_eq!(
+            vec![Diagnostic::new(
+                "Key \"x\" isn't a leaf of list \"l\"",
+                "list l {\n  key x;".find('x').unwrap(),
+            )],
+            run_check(
+                check_list_keys,
+                "list l {\n  key x;\n  leaf name;\n}\n",
+                None
+            ),
+        );
+    }
+
+    #[test]
+    fn test_check_list_keys_in_augments() {
+        // The augmented node may well be config false, like the state of an interface
+        let input = textwrap::dedent(
+            "
+            module foo {
+              prefix f;
+              augment /if:interfaces-state/if:interface {
+                list session {
+                  key id;
+                  leaf id {
+                    config false;
+                    type uint32;
+                  }
+                }
+              }
+              deviation /if:interfaces/if:interface {
+                deviate add {
+                  list peer {
+                    key id;
+                    leaf id {
+                      config false;
+                    }
+                  }
+                }
+              }
+            }
+            ",
+        );
+
+        assert_eq!(
+            Vec::<Diagnostic>::new(),
+            run_check(check_list_keys, &input, None)
+        );
+    }
+
+    #[test]
+    fn test_check_list_key_prefixes() {
+        let input = textwrap::dedent(
+            "
+            module foo {
+              prefix f;
+              list server {
+                key \"f:name if:name\";
+                leaf name {
+                  type string;
+                }
+              }
+            }
+            ",
+        );
+
+        assert_eq!(
+            vec![Diagnostic::new(
+                "Key \"if:name\" has the prefix \"if\" of another module, keys have to be leaves \
+                 of the list",
+                input.find("if:name").unwrap(),
+            )],
+            run_check(check_list_keys, &input, None),
+        );
+    }
+}
@@ -0,0 +1,1147 @@
+//! Rule-based checks of YANG documents.
+//!
+//! The formatter fixes the layout of a document, but some problems it sees can't be fixed
+//! automatically without changing the meaning of the model. Lint rules report those instead. Each
+//! rule has a name, used to refer to it in the output, and reports its findings as diagnostics.
+//!
+//! Rules can be disabled or have their severity changed with a LintConfig, and findings can be
+//! suppressed for single statements with comments, see the "suppression" module.
+
+#[macro_use]
+extern crate lazy_static;
+
+mod documentation;
+mod duplicates;
+mod file_names;
+mod grammar;
+mod guidelines;
+mod imports;
+mod keys;
+mod naming;
+mod patterns;
+mod prefixes;
+mod revisions;
+mod style;
+mod submodules;
+mod suppression;
+mod typedefs;
+mod types;
+mod versions;
+
+use std::collections::HashMap;
+
+use regex::Regex;
+
+use yangfmt_lexing::{Token, TokenType};
+use yangfmt_modules::Registry;
+use yangfmt_parsing::{parse, Diagnostic, Fix, Node, NodeValue, ParseError, RootNode, Statement};
+
+pub use crate::naming::DEFAULT_NAMING_PATTERN;
+pub use crate::prefixes::{DEFAULT_MAX_PREFIX_LENGTH, DEFAULT_PREFIX_PATTERN};
+
+use crate::suppression::find_suppressions;
+
+/// How serious a finding is
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+impl std::fmt::Display for Severity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Severity::Error => write!(f, "error"),
+            Severity::Warning => write!(f, "warning"),
+        }
+    }
+}
+
+impl std::str::FromStr for Severity {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "error" => Ok(Self::Error),
+            "warning" => Ok(Self::Warning),
+            _ => Err(format!("invalid severity {s:?}, expected error or warning")),
+        }
+    }
+}
+
+/// Statements that need a description by default, see "LintConfig::description_required"
+pub const DEFAULT_DESCRIPTION_REQUIRED: &[&str] = &[
+    "action",
+    "anydata",
+    "anyxml",
+    "augment",
+    "choice",
+    "container",
+    "extension",
+    "feature",
+    "grouping",
+    "identity",
+    "leaf",
+    "leaf-list",
+    "list",
+    "module",
+    "notification",
+    "rpc",
+    "submodule",
+    "typedef",
+];
+
+/// Groups of optional rules, which can be enabled together
+///
+/// - "guidelines": The YANG authoring guidelines of RFC 8407
+///
+pub const RULE_GROUPS: &[&str] = &["guidelines"];
+
+/// Lint options
+#[derive(Debug)]
+pub struct LintConfig {
+    /// Severity of rules by name, replacing their default severity, None disables the rule
+    pub severities: HashMap<String, Option<Severity>>,
+    /// Statements reported by "missing-description" if they don't have a description
+    pub description_required: Vec<String>,
+    /// Statements reported by "missing-description" if they don't have a reference
+    pub reference_required: Vec<String>,
+    /// Pattern the names of definitions must match, for "naming-convention"
+    pub naming_pattern: Regex,
+    /// Pattern prefixes must match, for "prefix-convention"
+    pub prefix_pattern: Regex,
+    /// Maximum number of characters in a prefix, for "prefix-convention"
+    pub max_prefix_length: usize,
+    /// Pattern module namespaces must match, for "namespace-convention", None allows any namespace
+    pub namespace_pattern: Option<Regex>,
+    /// Groups of optional rules to run, see "RULE_GROUPS"
+    pub groups: Vec<String>,
+    /// Modules found in the search path, for the rules about imported modules
+    pub modules: Registry,
+}
+
+impl Default for LintConfig {
+    fn default() -> Self {
+        Self {
+            severities: HashMap::new(),
+            description_required: DEFAULT_DESCRIPTION_REQUIRED
+                .iter()
+                .map(|keyword| keyword.to_string())
+                .collect(),
+            reference_required: vec![],
+            naming_pattern: DEFAULT_NAMING_PATTERN.clone(),
+            prefix_pattern: DEFAULT_PREFIX_PATTERN.clone(),
+            max_prefix_length: DEFAULT_MAX_PREFIX_LENGTH,
+            namespace_pattern: None,
+            groups: vec![],
+            modules: Registry::default(),
+        }
+    }
+}
+
+/// A lint rule
+#[derive(Debug)]
+pub struct Rule {
+    /// Name of the rule in kebab-case, e.g. "empty-block"
+    pub name: &'static str,
+    /// One line description of what the rule checks
+    pub summary: &'static str,
+    /// Why the rule exists and how to fix what it reports, printed by "yangfmt explain"
+    pub rationale: &'static str,
+    /// Source the rule reports, and the same source fixed
+    pub example: Option<(&'static str, &'static str)>,
+    pub severity: Severity,
+    /// Runs unless it's turned off in the config, otherwise it only runs when it's given a
+    /// severity in the config or its group is enabled
+    pub enabled: bool,
+    /// Checks that the model is valid rather than its style, such rules are also run as errors by
+    /// strict mode, see "check_strict"
+    pub strict: bool,
+    /// Group of optional rules the rule belongs to, enabled together in "LintConfig::groups"
+    pub group: Option<&'static str>,
+    check: fn(&Document, &mut Vec<Diagnostic>),
+}
+
+/// Every lint rule, in the order their findings are reported for the same position
+pub const RULES: &[Rule] = &[
+    Rule {
+        name: "empty-block",
+        summary: "Statements with an empty block, which should end with a semicolon instead",
+        rationale: "An empty block means the same as no block at all, but looks like something \
+                    was meant to go in it. Usually it's a leftover from removing sub-statements.",
+        example: Some(("container system {}", "container system;")),
+        severity: Severity::Warning,
+        enabled: true,
+        strict: false,
+        group: None,
+        check: style::check_empty_blocks,
+    },
+    Rule {
+        name: "todo-comment",
+        summary: "Comments with a TODO, FIXME or XXX marker",
+        rationale: "Unfinished work is easy to forget once a model is published. Finish the work, \
+                    or track it somewhere else and remove the comment.",
+        example: Some((
+            "// TODO: Add a range\nleaf mtu {\n  type uint16;\n}",
+            "leaf mtu {\n  type uint16 {\n    range \"68..65535\";\n  }\n}",
+        )),
+        severity: Severity::Warning,
+        enabled: true,
+        strict: false,
+        group: None,
+        check: style::check_todo_comments,
+    },
+    Rule {
+        name: "unquoted-text",
+        summary: "Free-form text arguments, like descriptions, that aren't quoted",
+        rationale: "YANG allows unquoted strings, but the text of descriptions, references and \
+                    other prose only stays valid unquoted as long as it's one word without \
+                    special characters. Quoting it from the start avoids syntax errors when the \
+                    text is extended later.",
+        example: Some(("description Interfaces;", "description \"Interfaces\";")),
+        severity: Severity::Warning,
+        enabled: true,
+        strict: false,
+        group: None,
+        check: style::check_unquoted_text,
+    },
+    Rule {
+        name: "missing-description",
+        summary: "Definitions without a description, or without a reference where one is required",
+        rationale: "A definition's name rarely says enough about what it means, what its values \
+                    are and how it's used, and IETF reviews ask for a description of every \
+                    definition. Which statements need a description or a reference can be \
+                    configured.",
+        example: Some((
+            "leaf mtu {\n  type uint16;\n}",
+            "leaf mtu {\n  type uint16;\n  description\n    \"The maximum size of IP packets on the interface, in octets.\";\n}",
+        )),
+        severity: Severity::Warning,
+        enabled: true,
+        strict: false,
+        group: None,
+        check: documentation::check_missing_documentation,
+    },
+    Rule {
+        name: "unexplained-status",
+        summary: "Deprecated or obsolete definitions that don't say what replaces them",
+        rationale: "Users of a deprecated definition need to know what to move to. Explain it \
+                    in the description, e.g. \"Use 'mtu' instead\", or add a reference to the \
+                    document that deprecated it.",
+        example: Some((
+            "leaf max-frame {\n  type uint16;\n  status deprecated;\n  description \"Maximum frame size.\";\n}",
+            "leaf max-frame {\n  type uint16;\n  status deprecated;\n  description \"Maximum frame size. Use 'mtu' instead.\";\n}",
+        )),
+        severity: Severity::Warning,
+        enabled: true,
+        strict: false,
+        group: None,
+        check: documentation::check_status_explained,
+    },
+    Rule {
+        name: "naming-convention",
+        summary: "Definitions with a name that isn't lowercase words separated by hyphens",
+        rationale: "YANG names are conventionally written in lowercase with hyphens between \
+                    words, as in the IETF modules, and mixing styles makes paths hard to \
+                    remember. The pattern names must match can be configured.",
+        example: Some((
+            "leaf interfaceName {\n  type string;\n}",
+            "leaf interface-name {\n  type string;\n}",
+        )),
+        severity: Severity::Warning,
+        enabled: true,
+        strict: false,
+        group: None,
+        check: naming::check_naming,
+    },
+    Rule {
+        name: "prefix-convention",
+        summary: "Prefixes that are generic, too long, or don't follow the prefix convention",
+        rationale: "Prefixes are repeated in every reference to a module's definitions, so they \
+                    should be short but still say which module they stand for. Placeholders like \
+                    \"p1\" or \"tmp\" make references hard to follow and are likely to collide \
+                    with other modules. The pattern and maximum length can be configured.",
+        example: Some((
+            "import ietf-interfaces {\n  prefix p1;\n}",
+            "import ietf-interfaces {\n  prefix if;\n}",
+        )),
+        severity: Severity::Warning,
+        enabled: true,
+        strict: false,
+        group: None,
+        check: prefixes::check_prefix_convention,
+    },
+    Rule {
+        name: "namespace-convention",
+        summary: "Module namespaces that don't follow the configured namespace convention",
+        rationale: "The namespace identifies a module in NETCONF and RESTCONF messages, so it \
+                    can't be changed once the module is published without breaking clients. \
+                    Checking it against the organization's convention, like a common URN or URL \
+                    prefix, catches mistakes before then. Nothing is reported unless \
+                    \"namespace_pattern\" is configured.",
+        example: Some((
+            "module foo {\n  namespace \"http://example.com/foo\";\n}",
+            "module foo {\n  namespace \"urn:example:params:xml:ns:yang:foo\";\n}",
+        )),
+        severity: Severity::Warning,
+        enabled: true,
+        strict: false,
+        group: None,
+        check: naming::check_namespace_convention,
+    },
+    Rule {
+        name: "unused-import",
+        summary: "Imports whose prefix isn't used anywhere in the module",
+        rationale: "An unused import makes the module depend on another module for nothing, and \
+                    tools have to find and load it anyway. It's usually left over after the \
+                    last reference to the imported module was removed. Run \"yangfmt lint \
+                    --fix\" to remove it.",
+        example: Some((
+            "import ietf-yang-types {\n  prefix yang;\n}\nleaf name {\n  type string;\n}",
+            "leaf name {\n  type string;\n}",
+        )),
+        severity: Severity::Warning,
+        enabled: true,
+        strict: false,
+        group: None,
+        check: prefixes::check_unused_imports,
+    },
+    Rule {
+        name: "unresolved-import",
+        summary: "Imports and includes of modules that aren't in the search path",
+        rationale: "Compilers load imported modules and included submodules from their search \
+                    path, and fail if one is missing or has another revision than the one asked \
+                    for. Only checked when a search path is given with \"--path\".",
+        example: Some((
+            "import ietf-inet-types {\n  prefix inet;\n  revision-date 2010-01-01;\n}",
+            "import ietf-inet-types {\n  prefix inet;\n  revision-date 2013-07-15;\n}",
+        )),
+        severity: Severity::Warning,
+        enabled: true,
+        strict: false,
+        group: None,
+        check: imports::check_unresolved_imports,
+    },
+    Rule {
+        name: "unknown-grouping",
+        summary: "Uses of groupings that the imported module doesn't define",
+        rationale: "A \"uses\" of a grouping from another module only compiles if that module, \
+                    or one of its submodules, defines the grouping at the top level. It's \
+                    usually a typo, or a grouping that was renamed in a later revision. Only \
+                    checked when the module is found in the search path given with \"--path\".",
+        example: Some((
+            "uses if:interface-ref;",
+            "uses if:interface-refs;",
+        )),
+        severity: Severity::Warning,
+        enabled: true,
+        strict: false,
+        group: None,
+        check: imports::check_imported_groupings,
+    },
+    Rule {
+        name: "submodule-mismatch",
+        summary: "Submodules included by another module than the one they belong to",
+        rationale: "A submodule is part of the module it belongs to, it shares the module's \
+                    namespace and prefix. The module has to include it and the submodule has to \
+                    name the module in \"belongs-to\", or compilers reject both. Only checked \
+                    when the other file is found in the search path given with \"--path\".",
+        example: Some((
+            "module foo {\n  include bar-types;\n}\n\nsubmodule bar-types {\n  belongs-to bar {\n    prefix bar;\n  }\n}",
+            "module foo {\n  include foo-types;\n}\n\nsubmodule foo-types {\n  belongs-to foo {\n    prefix foo;\n  }\n}",
+        )),
+        severity: Severity::Warning,
+        enabled: true,
+        strict: false,
+        group: None,
+        check: submodules::check_submodules,
+    },
+    Rule {
+        name: "module-file-name",
+        summary: "Modules in a file that isn't named after the module",
+        rationale: "Tools like pyang and YANG library servers find modules by file name, so a \
+                    module in a file with another name is silently left out. The file should be \
+                    named after the module, optionally followed by \"@\" and the revision.",
+        example: Some((
+            "// ietf-if.yang\nmodule ietf-interfaces {",
+            "// ietf-interfaces.yang\nmodule ietf-interfaces {",
+        )),
+        severity: Severity::Warning,
+        enabled: true,
+        strict: false,
+        group: None,
+        check: file_names::check_module_file_name,
+    },
+    Rule {
+        name: "revision-file-name",
+        summary: "Files named with a revision that isn't the latest revision of the module",
+        rationale: "A revision in the file name tells tools which revision of the module the file \
+                    contains without parsing it. When a revision is added but the file isn't \
+                    renamed, YANG library packaging picks the wrong file or none at all.",
+        example: Some((
+            "// foo@2023-05-01.yang\nmodule foo {\n  revision 2024-01-01;\n  revision 2023-05-01;\n}",
+            "// foo@2024-01-01.yang\nmodule foo {\n  revision 2024-01-01;\n  revision 2023-05-01;\n}",
+        )),
+        severity: Severity::Warning,
+        enabled: true,
+        strict: false,
+        group: None,
+        check: file_names::check_revision_file_name,
+    },
+    Rule {
+        name: "future-revision",
+        summary: "Revisions dated later than today",
+        rationale: "A revision is dated when it's published. A date in the future is usually a \
+                    typo, like the wrong year, and makes the revision look newer than revisions \
+                    actually published after it.",
+        example: Some((
+            "revision 2204-01-01 {\n  description \"Initial revision.\";\n}",
+            "revision 2024-01-01 {\n  description \"Initial revision.\";\n}",
+        )),
+        severity: Severity::Warning,
+        enabled: true,
+        strict: false,
+        group: None,
+        check: revisions::check_future_revisions,
+    },
+    Rule {
+        name: "revision-order",
+        summary: "Revisions that aren't listed newest first",
+        rationale: "RFC 7950 requires revisions to be listed in reverse chronological order, and \
+                    tools take the first revision as the revision of the module. The formatter \
+                    sorts revisions with \"--sort-revisions\", this rule reports them when it \
+                    isn't used.",
+        example: Some((
+            "revision 2023-01-01;\nrevision 2024-01-01;",
+            "revision 2024-01-01;\nrevision 2023-01-01;",
+        )),
+        severity: Severity::Warning,
+        enabled: true,
+        strict: false,
+        group: None,
+        check: revisions::check_revision_order,
+    },
+    Rule {
+        name: "explicit-enum-values",
+        summary: "Enums without a value and bits without a position",
+        rationale: "Enums and bits without an explicit value are numbered by their order, so \
+                    inserting one in the middle or removing one changes the values of those \
+                    after it, which breaks protocols encoding them as numbers. Many \
+                    organizations require explicit values, this rule is off unless enabled. \
+                    Run \"yangfmt lint --fix\" to add the values they're implicitly given.",
+        example: Some((
+            "type enumeration {\n  enum up;\n  enum down;\n}",
+            "type enumeration {\n  enum up {\n    value 0;\n  }\n  enum down {\n    value 1;\n  }\n}",
+        )),
+        severity: Severity::Warning,
+        enabled: false,
+        strict: false,
+        group: None,
+        check: types::check_explicit_values,
+    },
+    Rule {
+        name: "single-top-level-node",
+        summary: "Modules with more than one top-level data node",
+        rationale: "RFC 8407 recommends a single top-level container per module, since every \
+                    top-level node has to be considered on its own by tools and access control, \
+                    and the data of a module is easier to find under one root.",
+        example: Some((
+            "leaf hostname {\n  type string;\n}\nleaf domain {\n  type string;\n}",
+            "container system {\n  leaf hostname {\n    type string;\n  }\n  leaf domain {\n    type string;\n  }\n}",
+        )),
+        severity: Severity::Warning,
+        enabled: false,
+        strict: false,
+        group: Some("guidelines"),
+        check: guidelines::check_top_level_nodes,
+    },
+    Rule {
+        name: "config-placement",
+        summary: "\"config\" statements that repeat the inherited value or contradict it",
+        rationale: "Nodes inherit \"config\" from their parent, and are config true by default. \
+                    RFC 8407 asks for \"config false\" to be set once, on the top of a subtree of \
+                    state data, and a node can't be config true under a node that's config \
+                    false.",
+        example: Some((
+            "container state {\n  config false;\n  leaf uptime {\n    config false;\n    type uint32;\n  }\n}",
+            "container state {\n  config false;\n  leaf uptime {\n    type uint32;\n  }\n}",
+        )),
+        severity: Severity::Warning,
+        enabled: false,
+        strict: false,
+        group: Some("guidelines"),
+        check: guidelines::check_config_placement,
+    },
+    Rule {
+        name: "module-header",
+        summary: "Modules without an organization, a contact or a revision",
+        rationale: "RFC 8407 requires every module to say who is responsible for it and how to \
+                    reach them, and to have a revision statement for each published version.",
+        example: Some((
+            "module foo {\n  namespace \"urn:foo\";\n  prefix foo;\n}",
+            "module foo {\n  namespace \"urn:foo\";\n  prefix foo;\n  organization \"Example\";\n  contact \"ops@example.com\";\n  revision 2024-01-01;\n}",
+        )),
+        severity: Severity::Warning,
+        enabled: false,
+        strict: false,
+        group: Some("guidelines"),
+        check: guidelines::check_module_header,
+    },
+    Rule {
+        name: "copyright-notice",
+        summary: "Module descriptions without a copyright notice",
+        rationale: "RFC 8407 asks for the copyright notice and license of the module in its \
+                    description, so it's kept along with the module when it's extracted from \
+                    the RFC or copied elsewhere.",
+        example: Some((
+            "description \"Interface management.\";",
+            "description\n  \"Interface management.\n\n   Copyright (c) 2024 IETF Trust and the persons identified as\n   authors of the code. All rights reserved.\";",
+        )),
+        severity: Severity::Warning,
+        enabled: false,
+        strict: false,
+        group: Some("guidelines"),
+        check: guidelines::check_copyright_notice,
+    },
+    Rule {
+        name: "invalid-substatement",
+        summary: "Statements that aren't allowed under their parent statement",
+        rationale:
+            "RFC 7950 lists the sub-statements each statement can have, but yangfmt formats \
+                    any nesting, so mistakes are only found when the model is compiled. Usually \
+                    the statement belongs one level further in or out.",
+        example: Some((
+            "container mtu {\n  type uint16;\n}",
+            "leaf mtu {\n  type uint16;\n}",
+        )),
+        severity: Severity::Error,
+        enabled: false,
+        strict: true,
+        group: None,
+        check: grammar::check_substatements,
+    },
+    Rule {
+        name: "invalid-keyword",
+        summary: "Statements with a keyword that isn't a YANG keyword or a prefixed extension",
+        rationale: "yangfmt formats statements with unknown keywords like any other, but they \
+                    make the model invalid. Usually it's a typo, or an extension statement \
+                    missing its prefix.",
+        example: Some(("contianer system;", "container system;")),
+        severity: Severity::Error,
+        enabled: false,
+        strict: true,
+        group: None,
+        check: grammar::check_keywords,
+    },
+    Rule {
+        name: "unknown-prefix",
+        summary: "Extension statements with a prefix the module doesn't declare",
+        rationale: "The prefix of an extension statement has to be the module's own prefix or the \
+                    prefix of one of its imports. Usually the import is missing, or the \
+                    extension was copied from a module that imports it with another prefix.",
+        example: Some((
+            "module foo {\n  prefix f;\n  nacm:default-deny-all;\n}",
+            "module foo {\n  prefix f;\n  import ietf-netconf-acm {\n    prefix nacm;\n  }\n  nacm:default-deny-all;\n}",
+        )),
+        severity: Severity::Error,
+        enabled: false,
+        strict: true,
+        group: None,
+        check: prefixes::check_extension_prefixes,
+    },
+    Rule {
+        name: "duplicate-prefix",
+        summary: "Prefixes declared for more than one module",
+        rationale: "Each prefix has to refer to one module, the module itself or one of its \
+                    imports. A prefix declared twice makes every reference with it ambiguous, \
+                    which compilers report as confusing errors far from the cause.",
+        example: Some((
+            "prefix if;\nimport ietf-interfaces {\n  prefix if;\n}",
+            "prefix my-if;\nimport ietf-interfaces {\n  prefix if;\n}",
+        )),
+        severity: Severity::Error,
+        enabled: false,
+        strict: true,
+        group: None,
+        check: prefixes::check_duplicate_prefixes,
+    },
+    Rule {
+        name: "invalid-default",
+        summary: "Defaults that aren't valid values of the type",
+        rationale: "yangfmt doesn't know the types of the values it formats, so a default that \
+                    isn't one of the enums of its type, is out of range, or isn't a valid \
+                    number or boolean is only found when the model is compiled, or worse, when \
+                    a device loads it. Only types defined in the module itself are checked.",
+        example: Some((
+            "leaf mtu {\n  type uint16 {\n    range 68..9000;\n  }\n  default 65535;\n}",
+            "leaf mtu {\n  type uint16 {\n    range 68..9000;\n  }\n  default 1500;\n}",
+        )),
+        severity: Severity::Error,
+        enabled: false,
+        strict: true,
+        group: None,
+        check: types::check_defaults,
+    },
+    Rule {
+        name: "invalid-pattern",
+        summary: "Patterns that aren't valid XML Schema regular expressions",
+        rationale: "Patterns use the regex syntax of XML Schema, not the one of Perl or \
+                    JavaScript. They're always anchored, so \"^\" and \"$\" match themselves, \
+                    and there are no lookarounds, lazy quantifiers or back-references. Patterns \
+                    that don't compile are rejected by compilers, and patterns that compile to \
+                    something else than intended reject valid values.",
+        example: Some((
+            "pattern '^[a-z]+(?:-[a-z]+)*$';",
+            "pattern '[a-z]+(-[a-z]+)*';",
+        )),
+        severity: Severity::Error,
+        enabled: false,
+        strict: true,
+        group: None,
+        check: patterns::check_patterns,
+    },
+    Rule {
+        name: "yang-version",
+        summary: "YANG 1.1 statements in modules that don't declare \"yang-version 1.1\"",
+        rationale: "Modules without a yang-version statement are YANG 1.0, where statements like \
+                    \"action\" and \"anydata\", leaf-list defaults and if-feature expressions \
+                    don't exist. Compilers reject them, so either declare \"yang-version 1.1\" \
+                    or stick to YANG 1.0.",
+        example: Some((
+            "module foo {\n  namespace \"urn:foo\";\n  prefix foo;\n  anydata config-blob;\n}",
+            "module foo {\n  yang-version 1.1;\n  namespace \"urn:foo\";\n  prefix foo;\n  anydata config-blob;\n}",
+        )),
+        severity: Severity::Error,
+        enabled: false,
+        strict: true,
+        group: None,
+        check: versions::check_yang_version,
+    },
+    Rule {
+        name: "duplicate-statement",
+        summary: "Definitions with a name that's already taken, and statements repeated in a block",
+        rationale: "Names have to be unique among siblings, e.g. two enums or a leaf and a \
+                    container with the same name, a module can only be imported once, and \
+                    statements like \"description\" and \"type\" can only appear once in a block. \
+                    Usually it's a copy-paste mistake.",
+        example: Some((
+            "leaf mtu {\n  type uint16;\n  description \"MTU\";\n  description \"The MTU\";\n}",
+            "leaf mtu {\n  type uint16;\n  description \"The MTU\";\n}",
+        )),
+        severity: Severity::Error,
+        enabled: false,
+        strict: true,
+        group: None,
+        check: duplicates::check_duplicates,
+    },
+    Rule {
+        name: "invalid-key",
+        summary: "List keys that aren't leaves of the list",
+        rationale: "Every name in the key of a list has to be a leaf defined in the list itself, \
+                    directly or in a grouping it uses, listed only once. Key leaves also can't \
+                    be config false in a list that is configuration, or the other way around. A \
+                    misspelled key is only found when the model is compiled.",
+        example: Some((
+            "list server {\n  key \"nmae\";\n  leaf name {\n    type string;\n  }\n}",
+            "list server {\n  key \"name\";\n  leaf name {\n    type string;\n  }\n}",
+        )),
+        severity: Severity::Error,
+        enabled: false,
+        strict: true,
+        group: None,
+        check: keys::check_list_keys,
+    },
+];
+
+/// The document being linted, in the forms the rules need, along with the lint options
+///
+/// The syntax tree doesn't have positions for comments and empty lines, so rules about those use
+/// the tokens instead.
+///
+pub struct Document<'a> {
+    pub tokens: Vec<Token<'a>>,
+    pub tree: RootNode,
+    /// Path of the file the document was read from, None for STDIN
+    pub file_name: Option<&'a str>,
+    pub config: &'a LintConfig,
+}
+
+impl<'a> Document<'a> {
+    pub fn new(buffer: &'a [u8], config: &'a LintConfig) -> Result<Self, ParseError> {
+        Ok(Self {
+            tokens: yangfmt_lexing::scan(buffer)?,
+            tree: parse(buffer)?,
+            file_name: None,
+            config,
+        })
+    }
+}
+
+/// A problem reported by a lint rule
+#[derive(Debug, Clone, PartialEq)]
+pub struct Finding {
+    pub rule: &'static str,
+    pub severity: Severity,
+    pub diagnostic: Diagnostic,
+}
+
+/// Returns the rule with the given name
+pub fn find_rule(name: &str) -> Option<&'static Rule> {
+    RULES.iter().find(|rule| rule.name == name)
+}
+
+/// Runs the enabled lint rules on the input and returns the findings, ordered by position
+///
+/// Findings suppressed with "yangfmt-lint: disable" comments are left out. The file name is only
+/// used by the rules comparing it with the module.
+///
+pub fn lint(
+    buffer: &[u8],
+    file_name: Option<&str>,
+    config: &LintConfig,
+) -> Result<Vec<Finding>, ParseError> {
+    let mut document = Document::new(buffer, config)?;
+
+    document.file_name = file_name;
+
+    let mut findings = vec![];
+
+    let suppressions = find_suppressions(&document.tree, buffer.len());
+
+    for rule in RULES {
+        let severity = match config.severities.get(rule.name) {
+            Some(Some(severity)) => *severity,
+            Some(None) => continue,
+            None if rule
+                .group
+                .is_some_and(|group| config.groups.iter().any(|enabled| enabled == group)) =>
+            {
+                rule.severity
+            }
+            None if !rule.enabled => continue,
+            None => rule.severity,
+        };
+
+        let mut diagnostics = vec![];
+
+        (rule.check)(&document, &mut diagnostics);
+
+        findings.extend(
+            diagnostics
+                .into_iter()
+                .map(|diagnostic| Finding {
+                    rule: rule.name,
+                    severity,
+                    diagnostic,
+                })
+                .filter(|finding| {
+                    !suppressions
+                        .iter()
+                        .any(|suppression| suppression.suppresses(finding))
+                }),
+        );
+    }
+
+    findings.sort_by_key(|finding| finding.diagnostic.position);
+
+    Ok(findings)
+}
+
+/// Applies the fixes of the findings to the input, returning the fixed input and the findings that
+/// weren't fixed
+///
+/// Fixes overlapping a fix earlier in the input are skipped, running the rules again on the fixed
+/// input reports them again.
+///
+pub fn apply_fixes(buffer: &[u8], findings: Vec<Finding>) -> (Vec<u8>, Vec<Finding>) {
+    let mut fixes: Vec<&Fix> = vec![];
+    let mut unfixed = vec![];
+
+    for finding in &findings {
+        match finding.diagnostic.fix {
+            Some(ref fix) if fixes.iter().all(|other| fix.start >= other.end) => fixes.push(fix),
+            _ => unfixed.push(finding.clone()),
+        }
+    }
+
+    let mut output = buffer.to_vec();
+
+    for fix in fixes.iter().rev() {
+        output.splice(fix.start..fix.end, fix.replacement.bytes());
+    }
+
+    (output, unfixed)
+}
+
+/// Runs only the strict rules on the input, which report invalid YANG, as errors
+pub fn check_strict(buffer: &[u8]) -> Result<Vec<Finding>, ParseError> {
+    let config = LintConfig {
+        severities: RULES
+            .iter()
+            .map(|rule| {
+                (
+                    rule.name.to_string(),
+                    rule.strict.then_some(Severity::Error),
+                )
+            })
+            .collect(),
+        ..LintConfig::default()
+    };
+
+    lint(buffer, None, &config)
+}
+
+/// Calls the function for every statement in the tree, depth first, along with its parent
+///
+/// Statements at the top level have no parent.
+///
+pub fn walk_statements<'a>(
+    nodes: &'a [Node],
+    parent: Option<&'a Statement>,
+    f: &mut impl FnMut(&'a Statement, Option<&'a Statement>),
+) {
+    for node in nodes {
+        if let Node::Statement(statement) = node {
+            f(statement, parent);
+
+            if let Some(ref children) = statement.children {
+                walk_statements(children, Some(statement), f);
+            }
+        }
+    }
+}
+
+/// Returns the module or submodule statement of the document, if it has one
+pub fn module_statement(tree: &RootNode) -> Option<&Statement> {
+    tree.children.iter().find_map(|node| match node {
+        Node::Statement(statement)
+            if matches!(statement.keyword.text(), "module" | "submodule") =>
+        {
+            Some(statement)
+        }
+        _ => None,
+    })
+}
+
+/// Returns the first sub-statement with the given keyword
+pub fn find_child<'a>(statement: &'a Statement, keyword: &str) -> Option<&'a Statement> {
+    statement
+        .children
+        .iter()
+        .flatten()
+        .find_map(|node| match node {
+            Node::Statement(child) if child.keyword.text() == keyword => Some(child),
+            _ => None,
+        })
+}
+
+/// Returns the argument of a statement without quotes, or an empty string if it has none
+pub fn argument(statement: &Statement) -> &str {
+    match statement.value {
+        Some(
+            NodeValue::Date(ref text)
+            | NodeValue::Number(ref text)
+            | NodeValue::String(ref text)
+            | NodeValue::Other(ref text),
+        ) => text.trim_matches(['"', '\'']),
+        _ => "",
+    }
+}
+
+/// Describes the statement by its keyword and argument, e.g. "leaf "mtu""
+pub fn describe(statement: &Statement) -> String {
+    match argument(statement) {
+        "" => format!("\"{}\" statement", statement.keyword.text()),
+        name => format!("{} \"{}\"", statement.keyword.text(), name),
+    }
+}
+
+/// Returns a fix adding a sub-statement, like "value 1;", first in the block of the statement
+///
+/// Statements without a block are given one. The sub-statement is indented two spaces more than
+/// the statement, formatting the result fixes the indentation if the file uses another width.
+///
+pub fn insertion_fix(document: &Document, statement: &Statement, substatement: &str) -> Fix {
+    let tokens = &document.tokens;
+    let first = tokens
+        .iter()
+        .position(|token| token.span.0 == statement.position)
+        .unwrap_or_default();
+
+    let indent = match first.checked_sub(1).map(|index| &tokens[index]) {
+        Some(token)
+            if token.token_type == TokenType::WhiteSpace
+                && (first == 1 || tokens[first - 2].token_type == TokenType::LineBreak) =>
+        {
+            token.text
+        }
+        _ => "",
+    };
+
+    let end = tokens[first..]
+        .iter()
+        .find(|token| {
+            matches!(
+                token.token_type,
+                TokenType::SemiColon | TokenType::OpenCurlyBrace
+            )
+        })
+        .unwrap_or(&tokens[first]);
+
+    if end.token_type == TokenType::OpenCurlyBrace {
+        Fix {
+            start: end.span.1 + 1,
+            end: end.span.1 + 1,
+            replacement: format!("\n{indent}  {substatement}"),
+        }
+    } else {
+        Fix {
+            start: end.span.0,
+            end: end.span.1 + 1,
+            replacement: format!(" {{\n{indent}  {substatement}\n{indent}}}"),
+        }
+    }
+}
+
+/// Returns a fix removing the statement, along with its line if nothing but comments is left on it
+pub fn removal_fix(document: &Document, statement: &Statement) -> Fix {
+    let tokens = &document.tokens;
+    let first = tokens
+        .iter()
+        .position(|token| token.span.0 == statement.position)
+        .unwrap_or_default();
+
+    let mut depth = 0;
+    let mut last = first;
+
+    for (index, token) in tokens.iter().enumerate().skip(first) {
+        last = index;
+
+        match token.token_type {
+            TokenType::OpenCurlyBrace => depth += 1,
+            TokenType::ClosingCurlyBrace if depth <= 1 => break,
+            TokenType::ClosingCurlyBrace => depth -= 1,
+            TokenType::SemiColon if depth == 0 => break,
+            _ => (),
+        }
+    }
+
+    let mut start = first;
+
+    while start > 0 && tokens[start - 1].token_type == TokenType::WhiteSpace {
+        start -= 1;
+    }
+
+    let mut end = last + 1;
+
+    while end < tokens.len()
+        && matches!(
+            tokens[end].token_type,
+            TokenType::WhiteSpace | TokenType::Comment
+        )
+    {
+        end += 1;
+    }
+
+    let starts_line = start == 0 || tokens[start - 1].token_type == TokenType::LineBreak;
+    let ends_line = end == tokens.len() || tokens[end].token_type == TokenType::LineBreak;
+
+    if starts_line && ends_line {
+        Fix {
+            start: tokens[start].span.0,
+            end: tokens[end.min(tokens.len() - 1)].span.1 + 1,
+            replacement: String::new(),
+        }
+    } else {
+        Fix {
+            start: statement.position,
+            end: tokens[last].span.1 + 1,
+            replacement: String::new(),
+        }
+    }
+}
+
+/// Runs a single check on the input and returns its diagnostics, for the tests of the rules
+///
+/// The default config is used unless one is given.
+///
+#[cfg(test)]
+pub(crate) fn run_check(
+    check: fn(&Document, &mut Vec<Diagnostic>),
+    input: &str,
+    config: Option<LintConfig>,
+) -> Vec<Diagnostic> {
+    let config = config.unwrap_or_default();
+    let document = Document::new(input.as_bytes(), &config).unwrap();
+    let mut diagnostics = vec![];
+
+    check(&document, &mut diagnostics);
+    diagnostics
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_lint() {
+        let input = "module foo {\n  // TODO: Add leafs\n  container bar {}\n}\n";
+        let config = LintConfig {
+            description_required: vec![],
+            ..LintConfig::default()
+        };
+
+        assert_eq!(
+            vec![
+                Finding {
+                    rule: "todo-comment",
+                    severity: Severity::Warning,
+                    diagnostic: Diagnostic::new("Comment has a TODO marker", 15),
+                },
+                Finding {
+                    rule: "empty-block",
+                    severity: Severity::Warning,
+                    diagnostic: Diagnostic::new(
+                        "\"container\" statement has an empty block, end it with \";\" instead",
+                        36,
+                    ),
+                },
+            ],
+            lint(input.as_bytes(), None, &config).unwrap(),
+        );
+    }
+
+    #[test]
+    fn test_lint_with_config() {
+        let input = "module foo {\n  // TODO: Add leafs\n  container bar {}\n}\n";
+        let config = LintConfig {
+            severities: HashMap::from([
+                ("todo-comment".to_string(), None),
+                ("missing-description".to_string(), None),
+                ("empty-block".to_string(), Some(Severity::Error)),
+            ]),
+            ..LintConfig::default()
+        };
+
+        assert_eq!(
+            vec![Finding {
+                rule: "empty-block",
+                severity: Severity::Error,
+                diagnostic: Diagnostic::new(
+                    "\"container\" statement has an empty block, end it with \";\" instead",
+                    36,
+                ),
+            }],
+            lint(input.as_bytes(), None, &config).unwrap(),
+        );
+    }
+
+    #[test]
+    fn test_lint_suppressed() {
+        let input = "module foo {\n  container bar { // yangfmt-lint: disable=empty-block\n  }\n  // yangfmt-lint: disable=all\n  container baz {\n    description Baz; // TODO\n  }\n}\n";
+        let config = LintConfig {
+            description_required: vec![],
+            ..LintConfig::default()
+        };
+
+        assert_eq!(
+            Vec::<Finding>::new(),
+            lint(input.as_bytes(), None, &config).unwrap(),
+        );
+    }
+
+    #[test]
+    fn test_lint_rule_groups() {
+        let input = "module foo {\n  leaf a;\n  leaf b;\n}\n";
+        let mut config = LintConfig {
+            description_required: vec![],
+            ..LintConfig::default()
+        };
+
+        assert!(lint(input.as_bytes(), None, &config).unwrap().is_empty());
+
+        config.groups = vec!["guidelines".to_string()];
+
+        let rules: Vec<_> = lint(input.as_bytes(), None, &config)
+            .unwrap()
+            .iter()
+            .map(|finding| finding.rule)
+            .collect();
+
+        assert_eq!(
+            vec![
+                "module-header",
+                "module-header",
+                "module-header",
+                "single-top-level-node"
+            ],
+            rules,
+        );
+    }
+
+    #[test]
+    fn test_apply_fixes() {
+        let input =
+            "module foo {\n  import bar { prefix b; }\n  import baz { prefix z; } leaf x;\n}\n";
+        let config = LintConfig {
+            description_required: vec![],
+            ..LintConfig::default()
+        };
+        let findings = lint(input.as_bytes(), None, &config).unwrap();
+
+        assert_eq!(2, findings.len());
+
+        let (output, unfixed) = apply_fixes(input.as_bytes(), findings);
+
+        assert_eq!(
+            "module foo {\n   leaf x;\n}\n",
+            String::from_utf8(output).unwrap(),
+        );
+        assert!(unfixed.is_empty());
+    }
+
+    #[test]
+    fn test_check_strict() {
+        let input = "module foo {\n  container bar {}\n  leaf baz {\n    key qux;\n  }\n}\n";
+
+        assert_eq!(
+            vec![Finding {
+                rule: "invalid-substatement",
+                severity: Severity::Error,
+                diagnostic: Diagnostic::new("\"key\" statement isn't allowed in \"leaf\"", 49),
+            }],
+            check_strict(input.as_bytes()).unwrap(),
+        );
+    }
+
+    #[test]
+    fn test_lint_with_file_name() {
+        let input = "module foo {\n  container bar;\n}\n";
+        let rules = |file_name| {
+            lint(input.as_bytes(), file_name, &LintConfig::default())
+                .unwrap()
+                .iter()
+                .map(|finding| finding.rule)
+                .collect::<Vec<_>>()
+        };
+
+        assert!(rules(Some("bar.yang")).contains(&"module-file-name"));
+        assert!(!rules(Some("foo.yang")).contains(&"module-file-name"));
+        assert!(!rules(None).contains(&"module-file-name"));
+    }
+
+    #[test]
+    fn every_rule_has_a_unique_name() {
+        for rule in RULES {
+            let count = RULES.iter().filter(|other| other.name == rule.name).count();
+
+            assert_eq!(1, count, "{} is defined {count} times", rule.name);
+        }
+    }
+
+    #[test]
+    fn test_lint_parse_error() {
+        assert!(lint(b"module foo {", None, &LintConfig::default()).is_err());
+    }
+}
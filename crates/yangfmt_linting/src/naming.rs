@@ -0,0 +1,192 @@
+//! Lint rules about the names of definitions and the namespace of modules.
+
+use regex::Regex;
+use yangfmt_parsing::{ArgumentKind, Diagnostic};
+
+use crate::{argument, describe, find_child, module_statement, walk_statements, Document};
+
+lazy_static! {
+    /// Lowercase words separated by hyphens, like the names in IETF modules
+    pub static ref DEFAULT_NAMING_PATTERN: Regex =
+        Regex::new(r"^[a-z][a-z0-9]*(-[a-z0-9]+)*$").unwrap();
+}
+
+/// Statements whose identifier argument refers to something defined elsewhere, rather than
+/// naming a new definition
+const REFERENCING_KEYWORDS: &[&str] = &["belongs-to", "import", "include", "prefix"];
+
+/// Reports definitions with a name that doesn't match "LintConfig::naming_pattern"
+///
+/// A new name is suggested if converting the name to lowercase with hyphens makes it match.
+///
+pub fn check_naming(document: &Document, diagnostics: &mut Vec<Diagnostic>) {
+    let pattern = &document.config.naming_pattern;
+
+    walk_statements(&document.tree.children, None, &mut |statement, _| {
+        if statement.keyword.argument_kind() != ArgumentKind::Identifier
+            || REFERENCING_KEYWORDS.contains(&statement.keyword.text())
+        {
+            return;
+        }
+
+        let name = argument(statement);
+
+        if name.is_empty() || pattern.is_match(name) {
+            return;
+        }
+
+        let suggestion = to_kebab_case(name);
+
+        let message = if pattern.is_match(&suggestion) {
+            format!(
+                "{} doesn't follow the naming convention, consider \"{suggestion}\"",
+                describe(statement)
+            )
+        } else {
+            format!(
+                "{} doesn't follow the naming convention \"{pattern}\"",
+                describe(statement)
+            )
+        };
+
+        diagnostics.push(Diagnostic::new(
+            message,
+            statement.value_position.unwrap_or(statement.position),
+        ));
+    });
+}
+
+/// Reports a module namespace that doesn't match "LintConfig::namespace_pattern", if one is set
+pub fn check_namespace_convention(document: &Document, diagnostics: &mut Vec<Diagnostic>) {
+    let Some(ref pattern) = document.config.namespace_pattern else {
+        return;
+    };
+
+    let Some(namespace) =
+        module_statement(&document.tree).and_then(|module| find_child(module, "namespace"))
+    else {
+        return;
+    };
+
+    if !pattern.is_match(argument(namespace)) {
+        diagnostics.push(Diagnostic::new(
+            format!(
+                "Namespace \"{}\" doesn't follow the namespace convention \"{pattern}\"",
+                argument(namespace)
+            ),
+            namespace.value_position.unwrap_or(namespace.position),
+        ));
+    }
+}
+
+/// Converts camelCase and snake_case names to lowercase words separated by hyphens
+fn to_kebab_case(name: &str) -> String {
+    let mut result = String::new();
+    let mut previous: Option<char> = None;
+
+    for c in name.chars() {
+        let word_start = c.is_ascii_uppercase()
+            && previous.is_some_and(|p| p.is_ascii_lowercase() || p.is_ascii_digit());
+
+        if (word_start || c == '_' || c == '-') && !result.is_empty() && !result.ends_with('-') {
+            result.push('-');
+        }
+
+        if c != '_' && c != '-' {
+            result.push(c.to_ascii_lowercase());
+        }
+
+        previous = Some(c);
+    }
+
+    result.trim_end_matches('-').to_string()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{run_check, LintConfig};
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_to_kebab_case() {
+        assert_eq!("interface-name", to_kebab_case("interfaceName"));
+        assert_eq!("max-mtu-size", to_kebab_case("max_MTU_size"));
+        assert_eq!("ipv4-address", to_kebab_case("IPv4Address"));
+        assert_eq!("a-b", to_kebab_case("a__b_"));
+    }
+
+    #[test]
+    fn test_check_naming() {
+        let input = textwrap::dedent(
+            "
+            module foo-bar {
+              prefix FB;
+              import ietf_interfaces {
+                prefix if;
+              }
+              container interfaceStats {
+                leaf rx_packets {
+                  type uint64;
+                }
+                leaf tx-packets {
+                  type uint64;
+                }
+                leaf _9 {
+                  type string;
+                }
+              }
+            }
+            ",
+        );
+        let diagnostics = run_check(check_naming, &input, None);
+
+        assert_eq!(
+            vec![
+                Diagnostic::new(
+                    "container \"interfaceStats\" doesn't follow the naming convention, consider \
+                     \"interface-stats\"",
+                    input.find("interfaceStats").unwrap(),
+                ),
+                Diagnostic::new(
+                    "leaf \"rx_packets\" doesn't follow the naming convention, consider \
+                     \"rx-packets\"",
+                    input.find("rx_packets").unwrap(),
+                ),
+                Diagnostic::new(
+                    "leaf \"_9\" doesn't follow the naming convention \
+                     \"^[a-z][a-z0-9]*(-[a-z0-9]+)*$\"",
+                    input.find("_9").unwrap(),
+                ),
+            ],
+            diagnostics,
+        );
+    }
+
+    #[test]
+    fn test_check_namespace_convention() {
+        let check = |input: &str, namespace_pattern: Option<&str>| {
+            let config = LintConfig {
+                namespace_pattern: namespace_pattern.map(|pattern| Regex::new(pattern).unwrap()),
+                ..LintConfig::default()
+            };
+
+            run_check(check_namespace_convention, input, Some(config))
+        };
+
+        let input = "module foo {\n  namespace \"http://example.com/foo\";\n}\n";
+        let pattern = "^urn:example:params:xml:ns:yang:";
+
+        assert_eq!(
+            vec![Diagnostic::new(
+                "Namespace \"http://example.com/foo\" doesn't follow the namespace convention \
+                 \"^urn:example:params:xml:ns:yang:\"",
+                input.find('"').unwrap(),
+            )],
+            check(input, Some(pattern)),
+        );
+        assert!(check(input, Some("^http://example.com/")).is_empty());
+        assert!(check(input, None).is_empty());
+        assert!(check("submodule foo-sub {\n}\n", Some(pattern)).is_empty());
+    }
+}
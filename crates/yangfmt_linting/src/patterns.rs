@@ -0,0 +1,470 @@
+//! Validation of "pattern" arguments, which are regular expressions with the syntax of W3C XML
+//! Schema (XSD) rather than the Perl-like syntax most people know.
+//!
+//! XSD regexes are always anchored, so "^" and "$" match themselves, and they don't have groups
+//! like "(?:...)", lookarounds, back-references, lazy quantifiers or word boundaries.
+
+use yangfmt_parsing::{Diagnostic, NodeValue};
+
+use crate::{walk_statements, Document};
+
+/// The Unicode general categories of "\p{...}" escapes
+const CATEGORIES: &[&str] = &[
+    "C", "Cc", "Cf", "Cn", "Co", "L", "Ll", "Lm", "Lo", "Lt", "Lu", "M", "Mc", "Me", "Mn", "N",
+    "Nd", "Nl", "No", "P", "Pc", "Pd", "Pe", "Pf", "Pi", "Po", "Ps", "S", "Sc", "Sk", "Sm", "So",
+    "Z", "Zl", "Zp", "Zs",
+];
+
+/// A syntax error, at a character index of the pattern
+type SyntaxError = (usize, String);
+
+/// Reports "pattern" arguments that aren't valid XSD regexes
+pub fn check_patterns(document: &Document, diagnostics: &mut Vec<Diagnostic>) {
+    walk_statements(&document.tree.children, None, &mut |statement, _| {
+        if statement.keyword.text() != "pattern" {
+            return;
+        }
+
+        let value_position = statement.value_position.unwrap_or(statement.position);
+
+        // Offsets can only be mapped back to the input when the pattern is a single string
+        let (chars, offsets): (Vec<char>, Vec<usize>) = match statement.value {
+            Some(
+                NodeValue::String(ref text)
+                | NodeValue::Number(ref text)
+                | NodeValue::Date(ref text)
+                | NodeValue::Other(ref text),
+            ) => string_value(text).into_iter().unzip(),
+            Some(NodeValue::StringConcatenation(ref parts)) => parts
+                .iter()
+                .flat_map(|(text, _)| string_value(text))
+                .map(|(c, _)| (c, 0))
+                .unzip(),
+            _ => return,
+        };
+
+        if let Err((index, message)) = Parser::new(&chars).parse() {
+            let offset = offsets.get(index).or(offsets.last()).copied();
+
+            diagnostics.push(Diagnostic::new(
+                format!("Invalid pattern, {message} at character {}", index + 1),
+                value_position + offset.unwrap_or_default(),
+            ));
+        }
+    });
+}
+
+/// Returns the characters of a string argument after removing the quotes and resolving escapes,
+/// each with its byte offset in the argument as written
+fn string_value(text: &str) -> Vec<(char, usize)> {
+    let mut chars = text.char_indices().peekable();
+    let mut value = vec![];
+
+    match text.chars().next() {
+        Some('\'') => {
+            return text
+                .char_indices()
+                .skip(1)
+                .take(text.chars().count().saturating_sub(2))
+                .map(|(offset, c)| (c, offset))
+                .collect();
+        }
+        Some('"') => {
+            chars.next();
+        }
+        _ => return text.char_indices().map(|(offset, c)| (c, offset)).collect(),
+    }
+
+    while let Some((offset, c)) = chars.next() {
+        match c {
+            '"' if chars.peek().is_none() => break,
+            '\\' => match chars.next_if(|(_, next)| matches!(next, 'n' | 't' | '"' | '\\')) {
+                Some((_, 'n')) => value.push(('\n', offset)),
+                Some((_, 't')) => value.push(('\t', offset)),
+                Some((_, escaped)) => value.push((escaped, offset)),
+                None => value.push(('\\', offset)),
+            },
+            c => value.push((c, offset)),
+        }
+    }
+
+    value
+}
+
+/// Recursive descent parser of the XSD regex grammar, which only checks the syntax
+struct Parser<'a> {
+    chars: &'a [char],
+    index: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn new(chars: &'a [char]) -> Self {
+        Self { chars, index: 0 }
+    }
+
+    fn parse(&mut self) -> Result<(), SyntaxError> {
+        self.branches()?;
+
+        match self.peek() {
+            Some(')') => Err(self.error("unmatched \")\"")),
+            _ => Ok(()),
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.index).copied()
+    }
+
+    fn peek_at(&self, distance: usize) -> Option<char> {
+        self.chars.get(self.index + distance).copied()
+    }
+
+    fn error(&self, message: &str) -> SyntaxError {
+        (self.index, message.to_string())
+    }
+
+    /// regExp ::= branch ( '|' branch )*
+    fn branches(&mut self) -> Result<(), SyntaxError> {
+        loop {
+            while !matches!(self.peek(), None | Some('|') | Some(')')) {
+                self.atom()?;
+                self.quantifier()?;
+            }
+
+            if self.peek() != Some('|') {
+                return Ok(());
+            }
+
+            self.index += 1;
+        }
+    }
+
+    fn atom(&mut self) -> Result<(), SyntaxError> {
+        let c = self.peek().unwrap();
+
+        match c {
+            '(' => {
+                let start = self.index;
+
+                if self.peek_at(1) == Some('?') {
+                    return Err(
+                        self.error("groups like \"(?:...)\" and lookarounds aren't supported")
+                    );
+                }
+
+                self.index += 1;
+                self.branches()?;
+
+                if self.peek() != Some(')') {
+                    return Err((start, "unclosed \"(\"".to_string()));
+                }
+            }
+            '[' => return self.char_class(),
+            '\\' => {
+                self.escape()?;
+                return Ok(());
+            }
+            '?' | '*' | '+' | '{' => {
+                return Err(self.error(&format!("nothing to repeat before \"{c}\"")));
+            }
+            ']' | '}' => return Err(self.error(&format!("unescaped \"{c}\""))),
+            '^' if self.index == 0 => {
+                return Err(
+                    self.error("\"^\" matches a literal \"^\", since patterns are always anchored")
+                );
+            }
+            '$' if self.index == self.chars.len() - 1 => {
+                return Err(
+                    self.error("\"$\" matches a literal \"$\", since patterns are always anchored")
+                );
+            }
+            _ => (),
+        }
+
+        self.index += 1;
+
+        Ok(())
+    }
+
+    /// quantifier ::= [?*+] | '{' quantity '}'
+    fn quantifier(&mut self) -> Result<(), SyntaxError> {
+        match self.peek() {
+            Some('?' | '*' | '+') => self.index += 1,
+            Some('{') => {
+                let start = self.index;
+
+                self.index += 1;
+
+                let min = self.number();
+                let max = match self.peek() {
+                    Some(',') => {
+                        self.index += 1;
+                        self.number()
+                    }
+                    _ => min,
+                };
+
+                if min.is_none() || self.peek() != Some('}') {
+                    return Err((start, "invalid quantifier".to_string()));
+                }
+
+                if let (Some(min), Some(max)) = (min, max) {
+                    if min > max {
+                        return Err((
+                            start,
+                            "quantifier minimum is larger than its maximum".into(),
+                        ));
+                    }
+                }
+
+                self.index += 1;
+            }
+            _ => return Ok(()),
+        }
+
+        match self.peek() {
+            Some(c @ ('?' | '*' | '+' | '{')) => Err(self.error(&format!(
+                "\"{c}\" after a quantifier, lazy and possessive quantifiers aren't supported"
+            ))),
+            _ => Ok(()),
+        }
+    }
+
+    fn number(&mut self) -> Option<u32> {
+        let start = self.index;
+
+        while self.peek().is_some_and(|c| c.is_ascii_digit()) {
+            self.index += 1;
+        }
+
+        self.chars[start..self.index]
+            .iter()
+            .collect::<String>()
+            .parse()
+            .ok()
+    }
+
+    /// Parses an escape, returns the escaped character for escapes of single characters
+    fn escape(&mut self) -> Result<Option<char>, SyntaxError> {
+        let start = self.index;
+
+        self.index += 1;
+
+        let Some(c) = self.peek() else {
+            return Err((start, "unfinished escape at the end".to_string()));
+        };
+
+        self.index += 1;
+
+        match c {
+            'n' => Ok(Some('\n')),
+            'r' => Ok(Some('\r')),
+            't' => Ok(Some('\t')),
+            '\\' | '|' | '.' | '?' | '*' | '+' | '(' | ')' | '{' | '}' | '-' | '[' | ']' | '^' => {
+                Ok(Some(c))
+            }
+            's' | 'S' | 'i' | 'I' | 'c' | 'C' | 'd' | 'D' | 'w' | 'W' => Ok(None),
+            'p' | 'P' => {
+                if self.peek() != Some('{') {
+                    return Err((start, format!("\"\\{c}\" must be followed by \"{{\"")));
+                }
+
+                let name_start = self.index + 1;
+                let Some(length) = self.chars[name_start..].iter().position(|c| *c == '}') else {
+                    return Err((start, format!("unclosed \"\\{c}{{\"")));
+                };
+
+                let name: String = self.chars[name_start..name_start + length].iter().collect();
+
+                self.index = name_start + length + 1;
+
+                let is_block = name.strip_prefix("Is").is_some_and(|block| {
+                    !block.is_empty()
+                        && block.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+                });
+
+                if CATEGORIES.contains(&name.as_str()) || is_block {
+                    Ok(None)
+                } else {
+                    Err((start, format!("unknown character property \"{name}\"")))
+                }
+            }
+            '0'..='9' => Err((start, "back-references aren't supported".to_string())),
+            'b' | 'B' => Err((start, "word boundaries aren't supported".to_string())),
+            _ => Err((start, format!("unknown escape \"\\{c}\""))),
+        }
+    }
+
+    /// charClassExpr ::= '[' '^'? (charRange | charClassEsc)+ ('-' charClassExpr)? ']'
+    fn char_class(&mut self) -> Result<(), SyntaxError> {
+        let start = self.index;
+        let mut count = 0;
+
+        self.index += 1;
+
+        if self.peek() == Some('^') {
+            self.index += 1;
+        }
+
+        loop {
+            let Some(c) = self.peek() else {
+                return Err((start, "unclosed \"[\"".to_string()));
+            };
+
+            let first = match c {
+                ']' if count == 0 => return Err(self.error("empty character class")),
+                ']' => {
+                    self.index += 1;
+                    return Ok(());
+                }
+                '-' if count > 0 && self.peek_at(1) == Some('[') => {
+                    self.index += 1;
+                    self.char_class()?;
+
+                    if self.peek() != Some(']') {
+                        return Err(self.error("expected \"]\" after a subtracted character class"));
+                    }
+
+                    self.index += 1;
+                    return Ok(());
+                }
+                '-' if count > 0 && self.peek_at(1) != Some(']') => {
+                    return Err(
+                        self.error("\"-\" must be escaped, or first or last in a character class")
+                    );
+                }
+                '[' => return Err(self.error("\"[\" must be escaped in a character class")),
+                '\\' => self.escape()?,
+                c => {
+                    self.index += 1;
+                    Some(c)
+                }
+            };
+
+            count += 1;
+
+            // A range like "a-z", but not a "-" at the end or before a subtracted class
+            if self.peek() != Some('-') || matches!(self.peek_at(1), Some(']' | '[') | None) {
+                continue;
+            }
+
+            let range_start = self.index - 1;
+
+            self.index += 1;
+
+            let last = match self.peek() {
+                Some('\\') => self.escape()?,
+                Some(c) => {
+                    self.index += 1;
+                    Some(c)
+                }
+                None => None,
+            };
+
+            match (first, last) {
+                (Some(first), Some(last)) if first <= last => (),
+                (Some(_), Some(_)) => return Err((range_start, "range out of order".to_string())),
+                _ => return Err((range_start, "invalid range".to_string())),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::run_check;
+    use pretty_assertions::assert_eq;
+
+    fn parse(pattern: &str) -> Result<(), SyntaxError> {
+        let chars: Vec<char> = pattern.chars().collect();
+
+        Parser::new(&chars).parse()
+    }
+
+    #[test]
+    fn test_valid_patterns() {
+        for pattern in [
+            "",
+            "[a-zA-Z_][a-zA-Z0-9\\-_.]*",
+            "(([0-1]?[0-9]?[0-9]|2[0-4][0-9]|25[0-5])\\.){3}",
+            "\\p{L}+\\p{IsBasicLatin}*\\P{Nd}",
+            "[-a-z]+[a-z-]?[^\\s]{1,}",
+            "[a-z-[aeiou]]",
+            "x{0,3}a^b$c",
+            "\\d+(\\.\\d+)?|[$]",
+        ] {
+            assert_eq!(Ok(()), parse(pattern), "{pattern}");
+        }
+    }
+
+    #[test]
+    fn test_invalid_patterns() {
+        let error = |index: usize, message: &str| Err((index, message.to_string()));
+
+        assert_eq!(error(0, "unclosed \"(\""), parse("(ab"));
+        assert_eq!(error(2, "unmatched \")\""), parse("ab)"));
+        assert_eq!(error(1, "unclosed \"[\""), parse("a[bc"));
+        assert_eq!(error(0, "nothing to repeat before \"*\""), parse("*a"));
+        assert_eq!(
+            error(
+                2,
+                "\"?\" after a quantifier, lazy and possessive quantifiers aren't supported"
+            ),
+            parse("a+?"),
+        );
+        assert_eq!(
+            error(
+                0,
+                "groups like \"(?:...)\" and lookarounds aren't supported"
+            ),
+            parse("(?=a)"),
+        );
+        assert_eq!(error(1, "back-references aren't supported"), parse("a\\1"));
+        assert_eq!(
+            error(0, "unknown character property \"Foo\""),
+            parse("\\p{Foo}")
+        );
+        assert_eq!(error(1, "range out of order"), parse("[z-a]"));
+        assert_eq!(error(1, "invalid quantifier"), parse("a{x}"));
+        assert_eq!(
+            error(
+                0,
+                "\"^\" matches a literal \"^\", since patterns are always anchored"
+            ),
+            parse("^abc"),
+        );
+        assert_eq!(
+            error(
+                3,
+                "\"$\" matches a literal \"$\", since patterns are always anchored"
+            ),
+            parse("abc$"),
+        );
+    }
+
+    #[test]
+    fn test_string_value() {
+        assert_eq!(vec![('a', 1), ('\\', 2), ('d', 3)], string_value("'a\\d'"));
+        assert_eq!(
+            vec![('a', 1), ('\\', 2), ('d', 4), ('"', 5)],
+            string_value("\"a\\\\d\\\"\"")
+        );
+        assert_eq!(vec![('a', 0), ('b', 1)], string_value("ab"));
+    }
+
+    #[test]
+    fn test_check_patterns() {
+        let input =
+            "leaf a {\n  type string {\n    pattern '[a-z]+(';\n    pattern \"\\\\d+\";\n  }\n}\n";
+        let diagnostics = run_check(check_patterns, input, None);
+
+        assert_eq!(
+            vec![Diagnostic::new(
+                "Invalid pattern, unclosed \"(\" at character 7",
+                input.find('(').unwrap(),
+            )],
+            diagnostics,
+        );
+    }
+}
@@ -0,0 +1,428 @@
+//! Lint rules about the prefixes a module declares for itself and its imports.
+
+use std::collections::HashSet;
+
+use regex::Regex;
+use yangfmt_parsing::{Diagnostic, Node, NodeValue, RootNode, Statement, StatementKeyword};
+
+use crate::style::PROSE_KEYWORDS;
+use crate::{argument, find_child, module_statement, removal_fix, walk_statements, Document};
+
+lazy_static! {
+    /// An identifier followed by a colon, the prefix of a qualified name like "inet:ipv4-address"
+    static ref PREFIX_USE: Regex = Regex::new(r"(?:^|[^\w.-])([A-Za-z_][\w.-]*):").unwrap();
+
+    /// Lowercase letters, digits and hyphens, like the prefixes of IETF modules
+    pub static ref DEFAULT_PREFIX_PATTERN: Regex = Regex::new(r"^[a-z][a-z0-9-]*$").unwrap();
+
+    /// Placeholder prefixes that say nothing about the module, like "p1" or "tmp"
+    static ref GENERIC_PREFIX: Regex =
+        Regex::new(r"^(p|pfx|prefix|tmp|temp|test|x)[0-9]*$").unwrap();
+}
+
+/// Default of "LintConfig::max_prefix_length"
+pub const DEFAULT_MAX_PREFIX_LENGTH: usize = 10;
+
+/// Statements whose argument can't refer to anything by prefix
+const UNPREFIXED_KEYWORDS: &[&str] = &["import", "namespace", "pattern", "prefix"];
+
+/// A prefix declared in the header of a module
+#[derive(Debug, PartialEq)]
+pub struct Prefix<'a> {
+    pub prefix: &'a str,
+    /// The imported module, None for the prefix of the module itself
+    pub module: Option<&'a str>,
+    /// The "prefix" statement declaring the prefix
+    pub statement: &'a Statement,
+}
+
+/// Returns the prefixes declared by the module and its imports
+///
+/// The own prefix of a submodule is the prefix of the module it belongs to.
+///
+pub fn declared_prefixes(tree: &RootNode) -> Vec<Prefix<'_>> {
+    let Some(module) = module_statement(tree) else {
+        return vec![];
+    };
+
+    let own_prefix = match module.keyword.text() {
+        "submodule" => find_child(module, "belongs-to").and_then(|stmt| find_child(stmt, "prefix")),
+        _ => find_child(module, "prefix"),
+    };
+
+    let imports = module
+        .children
+        .iter()
+        .flatten()
+        .filter_map(|node| match node {
+            Node::Statement(stmt) if stmt.keyword.text() == "import" => {
+                let prefix = find_child(stmt, "prefix")?;
+
+                Some(Prefix {
+                    prefix: argument(prefix),
+                    module: Some(argument(stmt)),
+                    statement: prefix,
+                })
+            }
+            _ => None,
+        });
+
+    own_prefix
+        .map(|statement| Prefix {
+            prefix: argument(statement),
+            module: None,
+            statement,
+        })
+        .into_iter()
+        .chain(imports)
+        .collect()
+}
+
+/// Reports extension statements with a prefix that isn't declared by the module
+pub fn check_extension_prefixes(document: &Document, diagnostics: &mut Vec<Diagnostic>) {
+    if module_statement(&document.tree).is_none() {
+        return;
+    }
+
+    let prefixes = declared_prefixes(&document.tree);
+
+    walk_statements(&document.tree.children, None, &mut |statement, _| {
+        let StatementKeyword::ExtensionKeyword(ref keyword) = statement.keyword else {
+            return;
+        };
+
+        let Some((prefix, _)) = keyword.split_once(':') else {
+            return;
+        };
+
+        if !prefixes.iter().any(|declared| declared.prefix == prefix) {
+            diagnostics.push(Diagnostic::new(
+                format!(
+                    "The prefix of \"{keyword}\" isn't declared, \"{prefix}\" is neither the \
+                     module's own prefix nor the prefix of an import"
+                ),
+                statement.position,
+            ));
+        }
+    });
+}
+
+/// Reports prefixes declared for more than one module, or for both the module and an import
+pub fn check_duplicate_prefixes(document: &Document, diagnostics: &mut Vec<Diagnostic>) {
+    let prefixes = declared_prefixes(&document.tree);
+
+    for (index, declared) in prefixes.iter().enumerate() {
+        let Some(first) = prefixes[..index]
+            .iter()
+            .find(|other| other.prefix == declared.prefix)
+        else {
+            continue;
+        };
+
+        let owner = match first.module {
+            Some(module) => format!("module \"{module}\""),
+            None => "the module itself".to_string(),
+        };
+
+        diagnostics.push(Diagnostic::new(
+            format!(
+                "Prefix \"{}\" is already declared for {owner}",
+                declared.prefix
+            ),
+            declared
+                .statement
+                .value_position
+                .unwrap_or(declared.statement.position),
+        ));
+    }
+}
+
+/// Reports prefixes that are generic placeholders, too long, or don't match
+/// "LintConfig::prefix_pattern"
+pub fn check_prefix_convention(document: &Document, diagnostics: &mut Vec<Diagnostic>) {
+    let config = document.config;
+
+    for declared in declared_prefixes(&document.tree) {
+        let prefix = declared.prefix;
+
+        let message = if GENERIC_PREFIX.is_match(prefix) {
+            format!("Prefix \"{prefix}\" is too generic, use an abbreviation of the module name")
+        } else if prefix.chars().count() > config.max_prefix_length {
+            format!(
+                "Prefix \"{prefix}\" is longer than {} characters",
+                config.max_prefix_length
+            )
+        } else if !config.prefix_pattern.is_match(prefix) {
+            format!(
+                "Prefix \"{prefix}\" doesn't follow the prefix convention \"{}\"",
+                config.prefix_pattern
+            )
+        } else {
+            continue;
+        };
+
+        diagnostics.push(Diagnostic::new(
+            message,
+            declared
+                .statement
+                .value_position
+                .unwrap_or(declared.statement.position),
+        ));
+    }
+}
+
+/// Reports imports whose prefix isn't used anywhere in the module
+///
+/// A prefix is used by extension statements, and by arguments referring to definitions in the
+/// imported module, like types, groupings, identities, features and XPath expressions. The
+/// findings can be fixed by removing the import.
+///
+pub fn check_unused_imports(document: &Document, diagnostics: &mut Vec<Diagnostic>) {
+    let used = used_prefixes(document);
+
+    for declared in declared_prefixes(&document.tree) {
+        if declared.module.is_none() || used.contains(declared.prefix) {
+            continue;
+        }
+
+        let Some(import) = find_import(&document.tree, declared.statement) else {
+            continue;
+        };
+
+        diagnostics.push(
+            Diagnostic::new(
+                format!(
+                    "Module \"{}\" is imported, but its prefix \"{}\" is never used",
+                    argument(import),
+                    declared.prefix
+                ),
+                import.position,
+            )
+            .with_fix(removal_fix(document, import)),
+        );
+    }
+}
+
+/// Returns every prefix used by an extension statement or in an argument
+fn used_prefixes<'a>(document: &'a Document) -> HashSet<&'a str> {
+    let mut used = HashSet::new();
+
+    walk_statements(&document.tree.children, None, &mut |statement, _| {
+        if let StatementKeyword::ExtensionKeyword(ref keyword) = statement.keyword {
+            if let Some((prefix, _)) = keyword.split_once(':') {
+                used.insert(prefix);
+            }
+        }
+
+        let keyword = statement.keyword.text();
+
+        if PROSE_KEYWORDS.contains(&keyword) || UNPREFIXED_KEYWORDS.contains(&keyword) {
+            return;
+        }
+
+        let texts: Vec<&str> = match statement.value {
+            Some(NodeValue::StringConcatenation(ref parts)) => {
+                parts.iter().map(|(text, _)| text.as_str()).collect()
+            }
+            Some(_) => vec![argument(statement)],
+            None => vec![],
+        };
+
+        for text in texts {
+            used.extend(
+                PREFIX_USE
+                    .captures_iter(text)
+                    .filter_map(|captures| captures.get(1))
+                    .map(|prefix| prefix.as_str()),
+            );
+        }
+    });
+
+    used
+}
+
+/// Returns the import statement the "prefix" statement belongs to
+fn find_import<'a>(tree: &'a RootNode, prefix: &Statement) -> Option<&'a Statement> {
+    module_statement(tree)?
+        .children
+        .iter()
+        .flatten()
+        .find_map(|node| match node {
+            Node::Statement(import)
+                if import.keyword.text() == "import"
+                    && find_child(import, "prefix")
+                        .is_some_and(|child| std::ptr::eq(child, prefix)) =>
+            {
+                Some(import)
+            }
+            _ => None,
+        })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::run_check;
+    use pretty_assertions::assert_eq;
+    use yangfmt_parsing::{parse, Fix};
+
+    #[test]
+    fn test_declared_prefixes() {
+        let input = textwrap::dedent(
+            "
+            submodule foo-types {
+              belongs-to foo {
+                prefix \"f\";
+              }
+              import ietf-inet-types {
+                prefix inet;
+              }
+              import ietf-yang-types;
+            }
+            ",
+        );
+        let tree = parse(input.as_bytes()).unwrap();
+        let prefixes: Vec<_> = declared_prefixes(&tree)
+            .iter()
+            .map(|prefix| (prefix.prefix, prefix.module))
+            .collect();
+
+        assert_eq!(
+            vec![("f", None), ("inet", Some("ietf-inet-types"))],
+            prefixes
+        );
+    }
+
+    #[test]
+    fn test_check_extension_prefixes() {
+        let input = textwrap::dedent(
+            "
+            module foo {
+              prefix f;
+              import tailf-common {
+                prefix tailf;
+              }
+              f:note \"Own extension\";
+              container bar {
+                tailf:info \"Bar\";
+                nacm:default-deny-all;
+              }
+            }
+            ",
+        );
+        let diagnostics = run_check(check_extension_prefixes, &input, None);
+
+        assert_eq!(
+            vec![Diagnostic::new(
+                "The prefix of \"nacm:default-deny-all\" isn't declared, \"nacm\" is neither the \
+                 module's own prefix nor the prefix of an import",
+                input.find("nacm").unwrap(),
+            )],
+            diagnostics,
+        );
+    }
+
+    #[test]
+    fn test_check_unused_imports() {
+        let input = textwrap::dedent(
+            "
+            module foo {
+              prefix f;
+              import ietf-inet-types {
+                prefix inet;
+              }
+              import ietf-yang-types { prefix yang; }
+              import tailf-common { prefix tailf; }
+              import ietf-interfaces { prefix if; }
+              import iana-if-type { prefix ianaift; }
+              description \"Uses yang:counter64 in prose only\";
+              leaf address {
+                type inet:ip-address;
+                tailf:info \"Address\";
+                when \"derived-from(../type, \"
+                   + \"'ianaift:ethernetCsmacd')\";
+              }
+              augment \"/if:interfaces/if:interface\";
+            }
+            ",
+        );
+        let diagnostics = run_check(check_unused_imports, &input, None);
+
+        let start = input.find("  import ietf-yang-types").unwrap();
+
+        assert_eq!(
+            vec![Diagnostic::new(
+                "Module \"ietf-yang-types\" is imported, but its prefix \"yang\" is never used",
+                start + 2,
+            )
+            .with_fix(Fix {
+                start,
+                end: input.find("  import tailf-common").unwrap(),
+                replacement: String::new(),
+            })],
+            diagnostics,
+        );
+    }
+
+    #[test]
+    fn test_check_duplicate_prefixes() {
+        let input = textwrap::dedent(
+            "
+            module foo {
+              prefix if;
+              import ietf-interfaces { prefix if; }
+              import ietf-inet-types { prefix inet; }
+              import inet-extras { prefix inet; }
+            }
+            ",
+        );
+        let diagnostics = run_check(check_duplicate_prefixes, &input, None);
+
+        assert_eq!(
+            vec![
+                Diagnostic::new(
+                    "Prefix \"if\" is already declared for the module itself",
+                    input.rfind("if;").unwrap(),
+                ),
+                Diagnostic::new(
+                    "Prefix \"inet\" is already declared for module \"ietf-inet-types\"",
+                    input.rfind("inet;").unwrap(),
+                ),
+            ],
+            diagnostics,
+        );
+    }
+
+    #[test]
+    fn test_check_prefix_convention() {
+        let input = textwrap::dedent(
+            "
+            module foo {
+              prefix p1;
+              import ietf-interfaces { prefix if; }
+              import ietf-network-instance { prefix network-instance; }
+              import openconfig-types { prefix oc_types; }
+            }
+            ",
+        );
+        let diagnostics = run_check(check_prefix_convention, &input, None);
+
+        assert_eq!(
+            vec![
+                Diagnostic::new(
+                    "Prefix \"p1\" is too generic, use an abbreviation of the module name",
+                    input.find("p1").unwrap(),
+                ),
+                Diagnostic::new(
+                    "Prefix \"network-instance\" is longer than 10 characters",
+                    input.find("network-instance;").unwrap(),
+                ),
+                Diagnostic::new(
+                    "Prefix \"oc_types\" doesn't follow the prefix convention \"^[a-z][a-z0-9-]*$\"",
+                    input.find("oc_types").unwrap(),
+                ),
+            ],
+            diagnostics,
+        );
+    }
+}
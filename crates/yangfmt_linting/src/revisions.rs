@@ -0,0 +1,164 @@
+//! Lint rules about the revision history of a module.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use regex::Regex;
+use yangfmt_parsing::{Diagnostic, Node, Statement};
+
+use crate::{argument, module_statement, Document};
+
+lazy_static! {
+    static ref DATE: Regex = Regex::new(r"^\d{4}-\d{2}-\d{2}$").unwrap();
+}
+
+/// Reports revisions dated later than today
+pub fn check_future_revisions(document: &Document, diagnostics: &mut Vec<Diagnostic>) {
+    check_future_revisions_at(document, &today(), diagnostics);
+}
+
+fn check_future_revisions_at(document: &Document, today: &str, diagnostics: &mut Vec<Diagnostic>) {
+    for revision in revisions(document) {
+        let date = argument(revision);
+
+        if date > today {
+            diagnostics.push(Diagnostic::new(
+                format!("Revision {date} is in the future, today is {today}"),
+                revision.value_position.unwrap_or(revision.position),
+            ));
+        }
+    }
+}
+
+/// Reports revisions listed after an older revision, RFC 7950 requires the newest first
+pub fn check_revision_order(document: &Document, diagnostics: &mut Vec<Diagnostic>) {
+    let revisions = revisions(document);
+
+    for pair in revisions.windows(2) {
+        let (previous, revision) = (argument(pair[0]), argument(pair[1]));
+
+        if revision > previous {
+            diagnostics.push(Diagnostic::new(
+                format!(
+                    "Revision {revision} is listed after the older revision {previous}, \
+                     revisions should be listed newest first"
+                ),
+                pair[1].position,
+            ));
+        }
+    }
+}
+
+/// Returns the revision statements of the module with a date in the YYYY-MM-DD format, which
+/// sorts chronologically as text
+fn revisions<'a>(document: &'a Document) -> Vec<&'a Statement> {
+    let Some(module) = module_statement(&document.tree) else {
+        return vec![];
+    };
+
+    module
+        .children
+        .iter()
+        .flatten()
+        .filter_map(|node| match node {
+            Node::Statement(stmt)
+                if stmt.keyword.text() == "revision" && DATE.is_match(argument(stmt)) =>
+            {
+                Some(stmt)
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// Returns today's date (UTC) in the YYYY-MM-DD format
+fn today() -> String {
+    let seconds = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_secs());
+
+    civil_date((seconds / 86400) as i64)
+}
+
+/// Converts a number of days since 1970-01-01 to a date in the YYYY-MM-DD format
+///
+/// This is the "civil_from_days" algorithm by Howard Hinnant.
+///
+fn civil_date(days: i64) -> String {
+    let days = days + 719468;
+    let era = days.div_euclid(146097);
+    let day_of_era = days.rem_euclid(146097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+    format!("{year:04}-{month:02}-{day:02}")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{run_check, LintConfig};
+    use pretty_assertions::assert_eq;
+
+    const INPUT: &str = "module foo {\n  revision 2024-01-01;\n  revision 2025-06-01;\n  \
+                         revision 2023-01-01;\n  revision 2030-01-01;\n}\n";
+
+    #[test]
+    fn test_check_future_revisions() {
+        let config = LintConfig::default();
+        let document = Document::new(INPUT.as_bytes(), &config).unwrap();
+        let mut diagnostics = vec![];
+
+        check_future_revisions_at(&document, "2025-01-01", &mut diagnostics);
+
+        assert_eq!(
+            vec![
+                Diagnostic::new(
+                    "Revision 2025-06-01 is in the future, today is 2025-01-01",
+                    INPUT.find("2025-06-01").unwrap(),
+                ),
+                Diagnostic::new(
+                    "Revision 2030-01-01 is in the future, today is 2025-01-01",
+                    INPUT.find("2030-01-01").unwrap(),
+                ),
+            ],
+            diagnostics,
+        );
+    }
+
+    #[test]
+    fn test_check_revision_order() {
+        let diagnostics = run_check(check_revision_order, INPUT, None);
+
+        assert_eq!(
+            vec![
+                Diagnostic::new(
+                    "Revision 2025-06-01 is listed after the older revision 2024-01-01, \
+                     revisions should be listed newest first",
+                    INPUT.find("revision 2025-06-01").unwrap(),
+                ),
+                Diagnostic::new(
+                    "Revision 2030-01-01 is listed after the older revision 2023-01-01, \
+                     revisions should be listed newest first",
+                    INPUT.find("revision 2030-01-01").unwrap(),
+                ),
+            ],
+            diagnostics,
+        );
+    }
+
+    #[test]
+    fn test_civil_date() {
+        assert_eq!("1970-01-01", civil_date(0));
+        assert_eq!("2000-02-29", civil_date(11016));
+        assert_eq!("2024-12-31", civil_date(20088));
+    }
+}
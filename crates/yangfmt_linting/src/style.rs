@@ -0,0 +1,133 @@
+//! Lint rules about the style of the source, things pyang doesn't care about.
+
+use yangfmt_lexing::TokenType;
+use yangfmt_parsing::{Diagnostic, NodeHelpers, NodeValue};
+
+use crate::{walk_statements, Document};
+
+/// Words in comments marking unfinished work
+const TODO_MARKERS: &[&str] = &["TODO", "FIXME", "XXX"];
+
+/// Statements whose argument is prose, which is expected to be quoted even when it's one word
+pub const PROSE_KEYWORDS: &[&str] = &[
+    "contact",
+    "description",
+    "error-message",
+    "organization",
+    "presence",
+    "reference",
+];
+
+/// Reports statements with a block that contains nothing but empty lines
+pub fn check_empty_blocks(document: &Document, diagnostics: &mut Vec<Diagnostic>) {
+    walk_statements(&document.tree.children, None, &mut |statement, _| {
+        let Some(ref children) = statement.children else {
+            return;
+        };
+
+        if children.iter().all(|node| node.is_empty_line()) {
+            diagnostics.push(Diagnostic::new(
+                format!(
+                    "\"{}\" statement has an empty block, end it with \";\" instead",
+                    statement.keyword.text()
+                ),
+                statement.position,
+            ));
+        }
+    });
+}
+
+/// Reports comments containing a TODO marker, such as "TODO" or "FIXME"
+pub fn check_todo_comments(document: &Document, diagnostics: &mut Vec<Diagnostic>) {
+    for token in &document.tokens {
+        if token.token_type != TokenType::Comment {
+            continue;
+        }
+
+        let marker = token
+            .text
+            .split(|c: char| !c.is_ascii_alphanumeric())
+            .find(|word| TODO_MARKERS.contains(word));
+
+        if let Some(marker) = marker {
+            diagnostics.push(Diagnostic::new(
+                format!("Comment has a {marker} marker"),
+                token.span.0,
+            ));
+        }
+    }
+}
+
+/// Reports description, reference and other prose arguments that aren't quoted
+pub fn check_unquoted_text(document: &Document, diagnostics: &mut Vec<Diagnostic>) {
+    walk_statements(&document.tree.children, None, &mut |statement, _| {
+        if !PROSE_KEYWORDS.contains(&statement.keyword.text()) {
+            return;
+        }
+
+        if let Some(NodeValue::Other(_) | NodeValue::Number(_) | NodeValue::Date(_)) =
+            statement.value
+        {
+            diagnostics.push(Diagnostic::new(
+                format!("\"{}\" argument isn't quoted", statement.keyword.text()),
+                statement.value_position.unwrap_or(statement.position),
+            ));
+        }
+    });
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::run_check;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_check_empty_blocks() {
+        let input = textwrap::dedent(
+            "
+            module foo {
+              container bar {
+
+              }
+              container baz {
+                // Nothing yet
+              }
+              leaf qux;
+            }
+            ",
+        );
+
+        assert_eq!(
+            vec![Diagnostic::new(
+                "\"container\" statement has an empty block, end it with \";\" instead",
+                16,
+            )],
+            run_check(check_empty_blocks, &input, None),
+        );
+    }
+
+    #[test]
+    fn test_check_todo_comments() {
+        let input = "// FIXME\nmodule foo { /* todo */ // Not a TODOs\n  // XXX: Remove\n}\n";
+
+        assert_eq!(
+            vec![
+                Diagnostic::new("Comment has a FIXME marker", 0),
+                Diagnostic::new("Comment has a XXX marker", 50),
+            ],
+            run_check(check_todo_comments, input, None),
+        );
+    }
+
+    #[test]
+    fn test_check_unquoted_text() {
+        let input =
+            "module foo {\n  description Foo;\n  reference \"RFC 7950\";\n  units seconds;\n}\n";
+
+        assert_eq!(
+            vec![Diagnostic::new("\"description\" argument isn't quoted", 27)],
+            run_check(check_unquoted_text, input, None),
+        );
+    }
+}
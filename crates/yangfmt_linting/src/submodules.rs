@@ -0,0 +1,158 @@
+//! Lint rules about the relationship between a module and its submodules, found in the search
+//! path.
+//!
+//! A submodule is part of the module it belongs to, sharing its namespace and prefix, so the
+//! module has to include it and the submodule has to name the module in "belongs-to".
+
+use yangfmt_modules::Module;
+use yangfmt_parsing::{Diagnostic, Node};
+
+use crate::{argument, find_child, module_statement, Document};
+
+/// Reports included submodules that belong to another module, and submodules that the module
+/// they belong to doesn't include
+pub fn check_submodules(document: &Document, diagnostics: &mut Vec<Diagnostic>) {
+    let registry = &document.config.modules;
+
+    let Some(module) = module_statement(&document.tree) else {
+        return;
+    };
+
+    if registry.is_empty() {
+        return;
+    }
+
+    let name = argument(module);
+
+    if module.keyword.text() == "submodule" {
+        let Some(belongs_to) = find_child(module, "belongs-to") else {
+            return;
+        };
+
+        let parent_name = argument(belongs_to);
+
+        match registry.load(parent_name, None) {
+            Some(parent) if parent.is_submodule() => diagnostics.push(Diagnostic::new(
+                format!("\"{parent_name}\" is a submodule, submodules belong to modules"),
+                belongs_to.position,
+            )),
+            Some(parent) if !includes(parent, name) => diagnostics.push(Diagnostic::new(
+                format!("Module \"{parent_name}\" doesn't include submodule \"{name}\""),
+                belongs_to.position,
+            )),
+            _ => (),
+        }
+
+        return;
+    }
+
+    for node in module.children.iter().flatten() {
+        let Node::Statement(include) = node else {
+            continue;
+        };
+
+        if include.keyword.text() != "include" {
+            continue;
+        }
+
+        let included_name = argument(include);
+        let revision = find_child(include, "revision-date").map(argument);
+
+        let Some(included) = registry.load(included_name, revision) else {
+            continue;
+        };
+
+        let owner = included
+            .statement()
+            .and_then(|statement| find_child(statement, "belongs-to"))
+            .map(argument);
+
+        let message = match owner {
+            _ if !included.is_submodule() => {
+                format!("\"{included_name}\" is a module, only submodules can be included")
+            }
+            Some(owner) if owner != name => {
+                format!("Submodule \"{included_name}\" belongs to \"{owner}\", not to \"{name}\"")
+            }
+            _ => continue,
+        };
+
+        diagnostics.push(Diagnostic::new(message, include.position));
+    }
+}
+
+/// Returns true if the module includes the submodule
+fn includes(module: &Module, submodule: &str) -> bool {
+    module
+        .dependencies()
+        .iter()
+        .any(|dependency| dependency.keyword == "include" && dependency.module == submodule)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{run_check, LintConfig};
+    use pretty_assertions::assert_eq;
+    use yangfmt_modules::Registry;
+
+    #[test]
+    fn test_check_submodules() {
+        let path = std::env::temp_dir().join(format!("yangfmt-submodules-{}", std::process::id()));
+
+        let _ = std::fs::remove_dir_all(&path);
+        std::fs::create_dir_all(&path).unwrap();
+
+        for (file_name, contents) in [
+            ("foo.yang", "module foo { include foo-a; }"),
+            (
+                "foo-a.yang",
+                "submodule foo-a { belongs-to foo { prefix f; } }",
+            ),
+            (
+                "bar-a.yang",
+                "submodule bar-a { belongs-to bar { prefix b; } }",
+            ),
+            ("types.yang", "module types;"),
+        ] {
+            std::fs::write(path.join(file_name), contents).unwrap();
+        }
+
+        let config = || {
+            Some(LintConfig {
+                modules: Registry::new(std::slice::from_ref(&path)).unwrap(),
+                ..LintConfig::default()
+            })
+        };
+
+        let input = "module foo {\n  include foo-a;\n  include bar-a;\n  include types;\n}\n";
+
+        assert_eq!(
+            vec![
+                Diagnostic::new(
+                    "Submodule \"bar-a\" belongs to \"bar\", not to \"foo\"",
+                    input.find("include bar-a").unwrap(),
+                ),
+                Diagnostic::new(
+                    "\"types\" is a module, only submodules can be included",
+                    input.find("include types").unwrap(),
+                ),
+            ],
+            run_check(check_submodules, input, config()),
+        );
+
+        let input = "submodule foo-b {\n  belongs-to foo {\n    prefix f;\n  }\n}\n";
+
+        assert_eq!(
+            vec![Diagnostic::new(
+                "Module \"foo\" doesn't include submodule \"foo-b\"",
+                input.find("belongs-to").unwrap(),
+            )],
+            run_check(check_submodules, input, config()),
+        );
+
+        assert!(run_check(check_submodules, input, None).is_empty());
+
+        std::fs::remove_dir_all(path).unwrap();
+    }
+}
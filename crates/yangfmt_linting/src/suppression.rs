@@ -0,0 +1,140 @@
+//! Inline suppression of lint findings with comments.
+//!
+//! A "// yangfmt-lint: disable=rule-name" comment on the line above a statement, or after it on
+//! the same line, disables the rule for the statement and all its sub-statements. Several rules
+//! can be given separated by commas, and "all" disables every rule.
+
+use yangfmt_parsing::{Node, RootNode};
+
+use crate::Finding;
+
+/// Comment directive disabling lint rules, followed by a comma separated list of rule names
+const DISABLE_MARKER: &str = "yangfmt-lint: disable=";
+
+/// Rules disabled for the part of the input from "start" up to, but not including, "end"
+#[derive(Debug, PartialEq)]
+pub struct Suppression {
+    pub rules: Vec<String>,
+    pub start: usize,
+    pub end: usize,
+}
+
+impl Suppression {
+    pub fn suppresses(&self, finding: &Finding) -> bool {
+        (self.start..self.end).contains(&finding.diagnostic.position)
+            && self
+                .rules
+                .iter()
+                .any(|rule| rule == "all" || rule == finding.rule)
+    }
+}
+
+/// Finds the suppression comments in the tree and the parts of the input they apply to
+///
+/// A statement is considered to span from its keyword to the keyword of the next statement in the
+/// same block, or to the end of its parent if it's the last one.
+///
+pub fn find_suppressions(tree: &RootNode, buffer_len: usize) -> Vec<Suppression> {
+    let mut suppressions = vec![];
+
+    find_in(&tree.children, buffer_len, &mut suppressions);
+
+    suppressions
+}
+
+fn find_in(nodes: &[Node], end: usize, suppressions: &mut Vec<Suppression>) {
+    for (index, node) in nodes.iter().enumerate() {
+        let Node::Statement(statement) = node else {
+            continue;
+        };
+
+        let statement_end = nodes[index + 1..]
+            .iter()
+            .find_map(|node| match node {
+                Node::Statement(next) => Some(next.position),
+                _ => None,
+            })
+            .unwrap_or(end);
+
+        let comments_above = nodes[..index].iter().rev().map_while(|node| match node {
+            Node::Comment(text) => Some(text),
+            _ => None,
+        });
+
+        let rules: Vec<String> = comments_above
+            .chain(&statement.post_comments)
+            .flat_map(|comment| disabled_rules(comment))
+            .collect();
+
+        if !rules.is_empty() {
+            suppressions.push(Suppression {
+                rules,
+                start: statement.position,
+                end: statement_end,
+            });
+        }
+
+        if let Some(ref children) = statement.children {
+            find_in(children, statement_end, suppressions);
+        }
+    }
+}
+
+/// Returns the rule names listed in a suppression comment, if it is one
+fn disabled_rules(comment: &str) -> Vec<String> {
+    let Some(index) = comment.find(DISABLE_MARKER) else {
+        return vec![];
+    };
+
+    comment[index + DISABLE_MARKER.len()..]
+        .split(|c: char| c.is_whitespace() || c == '*')
+        .next()
+        .unwrap_or_default()
+        .split(',')
+        .filter(|rule| !rule.is_empty())
+        .map(|rule| rule.to_string())
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use yangfmt_parsing::parse;
+
+    #[test]
+    fn test_disabled_rules() {
+        assert_eq!(
+            vec!["empty-block", "todo-comment"],
+            disabled_rules("// yangfmt-lint: disable=empty-block,todo-comment")
+        );
+        assert_eq!(
+            vec!["all"],
+            disabled_rules("/* yangfmt-lint: disable=all */")
+        );
+        assert!(disabled_rules("// yangfmt-lint: disable=").is_empty());
+        assert!(disabled_rules("// Just a comment").is_empty());
+    }
+
+    #[test]
+    fn test_find_suppressions() {
+        let input = "module foo {\n  // yangfmt-lint: disable=empty-block\n  container bar {}\n  leaf baz; // yangfmt-lint: disable=all\n}\n";
+        let tree = parse(input.as_bytes()).unwrap();
+
+        assert_eq!(
+            vec![
+                Suppression {
+                    rules: vec!["empty-block".to_string()],
+                    start: 54,
+                    end: 73,
+                },
+                Suppression {
+                    rules: vec!["all".to_string()],
+                    start: 73,
+                    end: input.len(),
+                },
+            ],
+            find_suppressions(&tree, input.len()),
+        );
+    }
+}
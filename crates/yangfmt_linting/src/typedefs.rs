@@ -0,0 +1,177 @@
+//! Resolution of derived types to the typedefs they're derived from, within a module.
+//!
+//! Types are looked up in the blocks the "type" statement is nested in, from the innermost one
+//! out to the module, per section 5.5 of RFC 7950. Types from imported modules can't be resolved,
+//! since only the module itself is parsed.
+
+use yangfmt_parsing::{Node, Statement};
+
+use crate::{argument, find_child};
+
+/// Types built into YANG, which aren't derived from anything
+pub const BUILT_IN_TYPES: &[&str] = &[
+    "binary",
+    "bits",
+    "boolean",
+    "decimal64",
+    "empty",
+    "enumeration",
+    "identityref",
+    "instance-identifier",
+    "int8",
+    "int16",
+    "int32",
+    "int64",
+    "leafref",
+    "string",
+    "uint8",
+    "uint16",
+    "uint32",
+    "uint64",
+    "union",
+];
+
+/// Maximum number of typedefs followed, which stops the resolution of circular typedefs
+const MAX_DERIVATION_DEPTH: usize = 32;
+
+/// A "type" statement in the derivation of a type, along with the blocks it's nested in
+#[derive(Debug, Clone)]
+pub struct TypeStep<'a> {
+    pub statement: &'a Statement,
+    /// The blocks the statement is nested in, outermost first
+    pub scopes: Vec<&'a [Node]>,
+}
+
+/// Returns the "type" statements a type is derived through, starting with the given one and
+/// ending with the built-in type
+///
+/// Returns None if the type, or a type it's derived from, is from another module or isn't found.
+/// Types with the module's own prefix are looked up like unprefixed ones.
+///
+pub fn resolve_type<'a>(
+    type_statement: &'a Statement,
+    scopes: &[&'a [Node]],
+    own_prefix: Option<&str>,
+) -> Option<Vec<TypeStep<'a>>> {
+    let mut steps = vec![TypeStep {
+        statement: type_statement,
+        scopes: scopes.to_vec(),
+    }];
+
+    for _ in 0..MAX_DERIVATION_DEPTH {
+        let step = steps.last().unwrap();
+        let name = match argument(step.statement).split_once(':') {
+            Some((prefix, name)) if Some(prefix) == own_prefix => name,
+            Some(_) => return None,
+            None => argument(step.statement),
+        };
+
+        if BUILT_IN_TYPES.contains(&name) {
+            return Some(steps);
+        }
+
+        let next = find_typedef(name, &step.scopes)?;
+
+        steps.push(next);
+    }
+
+    None
+}
+
+/// Finds the typedef with the given name in the innermost of the blocks defining it, and returns
+/// its "type" statement
+fn find_typedef<'a>(name: &str, scopes: &[&'a [Node]]) -> Option<TypeStep<'a>> {
+    scopes.iter().enumerate().rev().find_map(|(index, nodes)| {
+        nodes.iter().find_map(|node| match node {
+            Node::Statement(stmt) if stmt.keyword.text() == "typedef" && argument(stmt) == name => {
+                Some(TypeStep {
+                    statement: find_child(stmt, "type")?,
+                    scopes: scopes[..=index].to_vec(),
+                })
+            }
+            _ => None,
+        })
+    })
+}
+
+/// Calls the function for every statement in the tree, depth first, along with the blocks it's
+/// nested in, outermost first and ending with the block the statement is in
+pub fn walk_scoped<'a>(
+    nodes: &'a [Node],
+    scopes: &mut Vec<&'a [Node]>,
+    f: &mut impl FnMut(&'a Statement, &[&'a [Node]]),
+) {
+    scopes.push(nodes);
+
+    for node in nodes {
+        if let Node::Statement(statement) = node {
+            f(statement, scopes);
+
+            if let Some(ref children) = statement.children {
+                walk_scoped(children, scopes, f);
+            }
+        }
+    }
+
+    scopes.pop();
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use yangfmt_parsing::parse;
+
+    #[test]
+    fn test_resolve_type() {
+        let input = textwrap::dedent(
+            "
+            module foo {
+              prefix f;
+              typedef percent {
+                type uint8;
+              }
+              typedef loop {
+                type loop;
+              }
+              container bar {
+                typedef ratio {
+                  type f:percent;
+                }
+                leaf a {
+                  type ratio;
+                }
+                leaf b {
+                  type inet:ip-address;
+                }
+                leaf c {
+                  type loop;
+                }
+              }
+            }
+            ",
+        );
+        let tree = parse(input.as_bytes()).unwrap();
+        let mut resolved = vec![];
+
+        walk_scoped(&tree.children, &mut vec![], &mut |statement, scopes| {
+            if statement.keyword.text() == "leaf" {
+                let type_statement = find_child(statement, "type").unwrap();
+
+                resolved.push(
+                    resolve_type(type_statement, scopes, Some("f")).map(|steps| {
+                        steps
+                            .iter()
+                            .map(|step| argument(step.statement))
+                            .collect::<Vec<_>>()
+                    }),
+                );
+            }
+        });
+
+        assert_eq!(
+            vec![Some(vec!["ratio", "f:percent", "uint8"]), None, None],
+            resolved,
+        );
+    }
+}
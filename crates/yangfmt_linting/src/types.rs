@@ -0,0 +1,532 @@
+//! Lint rules about types and the values of their restrictions.
+
+use yangfmt_parsing::{Diagnostic, Node, Statement};
+
+use crate::prefixes::declared_prefixes;
+use crate::typedefs::{resolve_type, walk_scoped, TypeStep};
+use crate::{argument, describe, find_child, insertion_fix, walk_statements, Document};
+
+/// The integer types and their ranges
+const INTEGER_TYPES: &[(&str, i128, i128)] = &[
+    ("int8", i8::MIN as i128, i8::MAX as i128),
+    ("int16", i16::MIN as i128, i16::MAX as i128),
+    ("int32", i32::MIN as i128, i32::MAX as i128),
+    ("int64", i64::MIN as i128, i64::MAX as i128),
+    ("uint8", 0, u8::MAX as i128),
+    ("uint16", 0, u16::MAX as i128),
+    ("uint32", 0, u32::MAX as i128),
+    ("uint64", 0, u64::MAX as i128),
+];
+
+/// Reports enums without a value and bits without a position
+///
+/// The findings are fixed by adding the value or position the enum or bit is implicitly given,
+/// so fixing them doesn't change the model.
+///
+pub fn check_explicit_values(document: &Document, diagnostics: &mut Vec<Diagnostic>) {
+    walk_statements(&document.tree.children, None, &mut |statement, _| {
+        if statement.keyword.text() != "type" {
+            return;
+        }
+
+        check_members(document, statement, "enum", "value", diagnostics);
+        check_members(document, statement, "bit", "position", diagnostics);
+    });
+}
+
+/// Checks the enums or bits of a type, per section 9.6.4.2 and 9.7.4.2 of RFC 7950 each one
+/// without a value is implicitly given one more than the highest value before it
+fn check_members(
+    document: &Document,
+    type_statement: &Statement,
+    keyword: &str,
+    value_keyword: &str,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    let members = type_statement
+        .children
+        .iter()
+        .flatten()
+        .filter_map(|node| match node {
+            Node::Statement(stmt) if stmt.keyword.text() == keyword => Some(stmt),
+            _ => None,
+        });
+
+    let mut highest: Option<i64> = None;
+
+    for member in members {
+        if let Some(value) = find_child(member, value_keyword) {
+            match argument(value).parse::<i64>() {
+                Ok(value) => highest = Some(highest.map_or(value, |highest| highest.max(value))),
+                // The implicit values after an invalid one aren't known
+                Err(_) => return,
+            }
+
+            continue;
+        }
+
+        let implicit = highest.map_or(0, |highest| highest + 1);
+
+        diagnostics.push(
+            Diagnostic::new(
+                format!(
+                    "{} has no {value_keyword}, its implicit {value_keyword} is {implicit}",
+                    describe(member)
+                ),
+                member.position,
+            )
+            .with_fix(insertion_fix(
+                document,
+                member,
+                &format!("{value_keyword} {implicit};"),
+            )),
+        );
+
+        highest = Some(implicit);
+    }
+}
+
+/// Reports defaults that aren't valid values of the type of the leaf, leaf-list or typedef
+///
+/// Only types defined in the module itself can be resolved, defaults of types from other modules
+/// aren't checked.
+///
+pub fn check_defaults(document: &Document, diagnostics: &mut Vec<Diagnostic>) {
+    let own_prefix = declared_prefixes(&document.tree)
+        .into_iter()
+        .find(|prefix| prefix.module.is_none())
+        .map(|prefix| prefix.prefix);
+
+    walk_scoped(
+        &document.tree.children,
+        &mut vec![],
+        &mut |statement, scopes| {
+            if !matches!(statement.keyword.text(), "leaf" | "leaf-list" | "typedef") {
+                return;
+            }
+
+            let Some(type_statement) = find_child(statement, "type") else {
+                return;
+            };
+
+            let defaults = statement
+                .children
+                .iter()
+                .flatten()
+                .filter_map(|node| match node {
+                    Node::Statement(stmt) if stmt.keyword.text() == "default" => Some(stmt),
+                    _ => None,
+                });
+
+            for default in defaults {
+                if let Err(message) =
+                    validate(argument(default), type_statement, scopes, own_prefix)
+                {
+                    diagnostics.push(Diagnostic::new(
+                        message,
+                        default.value_position.unwrap_or(default.position),
+                    ));
+                }
+            }
+        },
+    );
+}
+
+/// Checks that the value is valid for the type, returns Ok if the type can't be resolved
+fn validate(
+    value: &str,
+    type_statement: &Statement,
+    scopes: &[&[Node]],
+    own_prefix: Option<&str>,
+) -> Result<(), String> {
+    let Some(steps) = resolve_type(type_statement, scopes, own_prefix) else {
+        return Ok(());
+    };
+
+    let built_in = steps.last().unwrap();
+    let type_name = argument(built_in.statement);
+
+    // Restrictions like "range" can be given at every step, the value has to satisfy them all
+    let restrictions = |keyword: &str| -> Vec<&str> {
+        steps
+            .iter()
+            .filter_map(|step| find_child(step.statement, keyword))
+            .map(argument)
+            .collect()
+    };
+
+    if let Some((_, min, max)) = INTEGER_TYPES.iter().find(|(name, ..)| *name == type_name) {
+        let Some(number) = parse_integer(value) else {
+            return Err(format!("Default \"{value}\" isn't a valid {type_name}"));
+        };
+
+        if number < *min || number > *max {
+            return Err(format!(
+                "Default \"{value}\" is out of the range of {type_name}"
+            ));
+        }
+
+        for range in restrictions("range") {
+            if !in_ranges(number, range, *min, *max).unwrap_or(true) {
+                return Err(format!(
+                    "Default \"{value}\" is outside the range \"{range}\""
+                ));
+            }
+        }
+
+        return Ok(());
+    }
+
+    match type_name {
+        "boolean" if !matches!(value, "true" | "false") => Err(format!(
+            "Default \"{value}\" isn't a valid boolean, expected \"true\" or \"false\""
+        )),
+        "empty" => Err("Leafs of type \"empty\" can't have a default".to_string()),
+        "decimal64" => validate_decimal(value, &steps, &restrictions("range")),
+        "string" => {
+            let length = value.chars().count() as i128;
+
+            match restrictions("length")
+                .into_iter()
+                .find(|range| !in_ranges(length, range, 0, i128::MAX).unwrap_or(true))
+            {
+                Some(range) => Err(format!(
+                    "Default \"{value}\" is outside the length \"{range}\""
+                )),
+                None => Ok(()),
+            }
+        }
+        "enumeration" => match members(&steps, "enum") {
+            Some(names) if !names.contains(&value) => Err(format!(
+                "Default \"{value}\" isn't one of the enums of the type"
+            )),
+            _ => Ok(()),
+        },
+        "bits" => {
+            let names = members(&steps, "bit").unwrap_or_default();
+
+            match value
+                .split_whitespace()
+                .find(|bit| !names.is_empty() && !names.contains(bit))
+            {
+                Some(bit) => Err(format!(
+                    "Default \"{value}\" has \"{bit}\", which isn't one of the bits of the type"
+                )),
+                None => Ok(()),
+            }
+        }
+        "union" => {
+            let member_types: Vec<&Statement> = built_in
+                .statement
+                .children
+                .iter()
+                .flatten()
+                .filter_map(|node| match node {
+                    Node::Statement(stmt) if stmt.keyword.text() == "type" => Some(stmt),
+                    _ => None,
+                })
+                .collect();
+
+            if member_types.is_empty()
+                || member_types
+                    .iter()
+                    .any(|member| validate(value, member, &built_in.scopes, own_prefix).is_ok())
+            {
+                Ok(())
+            } else {
+                Err(format!(
+                    "Default \"{value}\" isn't valid for any member type of the union"
+                ))
+            }
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Checks a decimal64 value against the fraction digits and ranges of the type
+fn validate_decimal(value: &str, steps: &[TypeStep], ranges: &[&str]) -> Result<(), String> {
+    let fraction_digits = steps
+        .iter()
+        .find_map(|step| find_child(step.statement, "fraction-digits"))
+        .and_then(|digits| argument(digits).parse::<usize>().ok());
+
+    let digits_after_point = value
+        .split_once('.')
+        .map_or(0, |(_, fraction)| fraction.len());
+    let number = value.parse::<f64>().ok().filter(|_| {
+        value
+            .trim_start_matches('-')
+            .chars()
+            .all(|c| c.is_ascii_digit() || c == '.')
+    });
+
+    match (number, fraction_digits) {
+        (None, _) => Err(format!("Default \"{value}\" isn't a valid decimal64")),
+        (Some(_), Some(fraction_digits)) if digits_after_point > fraction_digits => Err(format!(
+            "Default \"{value}\" has more than {fraction_digits} fraction digits"
+        )),
+        (Some(number), _) => match ranges
+            .iter()
+            .find(|range| !in_ranges(number, range, f64::MIN, f64::MAX).unwrap_or(true))
+        {
+            Some(range) => Err(format!(
+                "Default \"{value}\" is outside the range \"{range}\""
+            )),
+            None => Ok(()),
+        },
+    }
+}
+
+/// Returns the names of the enums or bits of the type, from the step closest to where it's used
+/// that lists them
+fn members<'a>(steps: &[TypeStep<'a>], keyword: &str) -> Option<Vec<&'a str>> {
+    steps.iter().find_map(|step| {
+        let names: Vec<&str> = step
+            .statement
+            .children
+            .iter()
+            .flatten()
+            .filter_map(|node| match node {
+                Node::Statement(stmt) if stmt.keyword.text() == keyword => Some(argument(stmt)),
+                _ => None,
+            })
+            .collect();
+
+        (!names.is_empty()).then_some(names)
+    })
+}
+
+/// Parses an integer in the decimal, hexadecimal or octal notation of YANG
+fn parse_integer(text: &str) -> Option<i128> {
+    let (sign, digits) = match text.strip_prefix('-') {
+        Some(digits) => (-1, digits),
+        None => (1, text.strip_prefix('+').unwrap_or(text)),
+    };
+
+    let number = if let Some(hex) = digits.strip_prefix("0x") {
+        i128::from_str_radix(hex, 16).ok()?
+    } else if digits.len() > 1 && digits.starts_with('0') {
+        i128::from_str_radix(&digits[1..], 8).ok()?
+    } else {
+        digits.parse::<i128>().ok()?
+    };
+
+    Some(sign * number)
+}
+
+/// Returns true if the number is within one of the ranges of a "range" or "length" argument, like
+/// "1..10 | 20..max", or None if the argument can't be parsed
+fn in_ranges<T>(number: T, ranges: &str, min: T, max: T) -> Option<bool>
+where
+    T: PartialOrd + Copy + std::str::FromStr,
+{
+    let bound = |text: &str| match text.trim() {
+        "min" => Some(min),
+        "max" => Some(max),
+        text => text.parse::<T>().ok(),
+    };
+
+    for range in ranges.split('|') {
+        let (low, high) = match range.split_once("..") {
+            Some((low, high)) => (bound(low)?, bound(high)?),
+            None => (bound(range)?, bound(range)?),
+        };
+
+        if low <= number && number <= high {
+            return Some(true);
+        }
+    }
+
+    Some(false)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{apply_fixes, lint, run_check, LintConfig, Severity};
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_check_explicit_values() {
+        let input = textwrap::dedent(
+            "
+            type enumeration {
+              enum up;
+              enum down {
+                value 5;
+              }
+              enum testing {
+                description \"Testing\";
+              }
+            }
+            type bits {
+              bit a {
+                position 3;
+              }
+              bit b;
+            }
+            ",
+        );
+        let diagnostics = run_check(check_explicit_values, &input, None);
+
+        let messages: Vec<_> = diagnostics
+            .iter()
+            .map(|diagnostic| diagnostic.message.as_str())
+            .collect();
+
+        assert_eq!(
+            vec![
+                "enum \"up\" has no value, its implicit value is 0",
+                "enum \"testing\" has no value, its implicit value is 6",
+                "bit \"b\" has no position, its implicit position is 4",
+            ],
+            messages,
+        );
+    }
+
+    #[test]
+    fn test_fix_explicit_values() {
+        let input =
+            "type enumeration {\n  enum up;\n  enum down {\n    description \"Down\";\n  }\n}\n";
+        let config = LintConfig {
+            severities: [("explicit-enum-values".to_string(), Some(Severity::Warning))].into(),
+            description_required: vec![],
+            ..LintConfig::default()
+        };
+
+        let findings = lint(input.as_bytes(), None, &config).unwrap();
+        let (output, _) = apply_fixes(input.as_bytes(), findings);
+
+        assert_eq!(
+            "type enumeration {\n  enum up {\n    value 0;\n  }\n  enum down {\n    value 1;\n    description \"Down\";\n  }\n}\n",
+            String::from_utf8(output).unwrap(),
+        );
+    }
+
+    #[test]
+    fn test_check_defaults() {
+        let input = textwrap::dedent(
+            "
+            module foo {
+              prefix f;
+              typedef percent {
+                type uint8 {
+                  range 0..100;
+                }
+                default 150;
+              }
+              typedef state {
+                type enumeration {
+                  enum up;
+                  enum down;
+                }
+              }
+              leaf a {
+                type f:percent;
+                default 50;
+              }
+              leaf b {
+                type state;
+                default unknown;
+              }
+              leaf c {
+                type boolean;
+                default yes;
+              }
+              leaf d {
+                type int8;
+                default 0x80;
+              }
+              leaf-list e {
+                type bits {
+                  bit x;
+                  bit y;
+                }
+                default \"x y\";
+                default \"x z\";
+              }
+              leaf f {
+                type decimal64 {
+                  fraction-digits 2;
+                }
+                default 1.234;
+              }
+              leaf g {
+                type union {
+                  type int32;
+                  type state;
+                }
+                default unknown;
+              }
+              leaf h {
+                type string {
+                  length 2..4;
+                }
+                default abcde;
+              }
+              leaf i {
+                type inet:port-number;
+                default anything;
+              }
+            }
+            ",
+        );
+        let diagnostics = run_check(check_defaults, &input, None);
+
+        assert_eq!(
+            vec![
+                Diagnostic::new(
+                    "Default \"150\" is outside the range \"0..100\"",
+                    input.find("150").unwrap(),
+                ),
+                Diagnostic::new(
+                    "Default \"unknown\" isn't one of the enums of the type",
+                    input.find("unknown").unwrap(),
+                ),
+                Diagnostic::new(
+                    "Default \"yes\" isn't a valid boolean, expected \"true\" or \"false\"",
+                    input.find("yes").unwrap(),
+                ),
+                Diagnostic::new(
+                    "Default \"0x80\" is out of the range of int8",
+                    input.find("0x80").unwrap(),
+                ),
+                Diagnostic::new(
+                    "Default \"x z\" has \"z\", which isn't one of the bits of the type",
+                    input.find("\"x z\"").unwrap(),
+                ),
+                Diagnostic::new(
+                    "Default \"1.234\" has more than 2 fraction digits",
+                    input.find("1.234").unwrap(),
+                ),
+                Diagnostic::new(
+                    "Default \"unknown\" isn't valid for any member type of the union",
+                    input.rfind("unknown").unwrap(),
+                ),
+                Diagnostic::new(
+                    "Default \"abcde\" is outside the length \"2..4\"",
+                    input.find("abcde").unwrap(),
+                ),
+            ],
+            diagnostics,
+        );
+    }
+
+    #[test]
+    fn test_parse_integer() {
+        assert_eq!(Some(42), parse_integer("42"));
+        assert_eq!(Some(-16), parse_integer("-0x10"));
+        assert_eq!(Some(8), parse_integer("010"));
+        assert_eq!(Some(0), parse_integer("0"));
+        assert_eq!(None, parse_integer("4x"));
+    }
+
+    #[test]
+    fn test_in_ranges() {
+        assert_eq!(Some(true), in_ranges(5, "1..10", 0, 255));
+        assert_eq!(Some(true), in_ranges(25, "1..10 | 20..max", 0, 255));
+        assert_eq!(Some(false), in_ranges(15, "1..10 | 20..max", 0, 255));
+        assert_eq!(Some(true), in_ranges(7, "min..0 | 7", -128, 127));
+        assert_eq!(None, in_ranges(7, "one..two", 0, 255));
+    }
+}
@@ -0,0 +1,156 @@
+//! Lint rules about the YANG version a module declares.
+
+use regex::Regex;
+use yangfmt_parsing::{Diagnostic, StatementKeyword};
+
+use crate::{argument, find_child, module_statement, walk_statements, Document};
+
+lazy_static! {
+    /// The operators of YANG 1.1 if-feature expressions
+    static ref IF_FEATURE_OPERATOR: Regex = Regex::new(r"\b(and|or|not)\b|[()]").unwrap();
+}
+
+/// Statements added in YANG 1.1, along with the parents they're new in, or None if they're new
+/// everywhere
+const YANG_1_1_STATEMENTS: &[(&str, Option<&[&str]>)] = &[
+    ("action", None),
+    ("anydata", None),
+    ("modifier", None),
+    ("default", Some(&["leaf-list"])),
+    ("if-feature", Some(&["bit", "enum", "identity", "refine"])),
+    ("must", Some(&["input", "notification", "output"])),
+    (
+        "notification",
+        Some(&["augment", "case", "container", "grouping", "list"]),
+    ),
+];
+
+/// Reports YANG 1.1 statements in modules that don't declare "yang-version 1.1"
+pub fn check_yang_version(document: &Document, diagnostics: &mut Vec<Diagnostic>) {
+    let Some(module) = module_statement(&document.tree) else {
+        return;
+    };
+
+    if find_child(module, "yang-version").is_some_and(|version| argument(version) == "1.1") {
+        return;
+    }
+
+    walk_statements(&document.tree.children, None, &mut |statement, parent| {
+        let StatementKeyword::Keyword(ref keyword) = statement.keyword else {
+            return;
+        };
+
+        let parent_keyword = parent.map(|parent| parent.keyword.text()).unwrap_or("");
+
+        let new_statement = YANG_1_1_STATEMENTS.iter().find(|(new_keyword, parents)| {
+            new_keyword == keyword
+                && parents.is_none_or(|parents| parents.contains(&parent_keyword))
+        });
+
+        let feature = match (keyword.as_str(), new_statement) {
+            (_, Some((_, None))) => format!("\"{keyword}\" statement"),
+            (_, Some((_, Some(_)))) => format!("\"{keyword}\" in \"{parent_keyword}\""),
+            ("require-instance", _)
+                if parent.is_some_and(|parent| argument(parent) == "leafref") =>
+            {
+                "\"require-instance\" in leafref types".to_string()
+            }
+            ("base", _)
+                if parent
+                    .and_then(|parent| find_child(parent, "base"))
+                    .is_some_and(|first| !std::ptr::eq(first, statement)) =>
+            {
+                "More than one \"base\"".to_string()
+            }
+            ("if-feature", _) if IF_FEATURE_OPERATOR.is_match(argument(statement)) => {
+                "if-feature expression".to_string()
+            }
+            _ => return,
+        };
+
+        diagnostics.push(Diagnostic::new(
+            format!("{feature} requires \"yang-version 1.1\""),
+            statement.position,
+        ));
+    });
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::run_check;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_check_yang_version() {
+        let input = textwrap::dedent(
+            "
+            module foo {
+              yang-version 1;
+              container a {
+                action reset;
+                notification changed;
+                leaf-list b {
+                  default x;
+                }
+                leaf c {
+                  type leafref {
+                    path ../b;
+                    require-instance false;
+                  }
+                  if-feature \"x or y\";
+                }
+              }
+              identity d {
+                base x;
+                base y;
+              }
+              rpc e {
+                input {
+                  leaf f {
+                    if-feature x;
+                  }
+                }
+              }
+            }
+            ",
+        );
+
+        assert_eq!(
+            vec![
+                Diagnostic::new(
+                    "\"action\" statement requires \"yang-version 1.1\"",
+                    input.find("action").unwrap(),
+                ),
+                Diagnostic::new(
+                    "\"notification\" in \"container\" requires \"yang-version 1.1\"",
+                    input.find("notification").unwrap(),
+                ),
+                Diagnostic::new(
+                    "\"default\" in \"leaf-list\" requires \"yang-version 1.1\"",
+                    input.find("default").unwrap(),
+                ),
+                Diagnostic::new(
+                    "\"require-instance\" in leafref types requires \"yang-version 1.1\"",
+                    input.find("require-instance").unwrap(),
+                ),
+                Diagnostic::new(
+                    "if-feature expression requires \"yang-version 1.1\"",
+                    input.find("if-feature").unwrap(),
+                ),
+                Diagnostic::new(
+                    "More than one \"base\" requires \"yang-version 1.1\"",
+                    input.rfind("base").unwrap(),
+                ),
+            ],
+            run_check(check_yang_version, &input, None),
+        );
+
+        assert!(run_check(
+            check_yang_version,
+            &input.replace("yang-version 1;", "yang-version 1.1;"),
+            None
+        )
+        .is_empty());
+    }
+}
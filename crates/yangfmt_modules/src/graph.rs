@@ -0,0 +1,470 @@
+//! The graph of imports and includes between modules, printed by "yangfmt deps".
+
+use std::collections::HashSet;
+use std::fmt::Write;
+use std::path::PathBuf;
+
+use crate::{Module, Registry};
+
+/// Output formats of the dependency graph
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GraphFormat {
+    /// An indented tree, like the output of "tree"
+    Tree,
+    Json,
+    /// Graphviz
+    Dot,
+}
+
+impl std::str::FromStr for GraphFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "tree" => Ok(Self::Tree),
+            "json" => Ok(Self::Json),
+            "dot" => Ok(Self::Dot),
+            _ => Err(format!(
+                "Invalid graph format {s:?}, expected tree, json or dot"
+            )),
+        }
+    }
+}
+
+/// A module in the graph
+#[derive(Debug, PartialEq)]
+pub struct GraphNode {
+    pub name: String,
+    /// The latest revision of the module
+    pub revision: Option<String>,
+    /// Path of the file with the module, None if it isn't found in the search path
+    pub path: Option<PathBuf>,
+    pub dependencies: Vec<Edge>,
+}
+
+/// An import or include
+#[derive(Debug, PartialEq)]
+pub struct Edge {
+    /// "import" or "include"
+    pub keyword: String,
+    pub module: String,
+    /// The revision given with "revision-date", if any
+    pub revision: Option<String>,
+}
+
+/// The modules a module depends on, directly or through other modules
+#[derive(Debug, PartialEq)]
+pub struct Graph {
+    /// The modules in the order they're found, starting with the root module
+    pub nodes: Vec<GraphNode>,
+}
+
+impl Graph {
+    /// Follows the imports and includes of the module, loading the modules from the registry
+    ///
+    /// Each module is only visited once, by name, so revisions are only followed for the first
+    /// import of a module.
+    ///
+    pub fn build(root: &Module, registry: &Registry) -> Self {
+        let mut nodes = vec![node(root, Some(root.path.clone()))];
+        let mut index = 0;
+
+        while index < nodes.len() {
+            let edges: Vec<(String, Option<String>)> = nodes[index]
+                .dependencies
+                .iter()
+                .map(|edge| (edge.module.clone(), edge.revision.clone()))
+                .collect();
+
+            for (name, revision) in edges {
+                if nodes.iter().any(|node| node.name == name) {
+                    continue;
+                }
+
+                nodes.push(match registry.load(&name, revision.as_deref()) {
+                    Some(module) => node(module, Some(module.path.clone())),
+                    None => GraphNode {
+                        path: registry.find(&name, revision.as_deref()).map(Into::into),
+                        name,
+                        revision,
+                        dependencies: vec![],
+                    },
+                });
+            }
+
+            index += 1;
+        }
+
+        Self { nodes }
+    }
+
+    fn find(&self, name: &str) -> Option<&GraphNode> {
+        self.nodes.iter().find(|node| node.name == name)
+    }
+
+    /// Returns the module names in an order they can be compiled in, dependencies first
+    ///
+    /// Modules in a cycle are ordered as if the import closing the cycle wasn't there.
+    ///
+    pub fn compile_order(&self) -> Vec<&str> {
+        let mut order = vec![];
+        let mut visited = HashSet::new();
+
+        for node in &self.nodes {
+            self.visit(node, &mut visited, &mut order);
+        }
+
+        order
+    }
+
+    fn visit<'a>(
+        &'a self,
+        node: &'a GraphNode,
+        visited: &mut HashSet<&'a str>,
+        order: &mut Vec<&'a str>,
+    ) {
+        if !visited.insert(&node.name) {
+            return;
+        }
+
+        for edge in &node.dependencies {
+            if let Some(dependency) = self.find(&edge.module) {
+                self.visit(dependency, visited, order);
+            }
+        }
+
+        order.push(&node.name);
+    }
+
+    pub fn render(&self, format: GraphFormat) -> String {
+        match format {
+            GraphFormat::Tree => self.to_tree(),
+            GraphFormat::Json => self.to_json(),
+            GraphFormat::Dot => self.to_dot(),
+        }
+    }
+
+    /// Renders the graph as a tree, where modules already shown further up are marked with "(*)"
+    /// instead of repeating their dependencies
+    fn to_tree(&self) -> String {
+        let mut output = String::new();
+        let mut expanded = HashSet::new();
+
+        if let Some(root) = self.nodes.first() {
+            writeln!(output, "{}", describe(root)).unwrap();
+            expanded.insert(root.name.as_str());
+            self.tree_branches(root, "", &mut expanded, &mut output);
+        }
+
+        output
+    }
+
+    fn tree_branches<'a>(
+        &'a self,
+        node: &'a GraphNode,
+        indent: &str,
+        expanded: &mut HashSet<&'a str>,
+        output: &mut String,
+    ) {
+        for (index, edge) in node.dependencies.iter().enumerate() {
+            let last = index == node.dependencies.len() - 1;
+            let (branch, next_indent) = match last {
+                true => ("└── ", "    "),
+                false => ("├── ", "│   "),
+            };
+
+            let Some(dependency) = self.find(&edge.module) else {
+                continue;
+            };
+
+            let repeated =
+                !expanded.insert(&dependency.name) && !dependency.dependencies.is_empty();
+
+            writeln!(
+                output,
+                "{indent}{branch}{} {}{}",
+                edge.keyword,
+                describe(dependency),
+                if repeated { " (*)" } else { "" },
+            )
+            .unwrap();
+
+            if !repeated {
+                let indent = format!("{indent}{next_indent}");
+
+                self.tree_branches(dependency, &indent, expanded, output);
+            }
+        }
+    }
+
+    fn to_json(&self) -> String {
+        let mut output = String::from("{\n  \"modules\": [");
+
+        for (index, node) in self.nodes.iter().enumerate() {
+            let dependencies: Vec<String> = node
+                .dependencies
+                .iter()
+                .map(|edge| {
+                    format!(
+                        "{{\"type\": {}, \"module\": {}, \"revision\": {}}}",
+                        json_string(&edge.keyword),
+                        json_string(&edge.module),
+                        json_option(edge.revision.as_deref()),
+                    )
+                })
+                .collect();
+
+            write!(
+                output,
+                "{}\n    {{\n      \"name\": {},\n      \"revision\": {},\n      \"path\": {},\n      \
+                 \"dependencies\": [{}]\n    }}",
+                if index == 0 { "" } else { "," },
+                json_string(&node.name),
+                json_option(node.revision.as_deref()),
+                json_option(
+                    node.path
+                        .as_ref()
+                        .map(|path| path.to_string_lossy())
+                        .as_deref()
+                ),
+                dependencies.join(", "),
+            )
+            .unwrap();
+        }
+
+        let order: Vec<String> = self.compile_order().into_iter().map(json_string).collect();
+
+        write!(output, "\n  ],\n  \"order\": [{}]\n}}\n", order.join(", ")).unwrap();
+
+        output
+    }
+
+    /// Renders the graph for Graphviz, includes are dashed and modules that aren't found dotted
+    fn to_dot(&self) -> String {
+        let mut output = String::from("digraph dependencies {\n");
+
+        for node in &self.nodes {
+            if node.path.is_none() {
+                writeln!(output, "  {} [style=dotted];", json_string(&node.name)).unwrap();
+            }
+        }
+
+        for node in &self.nodes {
+            for edge in &node.dependencies {
+                writeln!(
+                    output,
+                    "  {} -> {}{};",
+                    json_string(&node.name),
+                    json_string(&edge.module),
+                    if edge.keyword == "include" {
+                        " [style=dashed]"
+                    } else {
+                        ""
+                    },
+                )
+                .unwrap();
+            }
+        }
+
+        output.push_str("}\n");
+        output
+    }
+}
+
+fn node(module: &Module, path: Option<PathBuf>) -> GraphNode {
+    GraphNode {
+        name: module.name().to_string(),
+        revision: module.revision().map(str::to_string),
+        path,
+        dependencies: module
+            .dependencies()
+            .into_iter()
+            .map(|dependency| Edge {
+                keyword: dependency.keyword.to_string(),
+                module: dependency.module.to_string(),
+                revision: dependency.revision.map(str::to_string),
+            })
+            .collect(),
+    }
+}
+
+/// Describes a module by its name and revision, or says it's not found
+fn describe(node: &GraphNode) -> String {
+    match (&node.revision, &node.path) {
+        (_, None) => format!("{} (not found)", node.name),
+        (Some(revision), _) => format!("{}@{revision}", node.name),
+        (None, _) => node.name.clone(),
+    }
+}
+
+/// Quotes and escapes the text as a JSON string, which is also a valid DOT identifier
+fn json_string(text: &str) -> String {
+    let mut quoted = String::from("\"");
+
+    for c in text.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            c if c.is_control() => write!(quoted, "\\u{:04x}", c as u32).unwrap(),
+            c => quoted.push(c),
+        }
+    }
+
+    quoted.push('"');
+    quoted
+}
+
+fn json_option(text: Option<&str>) -> String {
+    text.map_or("null".to_string(), json_string)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn graph() -> Graph {
+        let edge = |keyword: &str, module: &str| Edge {
+            keyword: keyword.to_string(),
+            module: module.to_string(),
+            revision: None,
+        };
+
+        Graph {
+            nodes: vec![
+                GraphNode {
+                    name: "foo".to_string(),
+                    revision: Some("2024-01-01".to_string()),
+                    path: Some(PathBuf::from("foo.yang")),
+                    dependencies: vec![
+                        edge("import", "types"),
+                        edge("include", "foo-sub"),
+                        edge("import", "missing"),
+                    ],
+                },
+                GraphNode {
+                    name: "types".to_string(),
+                    revision: None,
+                    path: Some(PathBuf::from("lib/types.yang")),
+                    dependencies: vec![edge("import", "base")],
+                },
+                GraphNode {
+                    name: "foo-sub".to_string(),
+                    revision: None,
+                    path: Some(PathBuf::from("foo-sub.yang")),
+                    dependencies: vec![edge("import", "types")],
+                },
+                GraphNode {
+                    name: "missing".to_string(),
+                    revision: None,
+                    path: None,
+                    dependencies: vec![],
+                },
+                GraphNode {
+                    name: "base".to_string(),
+                    revision: None,
+                    path: Some(PathBuf::from("lib/base.yang")),
+                    dependencies: vec![],
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn test_compile_order() {
+        assert_eq!(
+            vec!["base", "types", "foo-sub", "missing", "foo"],
+            graph().compile_order()
+        );
+    }
+
+    #[test]
+    fn test_render_tree() {
+        assert_eq!(
+            textwrap::dedent(
+                "
+                foo@2024-01-01
+                ├── import types
+                │   └── import base
+                ├── include foo-sub
+                │   └── import types (*)
+                └── import missing (not found)
+                "
+            )
+            .trim_start(),
+            graph().render(GraphFormat::Tree),
+        );
+    }
+
+    #[test]
+    fn test_render_json() {
+        let json = graph().render(GraphFormat::Json);
+
+        assert!(json.starts_with(
+            "{\n  \"modules\": [\n    {\n      \"name\": \"foo\",\n      \
+             \"revision\": \"2024-01-01\",\n      \"path\": \"foo.yang\",\n      \
+             \"dependencies\": [{\"type\": \"import\", \"module\": \"types\", \"revision\": null}, "
+        ));
+        assert!(json
+            .contains("\"name\": \"missing\",\n      \"revision\": null,\n      \"path\": null,"));
+        assert!(json.ends_with(
+            "\n  ],\n  \"order\": [\"base\", \"types\", \"foo-sub\", \"missing\", \"foo\"]\n}\n"
+        ));
+    }
+
+    #[test]
+    fn test_render_dot() {
+        assert_eq!(
+            textwrap::dedent(
+                "
+                digraph dependencies {
+                  \"missing\" [style=dotted];
+                  \"foo\" -> \"types\";
+                  \"foo\" -> \"foo-sub\" [style=dashed];
+                  \"foo\" -> \"missing\";
+                  \"types\" -> \"base\";
+                  \"foo-sub\" -> \"types\";
+                }
+                "
+            )
+            .trim_start(),
+            graph().render(GraphFormat::Dot),
+        );
+    }
+
+    #[test]
+    fn test_build() {
+        let path = std::env::temp_dir().join(format!("yangfmt-graph-{}", std::process::id()));
+
+        let _ = std::fs::remove_dir_all(&path);
+        std::fs::create_dir_all(&path).unwrap();
+        std::fs::write(
+            path.join("types.yang"),
+            "module types { import foo { prefix f; } }",
+        )
+        .unwrap();
+
+        let root = Module::new(
+            "foo.yang",
+            b"module foo { import types { prefix t; } import missing { prefix m; } }",
+        )
+        .unwrap();
+        let registry = Registry::new(std::slice::from_ref(&path)).unwrap();
+        let graph = Graph::build(&root, &registry);
+
+        assert_eq!(
+            vec!["foo", "types", "missing"],
+            graph
+                .nodes
+                .iter()
+                .map(|node| node.name.as_str())
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(Some(path.join("types.yang")), graph.nodes[1].path);
+        assert_eq!(None, graph.nodes[2].path);
+        assert_eq!(vec!["types", "missing", "foo"], graph.compile_order());
+
+        std::fs::remove_dir_all(path).unwrap();
+    }
+}
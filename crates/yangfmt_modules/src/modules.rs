@@ -0,0 +1,372 @@
+//! Lookup of YANG modules by name, in the directories of a search path.
+//!
+//! Some checks need more than the module being formatted, like whether a grouping used from an
+//! imported module exists. The registry finds the files of other modules by their name,
+//! "<module>.yang" or "<module>@<revision>.yang" as described in section 5.2 of RFC 7950, and only
+//! parses the ones that are actually loaded.
+
+mod graph;
+
+use std::cell::OnceCell;
+use std::path::{Path, PathBuf};
+
+use yangfmt_parsing::{parse, Node, NodeValue, ParseError, RootNode, Statement};
+
+pub use crate::graph::{Edge, Graph, GraphFormat, GraphNode};
+
+/// A parsed module or submodule
+#[derive(Debug)]
+pub struct Module {
+    /// Path of the file the module was read from
+    pub path: PathBuf,
+    pub tree: RootNode,
+}
+
+/// An "import" or "include" statement of a module
+#[derive(Debug, PartialEq)]
+pub struct Dependency<'a> {
+    /// The keyword, "import" or "include"
+    pub keyword: &'a str,
+    /// Name of the imported module or included submodule
+    pub module: &'a str,
+    /// The prefix of an import
+    pub prefix: Option<&'a str>,
+    /// The revision given with "revision-date", if any
+    pub revision: Option<&'a str>,
+    /// Byte offset of the statement in the file
+    pub position: usize,
+}
+
+impl Module {
+    pub fn new(path: impl Into<PathBuf>, buffer: &[u8]) -> Result<Self, ParseError> {
+        Ok(Self {
+            path: path.into(),
+            tree: parse(buffer)?,
+        })
+    }
+
+    /// Returns the "module" or "submodule" statement
+    pub fn statement(&self) -> Option<&Statement> {
+        self.tree.children.iter().find_map(|node| match node {
+            Node::Statement(statement)
+                if matches!(statement.keyword.text(), "module" | "submodule") =>
+            {
+                Some(statement)
+            }
+            _ => None,
+        })
+    }
+
+    /// Returns the name of the module, or an empty string if the file doesn't have one
+    pub fn name(&self) -> &str {
+        self.statement().map(argument).unwrap_or("")
+    }
+
+    pub fn is_submodule(&self) -> bool {
+        self.statement()
+            .is_some_and(|statement| statement.keyword.text() == "submodule")
+    }
+
+    /// Returns the prefix of the module, for submodules the prefix of the module they belong to
+    pub fn prefix(&self) -> Option<&str> {
+        let statement = self.statement()?;
+
+        match statement.keyword.text() {
+            "submodule" => find_child(find_child(statement, "belongs-to")?, "prefix"),
+            _ => find_child(statement, "prefix"),
+        }
+        .map(argument)
+    }
+
+    /// Returns the latest revision of the module
+    pub fn revision(&self) -> Option<&str> {
+        // Dates in the YYYY-MM-DD format sort chronologically as text
+        children(self.statement()?, "revision").map(argument).max()
+    }
+
+    /// Returns the imports and includes of the module, in the order they're declared
+    pub fn dependencies(&self) -> Vec<Dependency<'_>> {
+        let Some(statement) = self.statement() else {
+            return vec![];
+        };
+
+        statement
+            .children
+            .iter()
+            .flatten()
+            .filter_map(|node| match node {
+                Node::Statement(stmt) if matches!(stmt.keyword.text(), "import" | "include") => {
+                    Some(Dependency {
+                        keyword: stmt.keyword.text(),
+                        module: argument(stmt),
+                        prefix: find_child(stmt, "prefix").map(argument),
+                        revision: find_child(stmt, "revision-date").map(argument),
+                        position: stmt.position,
+                    })
+                }
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+/// The module files found in the directories of a search path
+///
+/// Files are only read and parsed the first time they're loaded.
+///
+#[derive(Debug, Default)]
+pub struct Registry {
+    files: Vec<ModuleFile>,
+}
+
+#[derive(Debug)]
+struct ModuleFile {
+    /// Index of the directory in the search path
+    directory: usize,
+    name: String,
+    /// The revision in the file name, if any
+    revision: Option<String>,
+    path: PathBuf,
+    /// The parsed module, None if the file can't be read or parsed
+    module: OnceCell<Option<Module>>,
+}
+
+impl Registry {
+    /// Lists the ".yang" files in the directories, which aren't searched recursively
+    pub fn new(search_path: &[PathBuf]) -> Result<Self, String> {
+        let mut files = vec![];
+
+        for (directory, path) in search_path.iter().enumerate() {
+            let entries = std::fs::read_dir(path).map_err(|error| {
+                format!(
+                    "Failed to read module directory {}: {error}",
+                    path.display()
+                )
+            })?;
+
+            let mut paths: Vec<PathBuf> = entries
+                .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+                .filter(|path| path.is_file())
+                .collect();
+
+            paths.sort();
+
+            for path in paths {
+                let Some((name, revision)) = path
+                    .file_name()
+                    .and_then(|file_name| file_name.to_str())
+                    .and_then(parse_file_name)
+                else {
+                    continue;
+                };
+
+                files.push(ModuleFile {
+                    directory,
+                    name: name.to_string(),
+                    revision: revision.map(str::to_string),
+                    path: path.clone(),
+                    module: OnceCell::new(),
+                });
+            }
+        }
+
+        Ok(Self { files })
+    }
+
+    /// Returns true if the search path has no module files
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+
+    /// Finds and parses a module or submodule, see "find" for how the file is chosen
+    ///
+    /// Returns None if the module isn't found, or its file can't be read or parsed.
+    ///
+    pub fn load(&self, name: &str, revision: Option<&str>) -> Option<&Module> {
+        self.find_file(name, revision).and_then(ModuleFile::module)
+    }
+
+    /// Returns the path of the file with the module or submodule
+    ///
+    /// The first directory of the search path with a file of the module wins. With a revision,
+    /// only the file named with that revision is used, or a file named without a revision whose
+    /// latest revision is the one asked for. Without, the file named with the latest revision is
+    /// used, or the one without a revision if there are none.
+    ///
+    pub fn find(&self, name: &str, revision: Option<&str>) -> Option<&Path> {
+        self.find_file(name, revision)
+            .map(|file| file.path.as_path())
+    }
+
+    fn find_file(&self, name: &str, revision: Option<&str>) -> Option<&ModuleFile> {
+        let directory = self.files.iter().find(|file| file.name == name)?.directory;
+
+        let mut candidates = self
+            .files
+            .iter()
+            .filter(|file| file.directory == directory && file.name == name);
+
+        match revision {
+            Some(revision) => candidates.find(|file| match file.revision {
+                Some(ref file_revision) => file_revision == revision,
+                None => file.module().and_then(Module::revision) == Some(revision),
+            }),
+            None => candidates.max_by_key(|file| file.revision.as_deref()),
+        }
+    }
+}
+
+impl ModuleFile {
+    /// Reads and parses the file the first time it's called
+    fn module(&self) -> Option<&Module> {
+        self.module
+            .get_or_init(|| {
+                let buffer = std::fs::read(&self.path).ok()?;
+
+                Module::new(&self.path, &buffer).ok()
+            })
+            .as_ref()
+    }
+}
+
+/// Returns the module name and revision in the name of a ".yang" file
+fn parse_file_name(file_name: &str) -> Option<(&str, Option<&str>)> {
+    let stem = file_name.strip_suffix(".yang")?;
+
+    match stem.split_once('@') {
+        Some((name, revision)) => Some((name, Some(revision))),
+        None => Some((stem, None)),
+    }
+}
+
+/// Returns the argument of a statement without quotes, or an empty string if it has none
+fn argument(statement: &Statement) -> &str {
+    match statement.value {
+        Some(
+            NodeValue::Date(ref text)
+            | NodeValue::Number(ref text)
+            | NodeValue::String(ref text)
+            | NodeValue::Other(ref text),
+        ) => text.trim_matches(['"', '\'']),
+        _ => "",
+    }
+}
+
+fn children<'a>(statement: &'a Statement, keyword: &'a str) -> impl Iterator<Item = &'a Statement> {
+    statement
+        .children
+        .iter()
+        .flatten()
+        .filter_map(move |node| match node {
+            Node::Statement(stmt) if stmt.keyword.text() == keyword => Some(stmt),
+            _ => None,
+        })
+}
+
+fn find_child<'a>(statement: &'a Statement, keyword: &str) -> Option<&'a Statement> {
+    statement
+        .children
+        .iter()
+        .flatten()
+        .find_map(|node| match node {
+            Node::Statement(stmt) if stmt.keyword.text() == keyword => Some(stmt),
+            _ => None,
+        })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    /// Creates a directory in the temporary directory with the given files
+    fn directory(name: &str, files: &[(&str, &str)]) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("yangfmt-{name}-{}", std::process::id()));
+
+        let _ = std::fs::remove_dir_all(&path);
+        std::fs::create_dir_all(&path).unwrap();
+
+        for (file_name, contents) in files {
+            std::fs::write(path.join(file_name), contents).unwrap();
+        }
+
+        path
+    }
+
+    #[test]
+    fn test_module() {
+        let input = "submodule foo-types {\n  belongs-to foo { prefix f; }\n  \
+                     import ietf-inet-types { prefix inet; revision-date 2013-07-15; }\n  \
+                     include foo-common;\n  revision 2023-01-01;\n  revision 2024-01-01;\n}\n";
+        let module = Module::new("foo-types.yang", input.as_bytes()).unwrap();
+
+        assert_eq!("foo-types", module.name());
+        assert!(module.is_submodule());
+        assert_eq!(Some("f"), module.prefix());
+        assert_eq!(Some("2024-01-01"), module.revision());
+        assert_eq!(
+            vec![
+                Dependency {
+                    keyword: "import",
+                    module: "ietf-inet-types",
+                    prefix: Some("inet"),
+                    revision: Some("2013-07-15"),
+                    position: input.find("import").unwrap(),
+                },
+                Dependency {
+                    keyword: "include",
+                    module: "foo-common",
+                    prefix: None,
+                    revision: None,
+                    position: input.find("include").unwrap(),
+                },
+            ],
+            module.dependencies(),
+        );
+    }
+
+    #[test]
+    fn test_registry() {
+        let first = directory(
+            "registry-first",
+            &[
+                ("foo@2023-01-01.yang", "module foo { revision 2023-01-01; }"),
+                ("foo@2024-01-01.yang", "module foo { revision 2024-01-01; }"),
+                ("broken.yang", "module broken {"),
+                ("notes.txt", "Not a module"),
+            ],
+        );
+        let second = directory(
+            "registry-second",
+            &[
+                ("foo.yang", "module foo;"),
+                ("baz.yang", "module baz { revision 2022-01-01; }"),
+                ("bar.yang", "module bar { prefix b; }"),
+            ],
+        );
+
+        let registry = Registry::new(&[first.clone(), second.clone()]).unwrap();
+
+        assert_eq!(
+            Some(first.join("foo@2024-01-01.yang").as_path()),
+            registry.find("foo", None)
+        );
+        assert_eq!(
+            Some(first.join("foo@2023-01-01.yang").as_path()),
+            registry.find("foo", Some("2023-01-01"))
+        );
+        assert_eq!(None, registry.find("foo", Some("2022-01-01")));
+        assert_eq!(None, registry.find("notes", None));
+        assert_eq!(Some("b"), registry.load("bar", None).unwrap().prefix());
+        assert!(registry.find("broken", None).is_some());
+        assert!(registry.load("broken", None).is_none());
+        assert!(registry.load("baz", Some("2022-01-01")).is_some());
+        assert!(registry.load("baz", Some("2021-01-01")).is_none());
+        assert!(registry.load("qux", None).is_none());
+
+        assert!(Registry::new(&[first.join("missing")]).is_err());
+
+        std::fs::remove_dir_all(first).unwrap();
+        std::fs::remove_dir_all(second).unwrap();
+    }
+}
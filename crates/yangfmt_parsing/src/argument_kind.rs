@@ -0,0 +1,143 @@
+// Classifies statement arguments by the grammar rule the ABNF uses for them
+
+use crate::StatementKeyword;
+
+/// The kind of argument a statement takes
+///
+/// The parser only distinguishes values by their lexical shape (see NodeValue), which tells us
+/// nothing about what the value means. This classification is based on the statement keyword
+/// instead, following the "*-arg-str" rules in the ABNF.
+///
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ArgumentKind {
+    /// The name of a new definition, e.g. "container foo"
+    Identifier,
+    /// A reference to another definition, possibly prefixed, e.g. "type inet:ip-address"
+    IdentifierRef,
+    /// Space separated node names, e.g. "key" and "unique"
+    IdentifierList,
+    /// Absolute or descendant schema node identifier, e.g. "augment /foo:bar"
+    SchemaNodeId,
+    /// XPath expression, e.g. "when" and "must"
+    XPath,
+    /// Leafref path expression
+    Path,
+    /// Boolean expression of feature names
+    IfFeatureExpr,
+    Boolean,
+    Date,
+    /// Integer or decimal number, including "unbounded" for max-elements
+    Number,
+    /// One of a fixed set of keywords, e.g. "status current" or "ordered-by user"
+    Keyword,
+    /// Free-form text, e.g. "description"
+    Text,
+    /// The statement doesn't take an argument, e.g. "input"
+    NoArgument,
+    /// Extension or invalid statement, the argument could be anything
+    Unknown,
+}
+
+/// Returns the kind of argument the given statement keyword takes
+pub fn argument_kind(keyword: &str) -> ArgumentKind {
+    match keyword {
+        "action" | "anydata" | "anyxml" | "argument" | "bit" | "case" | "choice" | "container"
+        | "extension" | "feature" | "grouping" | "identity" | "import" | "include" | "leaf"
+        | "leaf-list" | "list" | "module" | "notification" | "prefix" | "rpc" | "submodule"
+        | "typedef" | "belongs-to" => ArgumentKind::Identifier,
+
+        "base" | "type" | "uses" => ArgumentKind::IdentifierRef,
+
+        "key" | "unique" => ArgumentKind::IdentifierList,
+
+        "augment" | "deviation" | "refine" => ArgumentKind::SchemaNodeId,
+
+        "when" | "must" => ArgumentKind::XPath,
+
+        "path" => ArgumentKind::Path,
+
+        "if-feature" => ArgumentKind::IfFeatureExpr,
+
+        "config" | "mandatory" | "require-instance" | "yin-element" => ArgumentKind::Boolean,
+
+        "revision" | "revision-date" => ArgumentKind::Date,
+
+        "fraction-digits" | "max-elements" | "min-elements" | "position" | "value" => {
+            ArgumentKind::Number
+        }
+
+        "deviate" | "modifier" | "ordered-by" | "status" | "yang-version" => ArgumentKind::Keyword,
+
+        "contact" | "default" | "description" | "enum" | "error-app-tag" | "error-message"
+        | "length" | "namespace" | "organization" | "pattern" | "presence" | "range"
+        | "reference" | "units" => ArgumentKind::Text,
+
+        "input" | "output" => ArgumentKind::NoArgument,
+
+        _ => ArgumentKind::Unknown,
+    }
+}
+
+impl std::str::FromStr for ArgumentKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "identifier" => Ok(Self::Identifier),
+            "identifier-ref" => Ok(Self::IdentifierRef),
+            "identifier-list" => Ok(Self::IdentifierList),
+            "schema-node-id" => Ok(Self::SchemaNodeId),
+            "xpath" => Ok(Self::XPath),
+            "path" => Ok(Self::Path),
+            "if-feature-expr" => Ok(Self::IfFeatureExpr),
+            "boolean" => Ok(Self::Boolean),
+            "date" => Ok(Self::Date),
+            "number" => Ok(Self::Number),
+            "keyword" => Ok(Self::Keyword),
+            "text" => Ok(Self::Text),
+            "no-argument" => Ok(Self::NoArgument),
+            "unknown" => Ok(Self::Unknown),
+            _ => Err(format!(
+                "invalid argument kind {s:?}, expected identifier, identifier-ref, \
+                 identifier-list, schema-node-id, xpath, path, if-feature-expr, boolean, date, \
+                 number, keyword, text, no-argument or unknown"
+            )),
+        }
+    }
+}
+
+impl StatementKeyword {
+    /// Returns the kind of argument this statement takes
+    pub fn argument_kind(&self) -> ArgumentKind {
+        match self {
+            StatementKeyword::Keyword(text) => argument_kind(text),
+            StatementKeyword::ExtensionKeyword(_) | StatementKeyword::Invalid(_) => {
+                ArgumentKind::Unknown
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn every_keyword_is_classified() {
+        for keyword in crate::constants::STATEMENT_KEYWORDS {
+            assert_ne!(
+                ArgumentKind::Unknown,
+                argument_kind(keyword),
+                "{keyword} is not classified"
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_argument_kind() {
+        assert_eq!(Ok(ArgumentKind::Identifier), "identifier".parse());
+        assert_eq!(Ok(ArgumentKind::IfFeatureExpr), "if-feature-expr".parse());
+        assert_eq!(Ok(ArgumentKind::NoArgument), "no-argument".parse());
+        assert!("Identifier".parse::<ArgumentKind>().is_err());
+    }
+}
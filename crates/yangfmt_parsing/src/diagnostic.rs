@@ -0,0 +1,49 @@
+// Contains the type used for reporting problems that don't prevent the input from being processed
+
+/// A problem found in the input, such as a suspicious character or an invalid value
+///
+/// Unlike a ParseError, a diagnostic doesn't stop the input from being formatted.
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostic {
+    pub message: String,
+    /// Byte offset of the problem in the input buffer
+    pub position: usize,
+    /// Name of the kind of problem, which "yangfmt explain" can tell more about
+    pub code: Option<&'static str>,
+    /// Change to the input that solves the problem, if it can be fixed automatically
+    pub fix: Option<Fix>,
+}
+
+/// Replacement of the bytes from "start" up to, but not including, "end" of the input buffer
+#[derive(Debug, Clone, PartialEq)]
+pub struct Fix {
+    pub start: usize,
+    pub end: usize,
+    pub replacement: String,
+}
+
+impl Diagnostic {
+    pub fn new(message: impl Into<String>, position: usize) -> Self {
+        Self {
+            message: message.into(),
+            position,
+            code: None,
+            fix: None,
+        }
+    }
+
+    pub fn with_code(self, code: &'static str) -> Self {
+        Self {
+            code: Some(code),
+            ..self
+        }
+    }
+
+    pub fn with_fix(self, fix: Fix) -> Self {
+        Self {
+            fix: Some(fix),
+            ..self
+        }
+    }
+}
@@ -16,7 +16,11 @@ lazy_static! {
 pub enum Node {
     Statement(Statement),
     EmptyLine(String),
+    /// The continuation lines of multi-line comments are stored relative to the column the
+    /// comment starts at
     Comment(String),
+    /// Source text that is written exactly as is, see "yangfmt: off" in "parse"
+    Raw(String),
 }
 
 pub trait NodeHelpers {
@@ -79,10 +83,10 @@ impl NodeHelpers for Node {
 
 impl NodeHelpers for Option<&Node> {
     fn is_empty_line(&self) -> bool {
-        self.map_or(false, |node| node.is_empty_line())
+        self.is_some_and(|node| node.is_empty_line())
     }
     fn is_comment(&self) -> bool {
-        self.map_or(false, |node| node.is_comment())
+        self.is_some_and(|node| node.is_comment())
     }
     fn node_value(&self) -> Option<&NodeValue> {
         match self {
@@ -103,7 +107,7 @@ pub struct RootNode {
     pub children: Vec<Node>,
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug)]
 pub struct Statement {
     pub keyword: StatementKeyword,
     /// Comment(s) between the statement keyword and value
@@ -115,7 +119,26 @@ pub struct Statement {
     /// Any comments after the statement, but on the same line. For single-line statements, this is
     /// any comments after the semicolon. For block statements, this is any comment after the
     /// opening brace, on the same line.
+    ///
+    /// Like with comment nodes, the continuation lines of multi-line comments on the statement's
+    /// line are stored relative to the indentation of the statement.
     pub post_comments: Vec<String>,
+    /// Byte offset of the keyword in the input buffer
+    pub position: usize,
+    /// Byte offset of the value in the input buffer, if any
+    pub value_position: Option<usize>,
+}
+
+/// Statements are compared by content, where they were found in the input doesn't matter
+impl PartialEq for Statement {
+    fn eq(&self, other: &Self) -> bool {
+        self.keyword == other.keyword
+            && self.keyword_comments == other.keyword_comments
+            && self.value == other.value
+            && self.value_comments == other.value_comments
+            && self.children == other.children
+            && self.post_comments == other.post_comments
+    }
 }
 
 impl Statement {
@@ -127,6 +150,8 @@ impl Statement {
             value_comments: vec![],
             children: None,
             post_comments: vec![],
+            position: 0,
+            value_position: None,
         }
     }
 
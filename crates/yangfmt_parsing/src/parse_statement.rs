@@ -113,13 +113,12 @@ pub fn parse_statement(
 ) -> Result<(crate::Statement, bool), crate::ParseError> {
     let mut state = ParseState::new();
     let mut last_position: Option<usize> = None;
+    let mut keyword_position = 0;
+    let mut value_position: Option<usize> = None;
 
     // This loop parses the statement itself
     for token in token_stream.by_ref() {
-        let token = match (token) {
-            Ok(token) => token,
-            Err(err) => return Err(err.into()),
-        };
+        let token = token?;
 
         last_position = Some(token.span.0);
 
@@ -134,6 +133,7 @@ pub fn parse_statement(
         match state {
             ParseState::Clean => match token.token_type {
                 TokenType::Other => {
+                    keyword_position = token.span.0;
                     state = ParseState::got_keyword(token.text.into());
                 }
                 _ => {
@@ -166,6 +166,7 @@ pub fn parse_statement(
 
                 // Anything that isn't whitespace or a comment becomes the statement value
                 _ => {
+                    value_position = Some(token.span.0);
                     state = ParseState::got_value(keyword, keyword_comments, token.into());
                 }
             },
@@ -360,6 +361,8 @@ pub fn parse_statement(
                     value_comments,
                     children: None,
                     post_comments,
+                    position: keyword_position,
+                    value_position,
                 },
                 opens_block,
             ))
@@ -399,6 +402,18 @@ mod test {
         assert_eq!(opens_block, false);
     }
 
+    #[test]
+    fn parse_positions() {
+        let (statement, _) = test_parse_statement!("foo  \"bar\";").unwrap();
+
+        assert_eq!(0, statement.position);
+        assert_eq!(Some(5), statement.value_position);
+
+        let (statement, _) = test_parse_statement!("foo;").unwrap();
+
+        assert_eq!(None, statement.value_position);
+    }
+
     #[test]
     fn parse_keyword_and_value() {
         let (statement, opens_block) = test_parse_statement!("foo 123;").unwrap();
@@ -3,15 +3,41 @@
 #[macro_use]
 extern crate lazy_static;
 
+mod argument_kind;
 mod constants;
+mod diagnostic;
 mod node;
 mod parse_statement;
 mod parsing_dbg;
 
+pub use crate::argument_kind::{argument_kind, ArgumentKind};
+pub use crate::constants::STATEMENT_KEYWORDS;
+pub use crate::diagnostic::{Diagnostic, Fix};
 pub use crate::node::{Node, NodeHelpers, NodeValue, RootNode, Statement, StatementKeyword};
 use crate::parse_statement::parse_statement;
+use std::iter::Peekable;
 use yangfmt_lexing::{LexerError, Token, TokenType};
 
+/// Comment starting a region that is written exactly as it appears in the source
+const FORMAT_OFF_MARKER: &str = "yangfmt: off";
+
+/// Comment ending a region started by FORMAT_OFF_MARKER
+const FORMAT_ON_MARKER: &str = "yangfmt: on";
+
+/// Comment on the line above a statement, or after it on the same line, that makes the statement
+/// and its whole subtree be written exactly as it appears in the source
+const SKIP_MARKER: &str = "yangfmt: skip";
+
+/// A "yangfmt: off" region or "yangfmt: skip" statement that hasn't been closed yet
+struct OffRegion {
+    /// The depth of the node stack when the region started
+    depth: usize,
+    /// Number of nodes in the block when the region started
+    first_node: usize,
+    /// Byte offset of the start of the first line of the region
+    start: usize,
+}
+
 #[derive(Debug)]
 pub struct ParseError {
     pub message: String,
@@ -42,6 +68,14 @@ where
 /// invalid YANG. For example, this function will parse a document with multiple module blocks just
 /// fine, or no module node at all, just a bunch of leafs.
 ///
+/// The statements between a "// yangfmt: off" comment and a "// yangfmt: on" comment in the same
+/// block are parsed as usual, but then replaced by a single raw node containing their source text.
+/// The region is ended by the end of the block if there's no "yangfmt: on" comment.
+///
+/// Similarly, a statement with a "// yangfmt: skip" comment on the line above it or after its
+/// semicolon or curly braces is replaced by a raw node containing the source lines of the statement and its
+/// whole subtree.
+///
 pub fn parse(buffer: &[u8]) -> Result<RootNode, ParseError> {
     let mut tokens = yangfmt_lexing::scan_iter(buffer);
     let mut token_stream = tokens.peekable();
@@ -49,6 +83,10 @@ pub fn parse(buffer: &[u8]) -> Result<RootNode, ParseError> {
     let mut node_stack: Vec<Vec<Node>> = vec![vec![]];
     let mut prev_token_was_line_break = false;
     let mut prev_token_pos = 0;
+    let mut off_region: Option<OffRegion> = None;
+    let mut skipped: Option<OffRegion> = None;
+    // Start of the first line of each open block statement, if it starts its own line
+    let mut block_starts: Vec<Option<usize>> = vec![];
 
     loop {
         let next_token = match token_stream.peek() {
@@ -61,6 +99,7 @@ pub fn parse(buffer: &[u8]) -> Result<RootNode, ParseError> {
         let is_line_break = matches!(next_token.token_type, TokenType::LineBreak);
         let is_whitespace = matches!(next_token.token_type, TokenType::WhiteSpace);
 
+        let depth = node_stack.len();
         let mut nodes = node_stack.last_mut().expect("Stack should never be empty");
 
         match next_token.token_type {
@@ -77,11 +116,50 @@ pub fn parse(buffer: &[u8]) -> Result<RootNode, ParseError> {
             }
 
             TokenType::Comment => {
-                nodes.push(Node::Comment(next_token.text.to_string()));
+                let column = buffer[..token_pos]
+                    .iter()
+                    .rev()
+                    .take_while(|c| **c != b'\n')
+                    .count();
+
+                nodes.push(Node::Comment(dedent_comment(next_token.text, column)));
+
+                if is_pragma_comment(next_token.text, FORMAT_OFF_MARKER) && off_region.is_none() {
+                    off_region = Some(OffRegion {
+                        depth,
+                        first_node: nodes.len(),
+                        start: next_line_start(buffer, next_token.span.1),
+                    });
+                } else if is_pragma_comment(next_token.text, FORMAT_ON_MARKER)
+                    && off_region
+                        .as_ref()
+                        .is_some_and(|region| region.depth == depth)
+                {
+                    let region = off_region.take().unwrap();
+                    let end = line_start(buffer, token_pos);
+                    let on_comment = nodes.pop().unwrap();
+
+                    replace_with_raw_node(nodes, &region, &buffer[region.start..end]);
+                    nodes.push(on_comment);
+                }
+
                 token_stream.next();
             }
 
             TokenType::ClosingCurlyBrace => {
+                if off_region
+                    .as_ref()
+                    .is_some_and(|region| region.depth == depth)
+                {
+                    let region = off_region.take().unwrap();
+                    let end = token_pos.max(region.start);
+
+                    replace_with_raw_node(nodes, &region, &buffer[region.start..end]);
+                }
+
+                let skip_ends = skipped.as_ref().is_some_and(|region| region.depth == depth);
+                let block_start = block_starts.pop().flatten();
+
                 let nodes = node_stack.pop().expect("Node stack can never be empty");
 
                 let prev_nodes = match node_stack.last_mut() {
@@ -102,15 +180,93 @@ pub fn parse(buffer: &[u8]) -> Result<RootNode, ParseError> {
                 }
 
                 token_stream.next();
+
+                while matches!(token_stream.peek(), Some(Ok(token)) if token.is_whitespace()) {
+                    token_stream.next();
+                }
+
+                // A "yangfmt: skip" comment after the closing curly brace skips the whole block
+                let comment_after = matches!(
+                    token_stream.peek(),
+                    Some(Ok(token)) if token.token_type == TokenType::Comment
+                        && is_pragma_comment(token.text, SKIP_MARKER)
+                );
+
+                let region = if skip_ends {
+                    skipped.take()
+                } else if comment_after && off_region.is_none() && skipped.is_none() {
+                    block_start.map(|start| OffRegion {
+                        depth,
+                        first_node: prev_nodes.len() - 1,
+                        start,
+                    })
+                } else {
+                    None
+                };
+
+                if let Some(region) = region {
+                    let end = skipped_statement_end(&mut token_stream, buffer.len());
+
+                    replace_with_raw_node(prev_nodes, &region, &buffer[region.start..end]);
+                }
             }
 
             _ => {
-                let (statement, opens_block) = parse_statement(&mut token_stream)?;
+                // Only statements starting their own line can be skipped, otherwise the raw text
+                // would include whatever comes before them
+                let start = line_start(buffer, token_pos);
+                let own_line = buffer[start..token_pos]
+                    .iter()
+                    .all(|c| *c == b' ' || *c == b'\t');
+                let can_skip = own_line && off_region.is_none() && skipped.is_none();
+                let comment_above = matches!(nodes.last(), Some(Node::Comment(text)) if is_pragma_comment(text, SKIP_MARKER));
+
+                let (mut statement, opens_block) = parse_statement(&mut token_stream)?;
+
+                // Comments on the statement's lines are made relative to its indentation, so
+                // multi-line block comments can be re-indented along with it
+                let indentation = buffer[start..token_pos]
+                    .iter()
+                    .take_while(|c| **c == b' ' || **c == b'\t')
+                    .count();
+
+                for comment in statement
+                    .keyword_comments
+                    .iter_mut()
+                    .chain(&mut statement.value_comments)
+                    .chain(&mut statement.post_comments)
+                {
+                    *comment = dedent_comment(comment, indentation);
+                }
+
+                let skip = can_skip
+                    && (comment_above
+                        || statement
+                            .post_comments
+                            .iter()
+                            .any(|text| is_pragma_comment(text, SKIP_MARKER)));
 
                 nodes.push(Node::Statement(statement));
 
+                if skip {
+                    let region = OffRegion {
+                        depth: depth + 1,
+                        first_node: nodes.len() - 1,
+                        start,
+                    };
+
+                    if opens_block {
+                        skipped = Some(region);
+                    } else {
+                        let end = skipped_statement_end(&mut token_stream, buffer.len());
+
+                        replace_with_raw_node(nodes, &region, &buffer[start..end]);
+                    }
+                }
+
                 if opens_block {
                     node_stack.push(vec![]);
+                    block_starts.push(own_line.then_some(start));
                 }
             }
         };
@@ -131,11 +287,115 @@ pub fn parse(buffer: &[u8]) -> Result<RootNode, ParseError> {
         });
     }
 
-    Ok(RootNode {
-        children: node_stack
-            .pop()
-            .expect("Should be one node list in node stack after parsing is done"),
-    })
+    let mut children = node_stack
+        .pop()
+        .expect("Should be one node list in node stack after parsing is done");
+
+    if let Some(region) = off_region {
+        replace_with_raw_node(&mut children, &region, &buffer[region.start..]);
+    }
+
+    Ok(RootNode { children })
+}
+
+/// Replaces the nodes parsed since the start of the "yangfmt: off" region with a raw node
+///
+/// The raw text always ends with a line break, so whatever comes after it starts on a new line.
+///
+fn replace_with_raw_node(nodes: &mut Vec<Node>, region: &OffRegion, text: &[u8]) {
+    let mut text = String::from_utf8_lossy(text).into_owned();
+
+    if !text.ends_with('\n') {
+        text.truncate(text.trim_end().len());
+
+        if text.is_empty() {
+            nodes.truncate(region.first_node);
+            return;
+        }
+
+        text.push('\n');
+    }
+
+    nodes.truncate(region.first_node);
+    nodes.push(Node::Raw(text));
+}
+
+/// Checks if the text of a comment, without the comment markers, is exactly the given pragma
+///
+/// Comments mentioning a pragma in prose, like "// Wrap in yangfmt: off to keep the layout",
+/// aren't pragmas, and neither are longer pragmas like "yangfmt: skip-file" when looking for
+/// "yangfmt: skip".
+///
+pub fn is_pragma_comment(comment: &str, pragma: &str) -> bool {
+    let text = match comment.strip_prefix("/*") {
+        Some(text) => text.strip_suffix("*/").unwrap_or(text),
+        None => comment.strip_prefix("//").unwrap_or(comment),
+    };
+
+    text.trim() == pragma
+}
+
+/// Consumes the rest of the line after a skipped statement and returns the byte offset the raw
+/// text of the statement ends at
+///
+/// Comments after the statement on the same line are included in the raw text. If there's
+/// another statement on the same line, the raw text ends right before it.
+///
+fn skipped_statement_end(
+    token_stream: &mut Peekable<yangfmt_lexing::ScanIterator>,
+    buffer_len: usize,
+) -> usize {
+    while let Some(Ok(token)) = token_stream.peek() {
+        match token.token_type {
+            TokenType::WhiteSpace | TokenType::Comment => {
+                token_stream.next();
+            }
+            TokenType::LineBreak => return token.span.1,
+            _ => return token.span.0,
+        }
+    }
+
+    buffer_len
+}
+
+/// Returns the byte offset of the start of the line containing the given position
+fn line_start(buffer: &[u8], pos: usize) -> usize {
+    buffer[..pos]
+        .iter()
+        .rposition(|c| *c == b'\n')
+        .map_or(0, |index| index + 1)
+}
+
+/// Returns the byte offset of the start of the line after the given position
+fn next_line_start(buffer: &[u8], pos: usize) -> usize {
+    buffer[pos..]
+        .iter()
+        .position(|c| *c == b'\n')
+        .map_or(buffer.len(), |index| pos + index + 1)
+}
+
+/// Strips the given number of columns of leading whitespace from every line of a multi-line
+/// comment except the first
+///
+/// This makes the comment text relative to the column the comment starts at, so it can be
+/// re-indented along with the rest of the file.
+///
+fn dedent_comment(text: &str, column: usize) -> String {
+    let mut lines = text.split('\n');
+    let mut output = lines.next().unwrap_or_default().to_string();
+
+    for line in lines {
+        let indent = line
+            .chars()
+            .take(column)
+            .take_while(|c| *c == ' ' || *c == '\t')
+            .count();
+
+        output.push('\n');
+        output.push_str(&line[indent..]);
+    }
+
+    output
 }
 
 #[cfg(test)]
@@ -223,4 +483,79 @@ mod test {
           (comment))
         "#
     );
+
+    test_parse!(
+        format_off_regions,
+        // Input
+        r#"
+        module foo {
+            // yangfmt: off
+            leaf bar;
+            leaf baz;
+            // yangfmt: on
+            leaf qux;
+        }
+        "#,
+        // Expected output
+        r#"
+        (root
+          (Keyword "module" Other
+            (comment)
+            (raw)
+            (comment)
+            (Keyword "leaf" Other)))
+        "#
+    );
+
+    test_parse!(
+        format_off_markers_in_prose,
+        // Input
+        r#"
+        module foo {
+            // Wrap tables in yangfmt: off to keep them aligned
+            leaf bar;
+            /* yangfmt: off */
+            leaf baz;
+            /* yangfmt: on */
+        }
+        "#,
+        // Expected output
+        r#"
+        (root
+          (Keyword "module" Other
+            (comment)
+            (Keyword "leaf" Other)
+            (comment)
+            (raw)
+            (comment)))
+        "#
+    );
+
+    test_parse!(
+        skipped_statements,
+        // Input
+        r#"
+        module foo {
+            // yangfmt: skip
+            leaf bar {
+                type string;
+            } // end of bar
+            leaf baz;  // yangfmt: skip
+            leaf qux; // yangfmt: skip-file
+            // Don't run yangfmt: skip on this one
+            leaf quux;
+        }
+        "#,
+        // Expected output
+        r#"
+        (root
+          (Keyword "module" Other
+            (comment)
+            (raw)
+            (raw)
+            (Keyword "leaf" Other <post-comment>)
+            (comment)
+            (Keyword "leaf" Other)))
+        "#
+    );
 }
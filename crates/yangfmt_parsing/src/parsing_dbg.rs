@@ -60,6 +60,9 @@ fn format_node(out: &mut Formatter, node: &Node, depth: u8) -> Result<(), fmt::E
         Node::Comment(_) => {
             write!(out, "(comment)")?;
         }
+        Node::Raw(_) => {
+            write!(out, "(raw)")?;
+        }
     }
 
     Ok(())